@@ -4,7 +4,8 @@ mod utils;
 mod types;
 
 use std::error::Error;
-use ui::menus::main_menu;
+use memory::error::describe_error;
+use ui::menus::{load_cheat_table, main_menu, Target};
 use utils::system::check_root;
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -12,25 +13,29 @@ fn main() -> Result<(), Box<dyn Error>> {
     if !check_root() {
         ui::display::print_header();
         println!("\n⚠️  WARNING: Not running as root. Some operations may fail.");
+        println!("   Use \"Permission diagnostics\" to see what blocks access to a process.");
         utils::input::get_input("Press Enter to continue anyway...");
     }
 
     loop {
         match ui::menus::get_process() {
-            Ok(pid) => {
-                println!("🔗 Attaching to PID: {}...", pid);
-                match memory::process::ProcessMemory::new(pid) {
-                    Ok(mut process_mem) => {
-                        println!("✅ Successfully attached!");
+            Ok(Target::Saved(path)) => {
+                println!("📂 Opening {}...", path);
+                match memory::source::open_saved(&path) {
+                    Ok(saved) => {
+                        let mut process_mem = memory::process::ProcessMemory::open_saved(saved);
+                        println!("✅ Loaded {} regions for offline scanning", process_mem.regions.len());
                         utils::input::get_input("Press Enter to continue...");
                         main_menu(&mut process_mem)?;
                     }
                     Err(e) => {
-                        println!("❌ Failed to attach to process: {}", e);
-                        utils::input::get_input("Press Enter to try another process...");
+                        println!("❌ Failed to open saved memory: {}", e);
+                        utils::input::get_input("Press Enter to try again...");
                     }
                 }
             }
+            Ok(Target::Pid(pid)) => attach(pid, None)?,
+            Ok(Target::Waited { pid, table }) => attach(pid, table.as_deref())?,
             Err(e) => {
                 println!("❌ Error selecting process: {}", e);
                 utils::input::get_input("Press Enter to try again...");
@@ -38,3 +43,22 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
     }
 }
+
+fn attach(pid: u32, table: Option<&str>) -> Result<(), Box<dyn Error>> {
+    println!("🔗 Attaching to PID: {}...", pid);
+    match memory::process::ProcessMemory::new(pid) {
+        Ok(mut process_mem) => {
+            println!("✅ Successfully attached!");
+            if let Some(path) = table {
+                load_cheat_table(&mut process_mem, path);
+            }
+            utils::input::get_input("Press Enter to continue...");
+            main_menu(&mut process_mem)
+        }
+        Err(e) => {
+            println!("❌ Failed to attach to process: {}", describe_error(&e));
+            utils::input::get_input("Press Enter to try another process...");
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use super::error::MemoryError;
+use super::process::ProcessMemory;
+use super::ptrace::{set_regset, GP_REGS_SIZE, NT_PRSTATUS};
+use super::registers::{parse_integer, Registers, PC_INDEX};
+use std::fmt;
+use std::panic;
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+#[cfg(target_arch = "x86_64")]
+pub const TRAP_INSTRUCTION: &[u8] = &[0xcc]; // int3
+#[cfg(target_arch = "x86_64")]
+const TRAP_PC_ADJUST: u64 = 1; // Tras int3 el PC apunta al byte siguiente
+#[cfg(target_arch = "aarch64")]
+pub const TRAP_INSTRUCTION: &[u8] = &[0x00, 0x00, 0x20, 0xd4]; // brk #0
+#[cfg(target_arch = "aarch64")]
+const TRAP_PC_ADJUST: u64 = 0;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub const TRAP_INSTRUCTION: &[u8] = &[];
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const TRAP_PC_ADJUST: u64 = 0;
+
+const MAX_LOG: usize = 100_000; // Entradas del registro de disparos en memoria
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    const ALL: [(&'static str, CmpOp); 6] = [
+        ("==", CmpOp::Eq),
+        ("!=", CmpOp::Ne),
+        ("<=", CmpOp::Le),
+        (">=", CmpOp::Ge),
+        ("<", CmpOp::Lt),
+        (">", CmpOp::Gt),
+    ];
+
+    fn symbol(&self) -> &'static str {
+        CmpOp::ALL.iter().find(|(_, op)| op == self).map_or("?", |(symbol, _)| symbol)
+    }
+}
+
+/// Condición sobre un registro, como "rdi == 5" o "x0 > 0x10"; sin ella se registra todo disparo
+#[derive(Debug, Clone)]
+pub struct Condition {
+    register: String,
+    op: CmpOp,
+    value: u64,
+}
+
+impl Condition {
+    pub fn parse(text: &str) -> Result<Condition, String> {
+        let (symbol, op) = CmpOp::ALL
+            .iter()
+            .find(|(symbol, _)| text.contains(symbol))
+            .ok_or_else(|| format!("Missing comparison (==, !=, <, <=, >, >=) in '{}'", text))?;
+        let (register, value) = text.split_once(symbol).unwrap();
+        let register = register.trim().to_ascii_lowercase();
+        if !Registers::is_general(&register) {
+            return Err(format!("Unknown register: {}", register));
+        }
+        let value = parse_integer(value.trim()).ok_or_else(|| format!("Invalid value: {}", value.trim()))?;
+        Ok(Condition {
+            register,
+            op: *op,
+            value: value as u64,
+        })
+    }
+
+    pub fn matches(&self, registers: &Registers) -> bool {
+        let Some(current) = registers.get(&self.register) else {
+            return false;
+        };
+        match self.op {
+            CmpOp::Eq => current == self.value,
+            CmpOp::Ne => current != self.value,
+            CmpOp::Lt => current < self.value,
+            CmpOp::Le => current <= self.value,
+            CmpOp::Gt => current > self.value,
+            CmpOp::Ge => current >= self.value,
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} 0x{:x}", self.register, self.op.symbol(), self.value)
+    }
+}
+
+/// Punto de ruptura definido por el usuario; solo se escribe en memoria mientras se vigila
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    pub address: u64,
+    pub condition: Option<Condition>,
+    pub hits: u64,
+}
+
+/// Un disparo que cumplió la condición, con los registros generales en ese momento
+#[derive(Debug, Clone)]
+pub struct BreakHit {
+    pub tid: i32,
+    pub address: u64,
+    pub elapsed: Duration,
+    pub registers: Vec<(&'static str, u64)>,
+}
+
+/// Puntos de ruptura escritos en la memoria del proceso. Los bytes originales se guardan
+/// también en un registro global para restaurarlos si la herramienta cae (panic o señal).
+pub struct ArmedBreakpoints {
+    pid: u32,
+    armed: Vec<(u64, Vec<u8>)>,
+    started: Instant,
+    pub log: Vec<BreakHit>,
+    pub skipped: u64, // Disparos que no cumplieron la condición
+}
+
+impl ArmedBreakpoints {
+    /// Escribe la instrucción de trap en cada dirección (con `write_memory`, que puede
+    /// escribir en páginas de código de solo lectura). El proceso debe estar en pausa.
+    pub fn arm(process: &mut ProcessMemory, breakpoints: &[Breakpoint]) -> Result<ArmedBreakpoints, MemoryError> {
+        install_crash_hooks();
+        let mut armed = ArmedBreakpoints {
+            pid: process.pid,
+            armed: Vec::new(),
+            started: Instant::now(),
+            log: Vec::new(),
+            skipped: 0,
+        };
+        for breakpoint in breakpoints {
+            let result = process
+                .read_memory(breakpoint.address, TRAP_INSTRUCTION.len())
+                .and_then(|original| {
+                    register(process.pid, breakpoint.address, &original);
+                    armed.armed.push((breakpoint.address, original));
+                    process.write_memory(breakpoint.address, TRAP_INSTRUCTION)
+                });
+            if let Err(e) = result {
+                armed.disarm(process);
+                return Err(e);
+            }
+        }
+        Ok(armed)
+    }
+
+    fn original(&self, address: u64) -> Option<&[u8]> {
+        self.armed.iter().find(|(a, _)| *a == address).map(|(_, original)| original.as_slice())
+    }
+
+    /// Atiende el SIGTRAP de un hilo. Si es de uno de nuestros puntos, registra el disparo
+    /// (si cumple la condición), ejecuta la instrucción original paso a paso y continúa.
+    /// Devuelve false si el trap no era nuestro (el hilo sigue detenido).
+    pub fn handle_trap(&mut self, process: &mut ProcessMemory, breakpoints: &mut [Breakpoint], tid: i32) -> bool {
+        let Ok(mut registers) = Registers::read(tid) else {
+            return false;
+        };
+        let address = registers.pc().wrapping_sub(TRAP_PC_ADJUST);
+        let Some(original) = self.original(address).map(<[u8]>::to_vec) else {
+            return false;
+        };
+        registers.set_pc(address);
+        let _ = registers.write();
+
+        if let Some(breakpoint) = breakpoints.iter_mut().find(|b| b.address == address) {
+            if breakpoint.condition.as_ref().is_none_or(|c| c.matches(&registers)) {
+                breakpoint.hits += 1;
+                if self.log.len() < MAX_LOG {
+                    self.log.push(BreakHit {
+                        tid,
+                        address,
+                        elapsed: self.started.elapsed(),
+                        registers: registers.general(),
+                    });
+                }
+            } else {
+                self.skipped += 1;
+            }
+        }
+
+        // Otros hilos siguen corriendo mientras tanto: si pasan por aquí justo ahora no se ven
+        let _ = process.write_memory(address, &original);
+        let stepped = process.tracer_mut().is_some_and(|tracer| tracer.single_step(tid));
+        let _ = process.write_memory(address, TRAP_INSTRUCTION);
+        if stepped {
+            if let Some(tracer) = process.tracer_mut() {
+                tracer.cont(tid);
+            }
+        }
+        true
+    }
+
+    /// Hilo detenido justo después de un trap nuestro (al parar todos): se retrocede su PC
+    /// para que ejecute la instrucción original cuando se restaure
+    pub fn rewind(&self, tid: i32) -> bool {
+        let Ok(mut registers) = Registers::read(tid) else {
+            return false;
+        };
+        let address = registers.pc().wrapping_sub(TRAP_PC_ADJUST);
+        if self.original(address).is_none() {
+            return false;
+        }
+        registers.set_pc(address);
+        registers.write().is_ok()
+    }
+
+    /// Restaura los bytes originales (el proceso debe estar en pausa)
+    pub fn disarm(&mut self, process: &mut ProcessMemory) {
+        for (address, original) in self.armed.drain(..) {
+            let _ = process.write_memory(address, &original);
+            unregister(self.pid, address);
+        }
+    }
+}
+
+impl Drop for ArmedBreakpoints {
+    fn drop(&mut self) {
+        // Si no se desarmó (error a mitad de camino), al menos se restauran los bytes
+        for (address, _) in &self.armed {
+            restore(self.pid, *address);
+        }
+    }
+}
+
+// Registro global de bytes originales: (pid, dirección, bytes)
+static ARMED: Mutex<Vec<(u32, u64, Vec<u8>)>> = Mutex::new(Vec::new());
+static CRASH_HOOKS: Once = Once::new();
+
+/// Bytes originales bajo los puntos armados en el proceso: (dirección, bytes)
+pub fn armed_originals(pid: u32) -> Vec<(u64, Vec<u8>)> {
+    ARMED.lock().map_or(Vec::new(), |armed| {
+        armed
+            .iter()
+            .filter(|(p, _, _)| *p == pid)
+            .map(|(_, address, original)| (*address, original.clone()))
+            .collect()
+    })
+}
+
+fn register(pid: u32, address: u64, original: &[u8]) {
+    if let Ok(mut armed) = ARMED.lock() {
+        armed.push((pid, address, original.to_vec()));
+    }
+}
+
+fn unregister(pid: u32, address: u64) {
+    if let Ok(mut armed) = ARMED.lock() {
+        armed.retain(|(p, a, _)| !(*p == pid && *a == address));
+    }
+}
+
+fn restore(pid: u32, address: u64) {
+    if let Ok(mut armed) = ARMED.lock() {
+        if let Some(index) = armed.iter().position(|(p, a, _)| *p == pid && *a == address) {
+            let (pid, address, original) = armed.remove(index);
+            write_raw(pid, address, &original);
+        }
+    }
+}
+
+/// Si la herramienta cae con puntos armados el proceso moriría en el siguiente trap, y los
+/// hilos parados justo después de uno seguirían a mitad de instrucción. Un panic o una señal
+/// de terminación paran los hilos, restauran los bytes originales, retroceden el PC de los
+/// que están sobre un trap nuestro y los sueltan. Solo vale desde el hilo que los sigue con
+/// ptrace: la herramienta tiene uno solo.
+fn install_crash_hooks() {
+    CRASH_HOOKS.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_all();
+            previous(info);
+        }));
+        for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP, libc::SIGQUIT] {
+            unsafe {
+                libc::signal(signal, on_fatal_signal as *const () as libc::sighandler_t);
+            }
+        }
+    });
+}
+
+extern "C" fn on_fatal_signal(signal: libc::c_int) {
+    restore_all();
+    unsafe {
+        libc::signal(signal, libc::SIG_DFL);
+        libc::raise(signal);
+    }
+}
+
+/// Desde un manejador de señal: sin bloquear (try_lock), sin reservar memoria y solo con
+/// llamadas al sistema
+fn restore_all() {
+    let Ok(armed) = ARMED.try_lock() else {
+        return;
+    };
+    let first_of_pid = |i: usize| !armed[..i].iter().any(|(p, _, _)| *p == armed[i].0);
+
+    for i in (0..armed.len()).filter(|&i| first_of_pid(i)) {
+        for_each_thread(armed[i].0, |tid| unsafe {
+            libc::ptrace(libc::PTRACE_INTERRUPT, tid, 0, 0);
+        });
+    }
+    let mut signals = [(0i32, 0i32); 64]; // (hilo, señal) a devolver al soltarlo
+    let count = wait_for_stops(&mut signals);
+
+    for (pid, address, original) in armed.iter() {
+        write_raw(*pid, *address, original);
+    }
+    for i in (0..armed.len()).filter(|&i| first_of_pid(i)) {
+        let pid = armed[i].0;
+        for_each_thread(pid, |tid| {
+            let rewound = rewind_raw(tid, |address| armed.iter().any(|(p, a, _)| *p == pid && *a == address));
+            let signal = match signals[..count].iter().find(|(t, _)| *t == tid) {
+                Some((_, signal)) if !rewound => *signal,
+                _ => 0,
+            };
+            unsafe {
+                libc::ptrace(libc::PTRACE_DETACH, tid, 0, signal as libc::c_long);
+            }
+        });
+    }
+}
+
+/// Recoge las paradas que provocó PTRACE_INTERRUPT hasta que pasan 20 ms sin ninguna;
+/// devuelve cuántas señales pendientes se apuntaron en `signals`
+fn wait_for_stops(signals: &mut [(i32, i32)]) -> usize {
+    let mut count = 0;
+    let mut quiet = 0;
+    let pause = libc::timespec { tv_sec: 0, tv_nsec: 1_000_000 };
+    while quiet < 20 {
+        let mut status = 0;
+        let tid = unsafe { libc::waitpid(-1, &mut status, libc::__WALL | libc::WNOHANG) };
+        if tid < 0 {
+            break; // Nadie a quien esperar
+        }
+        if tid == 0 {
+            quiet += 1;
+            unsafe {
+                libc::nanosleep(&pause, std::ptr::null_mut());
+            }
+            continue;
+        }
+        quiet = 0;
+        if libc::WIFSTOPPED(status) && status >> 16 == 0 && count < signals.len() {
+            signals[count] = (tid, libc::WSTOPSIG(status));
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Como `ArmedBreakpoints::rewind` pero con los registros en la pila
+fn rewind_raw(tid: i32, is_armed: impl Fn(u64) -> bool) -> bool {
+    if GP_REGS_SIZE == 0 {
+        return false;
+    }
+    let mut regs = [0u8; GP_REGS_SIZE];
+    let mut iov = libc::iovec {
+        iov_base: regs.as_mut_ptr() as *mut libc::c_void,
+        iov_len: regs.len(),
+    };
+    let result = unsafe { libc::ptrace(libc::PTRACE_GETREGSET, tid, NT_PRSTATUS as usize, &mut iov as *mut libc::iovec) };
+    if result != 0 || iov.iov_len < GP_REGS_SIZE {
+        return false;
+    }
+    let at = PC_INDEX * 8;
+    let mut pc = [0u8; 8];
+    pc.copy_from_slice(&regs[at..at + 8]);
+    let address = u64::from_ne_bytes(pc).wrapping_sub(TRAP_PC_ADJUST);
+    if !is_armed(address) {
+        return false;
+    }
+    regs[at..at + 8].copy_from_slice(&address.to_ne_bytes());
+    set_regset(tid, NT_PRSTATUS, &regs).is_ok()
+}
+
+/// Hilos de /proc/<pid>/task leídos con getdents64 sobre un búfer en la pila
+fn for_each_thread(pid: u32, mut f: impl FnMut(i32)) {
+    let mut path = [0u8; 32];
+    proc_path(pid, b"/task", &mut path);
+    let fd = unsafe {
+        libc::open(path.as_ptr() as *const libc::c_char, libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC)
+    };
+    if fd < 0 {
+        return;
+    }
+    let mut buffer = [0u8; 4096];
+    loop {
+        let read = unsafe { libc::syscall(libc::SYS_getdents64, fd, buffer.as_mut_ptr(), buffer.len()) };
+        if read <= 0 {
+            break;
+        }
+        // linux_dirent64: d_ino (8), d_off (8), d_reclen (2), d_type (1), d_name
+        let mut offset = 0;
+        while offset + 19 <= read as usize {
+            let reclen = u16::from_ne_bytes([buffer[offset + 16], buffer[offset + 17]]) as usize;
+            if reclen == 0 {
+                break;
+            }
+            let name = &buffer[offset + 19..(offset + reclen).min(read as usize)];
+            let mut tid = 0i32;
+            let mut valid = false;
+            for &b in name.iter().take_while(|&&b| b != 0) {
+                valid = b.is_ascii_digit();
+                if !valid {
+                    break;
+                }
+                tid = tid * 10 + (b - b'0') as i32;
+            }
+            if valid {
+                f(tid);
+            }
+            offset += reclen;
+        }
+    }
+    unsafe {
+        libc::close(fd);
+    }
+}
+
+/// "/proc/<pid><file>" terminado en NUL y montado a mano: nada de reservas de memoria
+fn proc_path(pid: u32, file: &[u8], path: &mut [u8; 32]) {
+    let mut len = 0;
+    for &b in b"/proc/" {
+        path[len] = b;
+        len += 1;
+    }
+    let mut digits = [0u8; 10];
+    let mut count = 0;
+    let mut n = pid;
+    loop {
+        digits[count] = b'0' + (n % 10) as u8;
+        count += 1;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    for i in (0..count).rev() {
+        path[len] = digits[i];
+        len += 1;
+    }
+    for &b in file {
+        path[len] = b;
+        len += 1;
+    }
+    path[len] = 0;
+}
+
+fn write_raw(pid: u32, address: u64, bytes: &[u8]) {
+    let mut path = [0u8; 32];
+    proc_path(pid, b"/mem", &mut path);
+    unsafe {
+        let fd = libc::open(path.as_ptr() as *const libc::c_char, libc::O_WRONLY | libc::O_CLOEXEC);
+        if fd >= 0 {
+            libc::pwrite(fd, bytes.as_ptr() as *const libc::c_void, bytes.len(), address as libc::off_t);
+            libc::close(fd);
+        }
+    }
+}
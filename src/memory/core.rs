@@ -0,0 +1,479 @@
+use super::dump::read_pages;
+use super::process::ProcessMemory;
+use super::ptrace::{read_registers, Tracer, GP_REGS_SIZE, NT_PRSTATUS};
+use super::region::MemoryRegion;
+use super::source::{SavedMemory, Segment};
+use crate::utils::procfs::{list_threads, read_stat, stat_field};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+#[cfg(target_arch = "x86_64")]
+const ELF_MACHINE: u16 = 62; // EM_X86_64
+#[cfg(target_arch = "aarch64")]
+const ELF_MACHINE: u16 = 183; // EM_AARCH64
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const ELF_MACHINE: u16 = 0;
+
+const PAGE_SIZE: u64 = 4096;
+const CHUNK_SIZE: u64 = 1024 * 1024;
+const ELF_HEADER_SIZE: u64 = 64;
+const PHDR_SIZE: u64 = 56;
+
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+const NT_PRPSINFO: u32 = 3;
+const NT_AUXV: u32 = 6;
+const NT_FILE: u32 = 0x4649_4c45;
+
+// elf_prstatus: siginfo, cursig, sigpend, sighold, pid/ppid/pgrp/sid, 4 timeval, registros, fpvalid
+const PRSTATUS_PID_OFFSET: usize = 32;
+const PRSTATUS_TIMES_OFFSET: usize = 48;
+const PRSTATUS_REG_OFFSET: usize = 112;
+const PRSTATUS_SIZE: usize = PRSTATUS_REG_OFFSET + GP_REGS_SIZE + 8;
+const PRPSINFO_SIZE: usize = 136;
+
+#[derive(Debug, Default)]
+pub struct CoreStats {
+    pub threads: usize,
+    pub registers: usize, // Hilos con registros reales (sin ptrace quedan a cero)
+    pub segments: usize,
+    pub unreadable_bytes: u64,
+}
+
+/// Escribe un fichero core ELF del proceso: PT_NOTE con NT_PRSTATUS por hilo, NT_PRPSINFO,
+/// NT_AUXV y NT_FILE, y un PT_LOAD por región. Los hilos se detienen durante el volcado
+/// para que memoria y registros sean coherentes (si ya estaba en pausa, sigue en pausa).
+pub fn write_core(process: &mut ProcessMemory, path: &Path) -> Result<CoreStats, Box<dyn Error>> {
+    if ELF_MACHINE == 0 {
+        return Err("Core files are only supported on x86_64 and aarch64".into());
+    }
+    if !process.is_live() {
+        return Err("Core files can only be written from a live process".into());
+    }
+
+    // Sin ptrace el core se escribe igual, con los registros a cero
+    let paused_here = !process.is_paused() && process.pause().is_ok();
+    let result = write_core_file(process, path);
+    if paused_here {
+        process.resume();
+    }
+    result
+}
+
+fn write_core_file(process: &mut ProcessMemory, path: &Path) -> Result<CoreStats, Box<dyn Error>> {
+    process.refresh_regions()?;
+    let regions = process.regions.clone();
+    let phnum = regions.len() + 1;
+    if phnum >= 0xffff {
+        return Err(format!("Too many memory regions for a core file ({})", regions.len()).into());
+    }
+
+    let mut stats = CoreStats::default();
+    let notes = build_notes(process.pid, &regions, process.tracer(), &mut stats);
+
+    let notes_offset = ELF_HEADER_SIZE + PHDR_SIZE * phnum as u64;
+    let data_offset = (notes_offset + notes.len() as u64).div_ceil(PAGE_SIZE) * PAGE_SIZE;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    write_elf_header(&mut out, phnum as u16)?;
+    write_phdr(&mut out, PT_NOTE, 0, notes_offset, 0, notes.len() as u64, 0, 0)?;
+    let mut offset = data_offset;
+    for region in &regions {
+        let size = region.end - region.start;
+        let file_size = if region.permissions.starts_with('r') { size } else { 0 };
+        let perms = region.permissions.as_bytes();
+        let flags = (perms[0] == b'r') as u32 * 4 + (perms[1] == b'w') as u32 * 2 + (perms[2] == b'x') as u32;
+        write_phdr(&mut out, PT_LOAD, flags, offset, region.start, file_size, size, PAGE_SIZE)?;
+        offset += file_size;
+    }
+    out.write_all(&notes)?;
+
+    out.seek(SeekFrom::Start(data_offset))?;
+    for region in regions.iter().filter(|r| r.permissions.starts_with('r')) {
+        let mut unreadable = Vec::new();
+        let mut address = region.start;
+        while address < region.end {
+            let len = CHUNK_SIZE.min(region.end - address);
+            out.write_all(&read_pages(process, address, len, &mut unreadable))?;
+            address += len;
+        }
+        stats.unreadable_bytes += unreadable.iter().map(|(start, end)| end - start).sum::<u64>();
+        stats.segments += 1;
+    }
+    out.flush()?;
+    Ok(stats)
+}
+
+fn write_elf_header(out: &mut impl Write, phnum: u16) -> Result<(), Box<dyn Error>> {
+    let mut header = Vec::with_capacity(ELF_HEADER_SIZE as usize);
+    header.extend_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1, 1, 0]); // ELF64, little endian, versión 1
+    header.extend_from_slice(&[0; 8]);
+    header.extend_from_slice(&4u16.to_le_bytes()); // ET_CORE
+    header.extend_from_slice(&ELF_MACHINE.to_le_bytes());
+    header.extend_from_slice(&1u32.to_le_bytes());
+    header.extend_from_slice(&0u64.to_le_bytes()); // e_entry
+    header.extend_from_slice(&ELF_HEADER_SIZE.to_le_bytes()); // e_phoff
+    header.extend_from_slice(&0u64.to_le_bytes()); // e_shoff
+    header.extend_from_slice(&0u32.to_le_bytes()); // e_flags
+    header.extend_from_slice(&(ELF_HEADER_SIZE as u16).to_le_bytes());
+    header.extend_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+    header.extend_from_slice(&phnum.to_le_bytes());
+    header.extend_from_slice(&64u16.to_le_bytes()); // e_shentsize
+    header.extend_from_slice(&[0; 4]); // e_shnum, e_shstrndx
+    out.write_all(&header)?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn write_phdr(
+    out: &mut impl Write,
+    kind: u32,
+    flags: u32,
+    offset: u64,
+    vaddr: u64,
+    file_size: u64,
+    mem_size: u64,
+    align: u64,
+) -> Result<(), Box<dyn Error>> {
+    out.write_all(&kind.to_le_bytes())?;
+    out.write_all(&flags.to_le_bytes())?;
+    for value in [offset, vaddr, 0, file_size, mem_size, align] {
+        out.write_all(&value.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+fn push_note(notes: &mut Vec<u8>, kind: u32, desc: &[u8]) {
+    notes.extend_from_slice(&5u32.to_le_bytes());
+    notes.extend_from_slice(&(desc.len() as u32).to_le_bytes());
+    notes.extend_from_slice(&kind.to_le_bytes());
+    notes.extend_from_slice(b"CORE\0\0\0\0");
+    notes.extend_from_slice(desc);
+    notes.resize(notes.len().div_ceil(4) * 4, 0);
+}
+
+fn build_notes(pid: u32, regions: &[MemoryRegion], tracer: Option<&Tracer>, stats: &mut CoreStats) -> Vec<u8> {
+    let stat = read_stat(&format!("/proc/{}/stat", pid));
+    let mut notes = Vec::new();
+
+    for (i, tid) in list_threads(pid).into_iter().enumerate() {
+        let registers = if tracer.is_some_and(|t| t.is_stopped(tid)) { read_registers(tid) } else { None };
+        if registers.is_some() {
+            stats.registers += 1;
+        }
+        stats.threads += 1;
+        let thread_stat = read_stat(&format!("/proc/{}/task/{}/stat", pid, tid));
+        push_note(&mut notes, NT_PRSTATUS, &prstatus(tid, &stat, &thread_stat, registers.as_deref()));
+
+        // Las notas del proceso van tras el NT_PRSTATUS del hilo principal, como en el kernel
+        if i == 0 {
+            push_note(&mut notes, NT_PRPSINFO, &prpsinfo(pid, &stat));
+            if let Ok(auxv) = fs::read(format!("/proc/{}/auxv", pid)) {
+                push_note(&mut notes, NT_AUXV, &auxv);
+            }
+            push_note(&mut notes, NT_FILE, &file_note(regions));
+        }
+    }
+    notes
+}
+
+fn prstatus(tid: i32, stat: &[String], thread_stat: &[String], registers: Option<&[u8]>) -> Vec<u8> {
+    let mut desc = vec![0u8; PRSTATUS_SIZE];
+    let ids = [tid as u64, stat_field(stat, 1), stat_field(stat, 2), stat_field(stat, 3)];
+    for (i, id) in ids.iter().enumerate() {
+        let at = PRSTATUS_PID_OFFSET + i * 4;
+        desc[at..at + 4].copy_from_slice(&(*id as u32).to_le_bytes());
+    }
+    // utime y stime en ticks de reloj a timeval
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+    for (i, field) in [11, 12].into_iter().enumerate() {
+        let value = stat_field(thread_stat, field);
+        let at = PRSTATUS_TIMES_OFFSET + i * 16;
+        desc[at..at + 8].copy_from_slice(&(value / ticks).to_le_bytes());
+        desc[at + 8..at + 16].copy_from_slice(&((value % ticks) * 1_000_000 / ticks).to_le_bytes());
+    }
+    if let Some(registers) = registers {
+        desc[PRSTATUS_REG_OFFSET..PRSTATUS_REG_OFFSET + GP_REGS_SIZE].copy_from_slice(registers);
+    }
+    desc
+}
+
+fn prpsinfo(pid: u32, stat: &[String]) -> Vec<u8> {
+    let mut desc = vec![0u8; PRPSINFO_SIZE];
+    let state = stat.first().and_then(|s| s.bytes().next()).unwrap_or(b'R');
+    desc[0] = b"RSDTZW".iter().position(|&s| s == state).unwrap_or(0) as u8;
+    desc[1] = state;
+    desc[2] = (state == b'Z') as u8;
+    desc[3] = stat_field(stat, 16) as i8 as u8;
+    desc[8..16].copy_from_slice(&stat_field(stat, 6).to_le_bytes());
+
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).unwrap_or_default();
+    let id_of = |key: &str| -> u32 {
+        status
+            .lines()
+            .find_map(|l| l.strip_prefix(key))
+            .and_then(|rest| rest.split_whitespace().next()?.parse().ok())
+            .unwrap_or(0)
+    };
+    desc[16..20].copy_from_slice(&id_of("Uid:").to_le_bytes());
+    desc[20..24].copy_from_slice(&id_of("Gid:").to_le_bytes());
+    let ids = [pid as u64, stat_field(stat, 1), stat_field(stat, 2), stat_field(stat, 3)];
+    for (i, id) in ids.iter().enumerate() {
+        desc[24 + i * 4..28 + i * 4].copy_from_slice(&(*id as u32).to_le_bytes());
+    }
+
+    let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default();
+    let comm = comm.trim_end().as_bytes();
+    let len = comm.len().min(15);
+    desc[40..40 + len].copy_from_slice(&comm[..len]);
+    let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+    let args: Vec<u8> = cmdline.iter().map(|&b| if b == 0 { b' ' } else { b }).collect();
+    let args = args.trim_ascii_end();
+    let len = args.len().min(79);
+    desc[56..56 + len].copy_from_slice(&args[..len]);
+    desc
+}
+
+/// NT_FILE: número de entradas, tamaño de página, (inicio, fin, offset en páginas) y las rutas
+fn file_note(regions: &[MemoryRegion]) -> Vec<u8> {
+    let files: Vec<&MemoryRegion> = regions.iter().filter(|r| r.pathname.starts_with('/')).collect();
+    let mut desc = Vec::new();
+    desc.extend_from_slice(&(files.len() as u64).to_le_bytes());
+    desc.extend_from_slice(&PAGE_SIZE.to_le_bytes());
+    for region in &files {
+        for value in [region.start, region.end, region.offset / PAGE_SIZE] {
+            desc.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    for region in &files {
+        desc.extend_from_slice(region.pathname.as_bytes());
+        desc.push(0);
+    }
+    desc
+}
+
+pub fn u16_at(data: &[u8], at: usize) -> u16 {
+    data.get(at..at + 2).map_or(0, |b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+pub fn u32_at(data: &[u8], at: usize) -> u32 {
+    data.get(at..at + 4).map_or(0, |b| u32::from_le_bytes(b.try_into().unwrap()))
+}
+
+pub fn u64_at(data: &[u8], at: usize) -> u64 {
+    data.get(at..at + 8).map_or(0, |b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// Abre un fichero core ELF64 para escanearlo sin conexión. Las rutas de los módulos salen
+/// de NT_FILE, así las direcciones relativas a módulo siguen funcionando.
+pub fn open_core(path: &Path) -> Result<SavedMemory, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut header = [0u8; ELF_HEADER_SIZE as usize];
+    file.read_exact_at(&mut header, 0)?;
+    if header[..4] != [0x7f, b'E', b'L', b'F'] || header[4] != 2 || header[5] != 1 || u16_at(&header, 16) != 4 {
+        return Err("Not a 64-bit little-endian ELF core file".into());
+    }
+    let phoff = u64_at(&header, 32);
+    let phentsize = u16_at(&header, 54) as usize;
+    let phnum = u16_at(&header, 56) as usize;
+    if phentsize < PHDR_SIZE as usize {
+        return Err("Invalid ELF program header size".into());
+    }
+    // Los tamaños de la cabecera no se creen sin más: nada que pase del final del fichero
+    let fits = |offset: u64, size: u64| offset.checked_add(size).is_some_and(|end| end <= file_len);
+    if !fits(phoff, (phentsize * phnum) as u64) {
+        return Err("Invalid core file: program headers past the end of the file".into());
+    }
+    let mut phdrs = vec![0u8; phentsize * phnum];
+    file.read_exact_at(&mut phdrs, phoff)?;
+
+    let mut pid = 0;
+    let mut mapped_files: Vec<(u64, u64, u64, String)> = Vec::new(); // (inicio, fin, offset, ruta)
+    let mut loads = Vec::new();
+    for phdr in phdrs.chunks_exact(phentsize) {
+        let (kind, flags) = (u32_at(phdr, 0), u32_at(phdr, 4));
+        let (offset, vaddr, file_size, mem_size) = (u64_at(phdr, 8), u64_at(phdr, 16), u64_at(phdr, 32), u64_at(phdr, 40));
+        match kind {
+            PT_LOAD => {
+                let end = vaddr.checked_add(mem_size).ok_or("Invalid core file: segment past the end of memory")?;
+                loads.push((flags, offset, vaddr, end, file_size.min(mem_size)));
+            }
+            PT_NOTE => {
+                if !fits(offset, file_size) {
+                    return Err("Invalid core file: notes past the end of the file".into());
+                }
+                let mut notes = vec![0u8; file_size as usize];
+                file.read_exact_at(&mut notes, offset)?;
+                parse_notes(&notes, &mut pid, &mut mapped_files);
+            }
+            _ => {}
+        }
+    }
+
+    let parts = loads
+        .into_iter()
+        .map(|(flags, offset, vaddr, end, file_size)| {
+            let permissions: String = [(4, 'r'), (2, 'w'), (1, 'x')]
+                .iter()
+                .map(|&(bit, c)| if flags & bit != 0 { c } else { '-' })
+                .chain(std::iter::once('p'))
+                .collect();
+            let (pathname, file_offset) = mapped_files
+                .iter()
+                .find(|(start, end, _, _)| (*start..*end).contains(&vaddr))
+                .map(|(start, _, ofs, name)| (name.clone(), ofs + (vaddr - start)))
+                .unwrap_or_default();
+            let region = MemoryRegion {
+                start: vaddr,
+                end,
+                permissions,
+                offset: file_offset,
+                pathname,
+            };
+            (region, Segment { file: 0, offset, file_size })
+        })
+        .collect();
+
+    Ok(SavedMemory::new(pid, path.display().to_string(), parts, vec![file], Vec::new()))
+}
+
+fn parse_notes(notes: &[u8], pid: &mut u32, mapped_files: &mut Vec<(u64, u64, u64, String)>) {
+    let mut at = 0;
+    while at + 12 <= notes.len() {
+        let namesz = u32_at(notes, at) as usize;
+        let descsz = u32_at(notes, at + 4) as usize;
+        let kind = u32_at(notes, at + 8);
+        let desc_start = at + 12 + namesz.div_ceil(4) * 4;
+        let Some(desc) = notes.get(desc_start..desc_start + descsz) else {
+            return;
+        };
+        match kind {
+            NT_PRSTATUS if *pid == 0 => *pid = u32_at(desc, PRSTATUS_PID_OFFSET),
+            NT_PRPSINFO => *pid = u32_at(desc, 24),
+            NT_FILE => {
+                let count = u64_at(desc, 0) as usize;
+                let page_size = u64_at(desc, 8);
+                let mut names = desc.get(16 + count * 24..).unwrap_or_default().split(|&b| b == 0);
+                for i in 0..count {
+                    let entry = 16 + i * 24;
+                    let name = String::from_utf8_lossy(names.next().unwrap_or_default()).into_owned();
+                    mapped_files.push((u64_at(desc, entry), u64_at(desc, entry + 8), u64_at(desc, entry + 16) * page_size, name));
+                }
+            }
+            _ => {}
+        }
+        at = desc_start + descsz.div_ceil(4) * 4;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::search::search_pattern;
+    use crate::memory::source::MemorySource;
+
+    const MARKER: &[u8] = b"lpm-core-test-marker-0123456789";
+
+    fn notes_of(data: &[u8], phoff: usize) -> Vec<(u32, usize)> {
+        let (offset, size) = (u64_at(data, phoff + 8) as usize, u64_at(data, phoff + 32) as usize);
+        let notes = &data[offset..offset + size];
+        let mut found = Vec::new();
+        let mut at = 0;
+        while at + 12 <= notes.len() {
+            let (namesz, descsz) = (u32_at(notes, at) as usize, u32_at(notes, at + 4) as usize);
+            found.push((u32_at(notes, at + 8), descsz));
+            at += 12 + namesz.div_ceil(4) * 4 + descsz.div_ceil(4) * 4;
+        }
+        found
+    }
+
+    #[test]
+    fn note_sizes() {
+        #[cfg(target_arch = "x86_64")]
+        assert_eq!(PRSTATUS_SIZE, 336);
+        #[cfg(target_arch = "aarch64")]
+        assert_eq!(PRSTATUS_SIZE, 392);
+        assert_eq!(prpsinfo(std::process::id(), &[]).len(), 136);
+        assert_eq!(prstatus(1, &[], &[], None).len(), PRSTATUS_SIZE);
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    fn core_round_trip() {
+        let marker = MARKER.to_vec();
+        let address = marker.as_ptr() as u64;
+        let pid = std::process::id();
+        let path = std::env::temp_dir().join(format!("lpm-test-{}.core", pid));
+        let mut process = ProcessMemory::new(pid).unwrap();
+        // No se puede detener a sí mismo con ptrace: el core sale sin registros
+        let stats = write_core(&mut process, &path).unwrap();
+        assert!(stats.threads >= 1 && stats.segments >= 1);
+
+        let data = fs::read(&path).unwrap();
+        assert_eq!(&data[..6], &[0x7f, b'E', b'L', b'F', 2, 1]);
+        assert_eq!(u16_at(&data, 16), 4); // ET_CORE
+        assert_eq!(u16_at(&data, 18), ELF_MACHINE);
+        assert_eq!(u16_at(&data, 52) as u64, ELF_HEADER_SIZE);
+        assert_eq!(u16_at(&data, 54) as u64, PHDR_SIZE);
+        let phnum = u16_at(&data, 56) as usize;
+        let phoff = u64_at(&data, 32) as usize;
+        assert_eq!(u32_at(&data, phoff), PT_NOTE);
+        assert!((1..phnum).all(|i| u32_at(&data, phoff + i * PHDR_SIZE as usize) == PT_LOAD));
+
+        let notes = notes_of(&data, phoff);
+        let prstatus: Vec<usize> = notes.iter().filter(|(kind, _)| *kind == NT_PRSTATUS).map(|(_, size)| *size).collect();
+        assert_eq!(prstatus.len(), stats.threads);
+        assert!(prstatus.iter().all(|&size| size == PRSTATUS_SIZE));
+        assert_eq!(notes.iter().filter(|(kind, _)| *kind == NT_PRPSINFO).collect::<Vec<_>>(), [&(NT_PRPSINFO, 136)]);
+        assert!(notes.iter().any(|(kind, _)| *kind == NT_FILE));
+        assert!(notes.iter().any(|(kind, _)| *kind == NT_AUXV));
+
+        let mut saved = open_core(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(saved.pid, pid);
+        assert_eq!(saved.regions().len(), phnum - 1);
+        assert_eq!(saved.read_memory(address, MARKER.len()).unwrap(), MARKER);
+        // NT_FILE devuelve la ruta del ejecutable a sus regiones
+        let exe = fs::read_link("/proc/self/exe").unwrap();
+        let code = note_sizes as *const () as u64;
+        let region = saved.regions().iter().find(|r| r.contains(code)).unwrap();
+        assert_eq!(Path::new(&region.pathname), exe);
+        let matches = search_pattern(&mut saved, MARKER).unwrap();
+        assert!(matches.iter().any(|r| r.is_ok_and(|(a, _)| a == address)));
+    }
+
+    /// Core mínimo con una sola cabecera de programa: (tipo, offset, vaddr, tamaño en fichero, en memoria)
+    fn open_single_phdr(name: &str, phnum: u16, phdr: (u32, u64, u64, u64, u64)) -> Result<SavedMemory, String> {
+        let mut data = vec![0u8; (ELF_HEADER_SIZE + PHDR_SIZE) as usize];
+        data[..6].copy_from_slice(&[0x7f, b'E', b'L', b'F', 2, 1]);
+        data[16..18].copy_from_slice(&4u16.to_le_bytes());
+        data[32..40].copy_from_slice(&ELF_HEADER_SIZE.to_le_bytes());
+        data[54..56].copy_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+        data[56..58].copy_from_slice(&phnum.to_le_bytes());
+        let at = ELF_HEADER_SIZE as usize;
+        let (kind, offset, vaddr, file_size, mem_size) = phdr;
+        data[at..at + 4].copy_from_slice(&kind.to_le_bytes());
+        for (field, value) in [(8, offset), (16, vaddr), (32, file_size), (40, mem_size)] {
+            data[at + field..at + field + 8].copy_from_slice(&value.to_le_bytes());
+        }
+        let path = std::env::temp_dir().join(format!("lpm-test-{}-{}.core", std::process::id(), name));
+        fs::write(&path, &data).unwrap();
+        let result = open_core(&path).map_err(|e| e.to_string());
+        fs::remove_file(&path).unwrap();
+        result
+    }
+
+    #[test]
+    fn corrupt_headers() {
+        let invalid = |result: Result<SavedMemory, String>| result.is_err_and(|e| e.starts_with("Invalid core file"));
+        assert!(invalid(open_single_phdr("notes", 1, (PT_NOTE, 0, 0, u64::MAX / 2, 0))));
+        assert!(invalid(open_single_phdr("phnum", u16::MAX, (PT_NOTE, 0, 0, 0, 0))));
+        assert!(invalid(open_single_phdr("load", 1, (PT_LOAD, 0, u64::MAX - 10, 0, 100))));
+        let saved = open_single_phdr("valid", 1, (PT_LOAD, 0, 0x1000, 0, 0x1000)).unwrap();
+        assert_eq!(saved.regions()[0].end, 0x2000);
+    }
+}
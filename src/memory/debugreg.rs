@@ -0,0 +1,220 @@
+use std::io;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Write,
+    Access, // Lectura o escritura
+}
+
+impl WatchKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WatchKind::Write => "write",
+            WatchKind::Access => "read/write",
+        }
+    }
+}
+
+/// Watchpoint hardware (registros de depuración) sobre 1, 2, 4 u 8 bytes alineados
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub address: u64,
+    pub len: usize,
+    pub kind: WatchKind,
+}
+
+impl Watchpoint {
+    pub fn new(address: u64, len: usize, kind: WatchKind) -> Result<Watchpoint, String> {
+        if ![1, 2, 4, 8].contains(&len) {
+            return Err(format!("Watchpoint length must be 1, 2, 4 or 8 bytes (got {})", len));
+        }
+        if !address.is_multiple_of(len as u64) {
+            return Err(format!("Address 0x{:x} is not aligned to {} bytes", address, len));
+        }
+        Ok(Watchpoint { address, len, kind })
+    }
+}
+
+/// En aarch64 el watchpoint salta antes del acceso: hay que avanzar una instrucción sin él
+pub const STEP_OVER_HIT: bool = cfg!(target_arch = "aarch64");
+
+#[cfg(target_arch = "x86_64")]
+mod arch {
+    use super::{WatchKind, Watchpoint};
+    use std::io;
+
+    const DEBUGREG_OFFSET: usize = 848; // offsetof(struct user, u_debugreg)
+
+    fn poke(tid: i32, index: usize, value: u64) -> io::Result<()> {
+        let offset = DEBUGREG_OFFSET + index * 8;
+        if unsafe { libc::ptrace(libc::PTRACE_POKEUSER, tid, offset, value as libc::c_long) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn peek(tid: i32, index: usize) -> io::Result<u64> {
+        let offset = DEBUGREG_OFFSET + index * 8;
+        unsafe {
+            *libc::__errno_location() = 0;
+            let value = libc::ptrace(libc::PTRACE_PEEKUSER, tid, offset, 0);
+            if value == -1 && *libc::__errno_location() != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(value as u64)
+        }
+    }
+
+    pub fn slots(_tid: i32) -> usize {
+        4 // DR0-DR3
+    }
+
+    pub fn apply(tid: i32, watchpoints: &[Watchpoint]) -> io::Result<()> {
+        // DR7 a cero primero: el kernel valida cada dirección contra la configuración activa
+        poke(tid, 7, 0)?;
+        let mut dr7 = 0u64;
+        for (slot, watchpoint) in watchpoints.iter().enumerate() {
+            poke(tid, slot, watchpoint.address)?;
+            let rw = match watchpoint.kind {
+                WatchKind::Write => 0b01,
+                WatchKind::Access => 0b11,
+            };
+            let len = match watchpoint.len {
+                1 => 0b00,
+                2 => 0b01,
+                8 => 0b10,
+                _ => 0b11,
+            };
+            dr7 |= 1 << (slot * 2); // Habilitación local
+            dr7 |= (rw | len << 2) << (16 + slot * 4);
+        }
+        if dr7 != 0 {
+            poke(tid, 7, dr7)?;
+        }
+        Ok(())
+    }
+
+    /// DR6 indica qué DRn saltó; se limpia para la siguiente vez
+    pub fn hit_slot(tid: i32, watchpoints: &[Watchpoint]) -> Option<usize> {
+        let dr6 = peek(tid, 6).ok()?;
+        let _ = poke(tid, 6, 0);
+        (0..watchpoints.len()).find(|slot| dr6 & (1 << slot) != 0)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod arch {
+    use super::super::ptrace::{get_regset, set_regset};
+    use super::{WatchKind, Watchpoint};
+    use std::io;
+
+    const NT_ARM_HW_WATCH: u32 = 0x403;
+    const MAX_SLOTS: usize = 16;
+    const TRAP_HWBKPT: i32 = 4;
+
+    /// dbg_info de user_hwdebug_state: los 8 bits bajos son el número de registros
+    pub fn slots(tid: i32) -> usize {
+        get_regset(tid, NT_ARM_HW_WATCH, 8 + 16 * MAX_SLOTS)
+            .ok()
+            .and_then(|state| state.first().copied())
+            .map_or(0, |count| (count as usize).min(MAX_SLOTS))
+    }
+
+    pub fn apply(tid: i32, watchpoints: &[Watchpoint]) -> io::Result<()> {
+        let count = slots(tid).max(watchpoints.len());
+        let mut state = vec![0u8; 8 + 16 * count];
+        for (slot, watchpoint) in watchpoints.iter().enumerate() {
+            let lsc: u32 = match watchpoint.kind {
+                WatchKind::Write => 0b10,
+                WatchKind::Access => 0b11,
+            };
+            // BAS: bytes vigilados dentro de la doble palabra alineada
+            let bas = ((1u32 << watchpoint.len) - 1) << (watchpoint.address & 7);
+            let ctrl = 1 | (0b10 << 1) | (lsc << 3) | (bas << 5); // enable, EL0, load/store, BAS
+            let at = 8 + 16 * slot;
+            state[at..at + 8].copy_from_slice(&(watchpoint.address & !7).to_ne_bytes());
+            state[at + 8..at + 12].copy_from_slice(&ctrl.to_ne_bytes());
+        }
+        set_regset(tid, NT_ARM_HW_WATCH, &state)
+    }
+
+    /// El kernel deja en si_addr la dirección accedida
+    pub fn hit_slot(tid: i32, watchpoints: &[Watchpoint]) -> Option<usize> {
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ptrace(libc::PTRACE_GETSIGINFO, tid, 0, &mut info as *mut libc::siginfo_t) } != 0 {
+            return None;
+        }
+        if info.si_code != TRAP_HWBKPT {
+            return None;
+        }
+        // Puede señalar cualquier byte de la doble palabra vigilada
+        let address = unsafe { info.si_addr() } as u64;
+        watchpoints.iter().position(|w| (w.address & !7) == (address & !7))
+    }
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod arch {
+    use super::Watchpoint;
+    use std::io;
+
+    pub fn slots(_tid: i32) -> usize {
+        0
+    }
+
+    pub fn apply(_tid: i32, watchpoints: &[Watchpoint]) -> io::Result<()> {
+        if watchpoints.is_empty() {
+            return Ok(());
+        }
+        Err(io::Error::new(io::ErrorKind::Unsupported, "hardware watchpoints need x86_64 or aarch64"))
+    }
+
+    pub fn hit_slot(_tid: i32, _watchpoints: &[Watchpoint]) -> Option<usize> {
+        None
+    }
+}
+
+/// Registros de depuración disponibles para watchpoints en el hilo
+pub fn slots(tid: i32) -> usize {
+    arch::slots(tid)
+}
+
+/// Programa los watchpoints en un hilo detenido (una lista vacía los quita todos)
+pub fn apply(tid: i32, watchpoints: &[Watchpoint]) -> io::Result<()> {
+    arch::apply(tid, watchpoints)
+}
+
+/// Índice del watchpoint que provocó el SIGTRAP del hilo, o `None` si no fue uno nuestro
+pub fn hit_slot(tid: i32, watchpoints: &[Watchpoint]) -> Option<usize> {
+    arch::hit_slot(tid, watchpoints)
+}
+
+/// Un punto del código que disparó el watchpoint
+#[derive(Debug, Clone)]
+pub struct WatchHit {
+    pub tid: i32,
+    pub ip: u64,
+    pub count: u64,
+}
+
+/// Accesos agrupados por hilo e instrucción, en orden de aparición
+#[derive(Debug, Default)]
+pub struct HitLog {
+    pub hits: Vec<WatchHit>,
+    pub total: u64,
+}
+
+impl HitLog {
+    pub fn record(&mut self, tid: i32, ip: u64) {
+        self.total += 1;
+        match self.hits.iter_mut().find(|h| h.tid == tid && h.ip == ip) {
+            Some(hit) => hit.count += 1,
+            None => self.hits.push(WatchHit { tid, ip, count: 1 }),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.hits.clear();
+        self.total = 0;
+    }
+}
@@ -0,0 +1,395 @@
+/// Una instrucción decodificada
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+    pub text: String,
+    pub branch: bool, // Salto, llamada o retorno
+    pub target: Option<u64>, // Destino del salto o dirección de memoria referenciada
+}
+
+impl Instruction {
+    /// Dirección de la instrucción siguiente
+    pub fn next(&self) -> u64 {
+        self.address + self.bytes.len() as u64
+    }
+
+    fn unknown(address: u64, bytes: &[u8], text: String) -> Instruction {
+        Instruction {
+            address,
+            bytes: bytes.to_vec(),
+            text,
+            branch: false,
+            target: None,
+        }
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod arch {
+    use super::Instruction;
+    use iced_x86::{Decoder, DecoderOptions, FlowControl, Formatter, IntelFormatter, OpKind};
+
+    pub const MAX_INSTRUCTION: usize = 15;
+    pub const ALIGNMENT: u64 = 1;
+
+    pub fn decode(bytes: &[u8], address: u64) -> Option<Instruction> {
+        let first = bytes.first()?;
+        let mut decoder = Decoder::with_ip(64, bytes, address, DecoderOptions::NONE);
+        let instruction = decoder.decode();
+        if instruction.is_invalid() {
+            return Some(Instruction::unknown(address, &[*first], "(bad)".to_string()));
+        }
+
+        let mut formatter = IntelFormatter::new();
+        let options = formatter.options_mut();
+        options.set_hex_prefix("0x");
+        options.set_hex_suffix("");
+        options.set_uppercase_hex(false);
+        options.set_space_after_operand_separator(true);
+        options.set_branch_leading_zeros(false);
+        options.set_show_branch_size(false);
+        let mut text = String::new();
+        formatter.format(&instruction, &mut text);
+
+        let near_branch = matches!(
+            instruction.op0_kind(),
+            OpKind::NearBranch16 | OpKind::NearBranch32 | OpKind::NearBranch64
+        );
+        let target = if near_branch {
+            Some(instruction.near_branch_target())
+        } else if instruction.is_ip_rel_memory_operand() {
+            Some(instruction.ip_rel_memory_address())
+        } else {
+            None
+        };
+        Some(Instruction {
+            address,
+            bytes: bytes[..instruction.len()].to_vec(),
+            text,
+            branch: instruction.flow_control() != FlowControl::Next,
+            target,
+        })
+    }
+}
+
+/// Subconjunto de A64: saltos, carga/almacenamiento con inmediato, movimientos y aritmética
+/// habituales. El resto se muestra como ".inst". En x86_64 se compila solo para los tests.
+#[cfg(any(not(target_arch = "x86_64"), test))]
+mod a64 {
+    use super::Instruction;
+
+    pub const MAX_INSTRUCTION: usize = 4;
+    pub const ALIGNMENT: u64 = 4;
+
+    const CONDITIONS: [&str; 16] = [
+        "eq", "ne", "hs", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le", "al", "nv",
+    ];
+
+    fn bits(word: u32, low: u32, count: u32) -> u32 {
+        (word >> low) & ((1 << count) - 1)
+    }
+
+    fn signed(value: u32, count: u32) -> i64 {
+        ((value as i64) << (64 - count)) >> (64 - count)
+    }
+
+    /// Registro general; el 31 es sp o el registro cero según la instrucción
+    fn reg(n: u32, wide: bool, sp: bool) -> String {
+        match (n, wide, sp) {
+            (31, true, true) => "sp".to_string(),
+            (31, false, true) => "wsp".to_string(),
+            (31, true, false) => "xzr".to_string(),
+            (31, false, false) => "wzr".to_string(),
+            (n, true, _) => format!("x{}", n),
+            (n, false, _) => format!("w{}", n),
+        }
+    }
+
+    fn relative(address: u64, offset: i64) -> u64 {
+        address.wrapping_add_signed(offset)
+    }
+
+    pub fn decode(bytes: &[u8], address: u64) -> Option<Instruction> {
+        let word = u32::from_le_bytes(bytes.get(..4)?.try_into().ok()?);
+        let bytes = &bytes[..4];
+        let instruction = |text: String, branch: bool, target: Option<u64>| Instruction {
+            address,
+            bytes: bytes.to_vec(),
+            text,
+            branch,
+            target,
+        };
+        let rd = bits(word, 0, 5);
+        let rn = bits(word, 5, 5);
+        let wide = word >> 31 == 1;
+
+        if word == 0xd503201f {
+            return Some(instruction("nop".to_string(), false, None));
+        }
+        // RET / BR / BLR
+        if word & 0xfffffc1f == 0xd65f0000 {
+            let text = if rn == 30 { "ret".to_string() } else { format!("ret {}", reg(rn, true, false)) };
+            return Some(instruction(text, true, None));
+        }
+        if word & 0xffdffc1f == 0xd61f0000 {
+            let name = if word & 0x00200000 != 0 { "blr" } else { "br" };
+            return Some(instruction(format!("{} {}", name, reg(rn, true, false)), true, None));
+        }
+        // B / BL
+        if word & 0x7c000000 == 0x14000000 {
+            let target = relative(address, signed(bits(word, 0, 26), 26) * 4);
+            let name = if wide { "bl" } else { "b" };
+            return Some(instruction(format!("{} 0x{:x}", name, target), true, Some(target)));
+        }
+        // B.cond
+        if word & 0xff000010 == 0x54000000 {
+            let target = relative(address, signed(bits(word, 5, 19), 19) * 4);
+            let condition = CONDITIONS[bits(word, 0, 4) as usize];
+            return Some(instruction(format!("b.{} 0x{:x}", condition, target), true, Some(target)));
+        }
+        // CBZ / CBNZ
+        if word & 0x7e000000 == 0x34000000 {
+            let target = relative(address, signed(bits(word, 5, 19), 19) * 4);
+            let name = if bits(word, 24, 1) == 1 { "cbnz" } else { "cbz" };
+            return Some(instruction(format!("{} {}, 0x{:x}", name, reg(rd, wide, false), target), true, Some(target)));
+        }
+        // TBZ / TBNZ
+        if word & 0x7e000000 == 0x36000000 {
+            let target = relative(address, signed(bits(word, 5, 14), 14) * 4);
+            let bit = (word >> 31) << 5 | bits(word, 19, 5);
+            let name = if bits(word, 24, 1) == 1 { "tbnz" } else { "tbz" };
+            let text = format!("{} {}, #{}, 0x{:x}", name, reg(rd, bit >= 32, false), bit, target);
+            return Some(instruction(text, true, Some(target)));
+        }
+        // ADR / ADRP
+        if word & 0x1f000000 == 0x10000000 {
+            let imm = signed(bits(word, 5, 19) << 2 | bits(word, 29, 2), 21);
+            let (name, target) = if wide {
+                ("adrp", relative(address & !0xfff, imm << 12))
+            } else {
+                ("adr", relative(address, imm))
+            };
+            return Some(instruction(format!("{} {}, 0x{:x}", name, reg(rd, true, false), target), false, Some(target)));
+        }
+        // MOVN / MOVZ / MOVK
+        if word & 0x1f800000 == 0x12800000 {
+            let name = match bits(word, 29, 2) {
+                0 => "movn",
+                2 => "movz",
+                3 => "movk",
+                _ => return Some(unknown(address, bytes, word)),
+            };
+            let shift = bits(word, 21, 2) * 16;
+            let mut text = format!("{} {}, #0x{:x}", name, reg(rd, wide, false), bits(word, 5, 16));
+            if shift != 0 {
+                text.push_str(&format!(", lsl #{}", shift));
+            }
+            return Some(instruction(text, false, None));
+        }
+        // ADD / SUB con inmediato
+        if word & 0x1f800000 == 0x11000000 {
+            let setflags = bits(word, 29, 1) == 1;
+            let name = match (bits(word, 30, 1), setflags) {
+                (0, false) => "add",
+                (0, true) => "adds",
+                (_, false) => "sub",
+                (_, true) => "subs",
+            };
+            let imm = bits(word, 10, 12) << (bits(word, 22, 1) * 12);
+            let text = if name == "subs" && rd == 31 {
+                format!("cmp {}, #0x{:x}", reg(rn, wide, true), imm)
+            } else if name == "add" && imm == 0 && (rd == 31 || rn == 31) {
+                format!("mov {}, {}", reg(rd, wide, true), reg(rn, wide, true))
+            } else {
+                format!("{} {}, {}, #0x{:x}", name, reg(rd, wide, !setflags), reg(rn, wide, true), imm)
+            };
+            return Some(instruction(text, false, None));
+        }
+        // ADD / SUB / ORR con registro desplazado (mov y cmp como alias)
+        if word & 0x1f200000 == 0x0b000000 || word & 0x7f200000 == 0x2a000000 {
+            let rm = reg(bits(word, 16, 5), wide, false);
+            let amount = bits(word, 10, 6);
+            let shift = if amount == 0 {
+                String::new()
+            } else {
+                format!(", {} #{}", ["lsl", "lsr", "asr", "ror"][bits(word, 22, 2) as usize], amount)
+            };
+            let text = if word & 0x7f200000 == 0x2a000000 {
+                if rn == 31 && amount == 0 {
+                    format!("mov {}, {}", reg(rd, wide, false), rm)
+                } else {
+                    format!("orr {}, {}, {}{}", reg(rd, wide, false), reg(rn, wide, false), rm, shift)
+                }
+            } else {
+                let name = ["add", "adds", "sub", "subs"][bits(word, 29, 2) as usize];
+                if name == "subs" && rd == 31 {
+                    format!("cmp {}, {}{}", reg(rn, wide, false), rm, shift)
+                } else {
+                    format!("{} {}, {}, {}{}", name, reg(rd, wide, false), reg(rn, wide, false), rm, shift)
+                }
+            };
+            return Some(instruction(text, false, None));
+        }
+        // LDR / STR con inmediato sin signo (registros generales)
+        if word & 0x3f000000 == 0x39000000 {
+            let size = bits(word, 30, 2);
+            let (name, wide) = match (bits(word, 22, 2), size) {
+                (0, 0) => ("strb", false),
+                (1, 0) => ("ldrb", false),
+                (0, 1) => ("strh", false),
+                (1, 1) => ("ldrh", false),
+                (0, _) => ("str", size == 3),
+                (1, _) => ("ldr", size == 3),
+                (2, 0) => ("ldrsb", true),
+                (2, 1) => ("ldrsh", true),
+                (2, 2) => ("ldrsw", true),
+                (3, 0) => ("ldrsb", false),
+                (3, 1) => ("ldrsh", false),
+                _ => return Some(unknown(address, bytes, word)),
+            };
+            let offset = match bits(word, 10, 12) << size {
+                0 => String::new(),
+                offset => format!(", #0x{:x}", offset),
+            };
+            let text = format!("{} {}, [{}{}]", name, reg(rd, wide, false), reg(rn, true, true), offset);
+            return Some(instruction(text, false, None));
+        }
+        // LDR literal
+        if word & 0x3f000000 == 0x18000000 {
+            let target = relative(address, signed(bits(word, 5, 19), 19) * 4);
+            let (name, wide) = match bits(word, 30, 2) {
+                0 => ("ldr", false),
+                1 => ("ldr", true),
+                2 => ("ldrsw", true),
+                _ => return Some(unknown(address, bytes, word)),
+            };
+            return Some(instruction(format!("{} {}, 0x{:x}", name, reg(rd, wide, false), target), false, Some(target)));
+        }
+        // STP / LDP (post-índice, offset, pre-índice)
+        if word & 0x3e000000 == 0x28000000 && bits(word, 23, 2) != 0 && bits(word, 30, 1) == 0 {
+            let wide = bits(word, 31, 1) == 1;
+            let offset = signed(bits(word, 15, 7), 7) * if wide { 8 } else { 4 };
+            let name = if bits(word, 22, 1) == 1 { "ldp" } else { "stp" };
+            let (rt, rt2, base) = (reg(rd, wide, false), reg(bits(word, 10, 5), wide, false), reg(rn, true, true));
+            let sign = if offset < 0 { "-" } else { "" };
+            let text = match bits(word, 23, 2) {
+                1 => format!("{} {}, {}, [{}], #{}0x{:x}", name, rt, rt2, base, sign, offset.unsigned_abs()),
+                2 => format!("{} {}, {}, [{}, #{}0x{:x}]", name, rt, rt2, base, sign, offset.unsigned_abs()),
+                _ => format!("{} {}, {}, [{}, #{}0x{:x}]!", name, rt, rt2, base, sign, offset.unsigned_abs()),
+            };
+            return Some(instruction(text, false, None));
+        }
+        // SVC / BRK
+        if word & 0xffe0001f == 0xd4000001 {
+            return Some(instruction(format!("svc #0x{:x}", bits(word, 5, 16)), false, None));
+        }
+        if word & 0xffe0001f == 0xd4200000 {
+            return Some(instruction(format!("brk #0x{:x}", bits(word, 5, 16)), false, None));
+        }
+        Some(unknown(address, bytes, word))
+    }
+
+    fn unknown(address: u64, bytes: &[u8], word: u32) -> Instruction {
+        Instruction::unknown(address, bytes, format!(".inst 0x{:08x}", word))
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+use a64 as arch;
+
+/// Longitud máxima de una instrucción: lo que hay que leer para decodificar una
+pub const MAX_INSTRUCTION: usize = arch::MAX_INSTRUCTION;
+
+/// Las instrucciones A64 van alineadas a 4 bytes
+pub const ALIGNMENT: u64 = arch::ALIGNMENT;
+
+/// Decodifica la instrucción al principio de `bytes`; `None` si no hay bytes suficientes
+pub fn decode(bytes: &[u8], address: u64) -> Option<Instruction> {
+    arch::decode(bytes, address)
+}
+
+/// Decodifica instrucciones seguidas mientras haya bytes
+pub fn decode_all(bytes: &[u8], address: u64) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while let Some(instruction) = bytes.get(offset..).and_then(|rest| decode(rest, address + offset as u64)) {
+        offset += instruction.bytes.len();
+        instructions.push(instruction);
+    }
+    instructions
+}
+
+/// Inicio de la instrucción anterior a `address`. En x86 la longitud es variable: se decodifica
+/// desde varios puntos anteriores y se toma el primero que cae justo en `address`.
+pub fn previous_start(bytes_before: &[u8], address: u64) -> Option<u64> {
+    let start = address - bytes_before.len() as u64;
+    for skip in 0..bytes_before.len() {
+        let from = start + skip as u64;
+        if !from.is_multiple_of(ALIGNMENT) {
+            continue;
+        }
+        let instructions = decode_all(&bytes_before[skip..], from);
+        if let Some(last) = instructions.last() {
+            if last.next() == address {
+                return Some(last.address);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::a64;
+
+    /// (dirección, palabra, texto, es salto, destino)
+    const A64_CASES: [(u64, u32, &str, bool, Option<u64>); 30] = [
+        (0x0, 0xd503201f, "nop", false, None),
+        (0x4, 0xd65f03c0, "ret", true, None),
+        (0x8, 0xd65f0020, "ret x1", true, None),
+        (0xc, 0xd61f0060, "br x3", true, None),
+        (0x10, 0xd63f0100, "blr x8", true, None),
+        (0x14, 0x14000040, "b 0x114", true, Some(0x114)),
+        (0x1000, 0x94000000, "bl 0x1000", true, Some(0x1000)),
+        (0x18, 0x97fffff0, "bl 0xffffffffffffffd8", true, Some(0xffff_ffff_ffff_ffd8)),
+        (0x1000, 0x54000040, "b.eq 0x1008", true, Some(0x1008)),
+        (0x20, 0x54ffffad, "b.le 0x14", true, Some(0x14)),
+        (0x24, 0x34000080, "cbz w0, 0x34", true, Some(0x34)),
+        (0x28, 0xb5ffff85, "cbnz x5, 0x18", true, Some(0x18)),
+        (0x2c, 0x362000a3, "tbz w3, #4, 0x40", true, Some(0x40)),
+        (0x30, 0xb747ffc9, "tbnz x9, #40, 0x28", true, Some(0x28)),
+        (0x34, 0x70000900, "adr x0, 0x157", false, Some(0x157)),
+        (0x12345, 0xb0000000, "adrp x0, 0x13000", false, Some(0x13000)),
+        (0x3c, 0x52824680, "movz w0, #0x1234", false, None),
+        (0x44, 0xf2e00022, "movk x2, #0x1, lsl #48", false, None),
+        (0x50, 0x11400420, "add w0, w1, #0x1000", false, None),
+        (0x54, 0xd100c3ff, "sub sp, sp, #0x30", false, None),
+        (0x60, 0xf100141f, "cmp x0, #0x5", false, None),
+        (0x64, 0x910003fd, "mov x29, sp", false, None),
+        (0x6c, 0x4b050883, "sub w3, w4, w5, lsl #2", false, None),
+        (0x80, 0xf9400c20, "ldr x0, [x1, #0x18]", false, None),
+        (0x88, 0xf9000083, "str x3, [x4]", false, None),
+        (0x9c, 0x58000200, "ldr x0, 0xdc", false, Some(0xdc)),
+        (0x0, 0xa9bf7bfd, "stp x29, x30, [sp, #-0x10]!", false, None),
+        (0xa8, 0xa8c27bfd, "ldp x29, x30, [sp], #0x20", false, None),
+        (0xb4, 0xd4200000, "brk #0x0", false, None),
+        (0xb8, 0x9b027c20, ".inst 0x9b027c20", false, None),
+    ];
+
+    #[test]
+    fn a64_known_words() {
+        for (address, word, text, branch, target) in A64_CASES {
+            let instruction = a64::decode(&word.to_le_bytes(), address).unwrap();
+            assert_eq!(instruction.text, text, "{:08x}", word);
+            assert_eq!((instruction.branch, instruction.target), (branch, target), "{:08x}", word);
+            assert_eq!(instruction.next(), address + 4);
+        }
+    }
+
+    #[test]
+    fn a64_needs_a_whole_word() {
+        assert_eq!((a64::MAX_INSTRUCTION, a64::ALIGNMENT), (4, 4));
+        assert!(a64::decode(&[0xc0, 0x03, 0x5f], 0).is_none());
+    }
+}
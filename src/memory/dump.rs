@@ -0,0 +1,307 @@
+use super::process::ProcessMemory;
+use super::region::MemoryRegion;
+use super::source::{MemorySource, SavedMemory, Segment};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+pub const MANIFEST_HEADER: &str = "# linux-proc-modifier memory dump v1";
+pub const MANIFEST_NAME: &str = "manifest.txt";
+pub const SPARSE_NAME: &str = "memory.bin";
+const DUMP_CHUNK: u64 = 1024 * 1024;
+const PAGE_SIZE: u64 = 4096;
+const MAX_SPARSE_GAP: u64 = 1 << 30; // Hueco máximo entre regiones dentro del fichero disperso
+
+#[derive(Debug, Default)]
+pub struct DumpStats {
+    pub regions: usize,
+    pub bytes: u64,
+    pub unreadable_bytes: u64, // Páginas que no se pudieron leer (ceros o hueco en el fichero)
+}
+
+/// Vuelca las regiones a `dir` junto con un manifiesto (dirección, permisos, ruta, sha256).
+/// Con `sparse` todo va a un único fichero disperso (offset = dirección − base, huecos grandes
+/// acortados); si no, un fichero por región. Las páginas ilegibles se rellenan con ceros (o se
+/// dejan como hueco) y se anotan en el manifiesto.
+pub fn dump_regions(
+    process: &mut ProcessMemory,
+    regions: &[MemoryRegion],
+    dir: &Path,
+    sparse: bool,
+) -> Result<DumpStats, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    let mut manifest = BufWriter::new(File::create(dir.join(MANIFEST_NAME))?);
+    writeln!(manifest, "{}", MANIFEST_HEADER)?;
+    writeln!(manifest, "pid {}", process.pid)?;
+
+    let sparse_file = if sparse {
+        Some(File::create(dir.join(SPARSE_NAME))?)
+    } else {
+        None
+    };
+    let offsets = sparse_offsets(regions);
+    if sparse {
+        writeln!(manifest, "base {:x}", regions.iter().map(|r| r.start).min().unwrap_or(0))?;
+    }
+    let mut stats = DumpStats::default();
+
+    for (region, &sparse_offset) in regions.iter().zip(&offsets) {
+        let (file_name, offset) = match sparse_file {
+            Some(_) => (SPARSE_NAME.to_string(), sparse_offset),
+            None => (format!("{:016x}-{:016x}.bin", region.start, region.end), 0),
+        };
+        let region_file;
+        let out = match &sparse_file {
+            Some(file) => file,
+            None => {
+                region_file = File::create(dir.join(&file_name))?;
+                &region_file
+            }
+        };
+
+        let mut hasher = Sha256::new();
+        let mut unreadable: Vec<(u64, u64)> = Vec::new();
+        let mut address = region.start;
+        while address < region.end {
+            let len = DUMP_CHUNK.min(region.end - address);
+            let data = read_pages(process, address, len, &mut unreadable);
+            let position = offset + (address - region.start);
+            if sparse_file.is_none() || data.iter().any(|&b| b != 0) {
+                out.write_all_at(&data, position).map_err(|e| {
+                    format!("Cannot write 0x{:x} to {}: {}", address, file_name, e)
+                })?;
+            }
+            hasher.update(&data);
+            address += len;
+        }
+        if sparse_file.is_none() {
+            out.set_len(region.end - region.start)?;
+        }
+
+        // region <inicio> <fin> <permisos> <fichero> <offset> <sha256> <ruta>; la ruta al final porque puede tener espacios
+        writeln!(
+            manifest,
+            "region {:x} {:x} {} {} {:x} {:x} {}",
+            region.start,
+            region.end,
+            region.permissions,
+            file_name,
+            offset,
+            hasher.finalize(),
+            region.pathname
+        )?;
+        for (start, end) in unreadable {
+            writeln!(manifest, "unreadable {:x} {:x}", start, end)?;
+            stats.unreadable_bytes += end - start;
+        }
+        stats.regions += 1;
+        stats.bytes += region.end - region.start;
+    }
+
+    if let Some(file) = &sparse_file {
+        if let Some(end) = regions.iter().zip(&offsets).map(|(r, offset)| offset + r.end - r.start).max() {
+            file.set_len(end)?;
+        }
+    }
+    manifest.flush()?;
+    Ok(stats)
+}
+
+/// Offset de cada región en el fichero disperso. Con la dirección virtual tal cual el fichero
+/// llegaría a 0x7fff... y ext4/f2fs lo rechazan (EFBIG): se resta la base y los huecos de más
+/// de MAX_SPARSE_GAP entre regiones se acortan a ese tamaño.
+fn sparse_offsets(regions: &[MemoryRegion]) -> Vec<u64> {
+    let mut order: Vec<usize> = (0..regions.len()).collect();
+    order.sort_by_key(|&i| regions[i].start);
+    let mut offsets = vec![0; regions.len()];
+    let mut previous: Option<(u64, u64)> = None; // (fin de la región anterior, su fin en el fichero)
+    for i in order {
+        let region = &regions[i];
+        offsets[i] = match previous {
+            Some((end, file_end)) => file_end + region.start.saturating_sub(end).min(MAX_SPARSE_GAP),
+            None => 0,
+        };
+        let file_end = offsets[i] + (region.end - region.start);
+        previous = Some((region.end, file_end));
+    }
+    offsets
+}
+
+/// Lee `len` bytes; si el bloque falla, reintenta página a página y rellena con ceros
+/// las que no se pueden leer, acumulando los rangos en `unreadable`
+pub fn read_pages<S: MemorySource + ?Sized>(
+    process: &mut S,
+    address: u64,
+    len: u64,
+    unreadable: &mut Vec<(u64, u64)>,
+) -> Vec<u8> {
+    if let Ok(data) = process.read_memory(address, len as usize) {
+        return data;
+    }
+    let mut data = Vec::with_capacity(len as usize);
+    let end = address + len;
+    let mut page = address;
+    while page < end {
+        let page_len = (PAGE_SIZE - page % PAGE_SIZE).min(end - page);
+        match process.read_memory(page, page_len as usize) {
+            Ok(bytes) => data.extend_from_slice(&bytes),
+            Err(_) => {
+                data.resize(data.len() + page_len as usize, 0);
+                match unreadable.last_mut() {
+                    Some((_, last_end)) if *last_end == page => *last_end = page + page_len,
+                    _ => unreadable.push((page, page + page_len)),
+                }
+            }
+        }
+        page += page_len;
+    }
+    data
+}
+
+/// Abre un volcado para escanearlo sin conexión; las regiones y páginas ilegibles salen del manifiesto
+pub fn open_dump(dir: &Path) -> Result<SavedMemory, Box<dyn Error>> {
+    let reader = BufReader::new(File::open(dir.join(MANIFEST_NAME))?);
+    let mut lines = reader.lines();
+    match lines.next() {
+        Some(Ok(line)) if line == MANIFEST_HEADER => {}
+        _ => return Err("Not a memory dump manifest".into()),
+    }
+
+    let mut pid = 0;
+    let mut base = 0; // Región más baja del fichero disperso
+    let mut file_names: Vec<String> = Vec::new();
+    let mut files = Vec::new();
+    let mut parts = Vec::new();
+    let mut unreadable = Vec::new();
+
+    for (number, line) in lines.enumerate() {
+        let line = line?;
+        let bad_line = || format!("Invalid manifest line {}: {}", number + 2, line);
+        let hex = |s: Option<&str>| s.and_then(|s| u64::from_str_radix(s, 16).ok()).ok_or_else(bad_line);
+        let (key, rest) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+        match key {
+            "pid" => pid = rest.parse().map_err(|_| bad_line())?,
+            "base" => base = hex(Some(rest))?,
+            "region" => {
+                let mut fields = rest.splitn(7, ' ');
+                let start = hex(fields.next())?;
+                let end = hex(fields.next())?;
+                let permissions = fields.next().ok_or_else(bad_line)?.to_string();
+                let file_name = fields.next().ok_or_else(bad_line)?;
+                let offset = hex(fields.next())?;
+                let _sha256 = fields.next().ok_or_else(bad_line)?;
+                let pathname = fields.next().unwrap_or("").to_string();
+                if file_name == SPARSE_NAME && start < base {
+                    return Err(bad_line().into());
+                }
+
+                let file = match file_names.iter().position(|f| f == file_name) {
+                    Some(index) => index,
+                    None => {
+                        files.push(File::open(dir.join(file_name))?);
+                        file_names.push(file_name.to_string());
+                        files.len() - 1
+                    }
+                };
+                let region = MemoryRegion {
+                    start,
+                    end,
+                    permissions,
+                    offset: 0,
+                    pathname,
+                };
+                parts.push((region, Segment { file, offset, file_size: end - start }));
+            }
+            "unreadable" => {
+                let mut fields = rest.split(' ');
+                unreadable.push((hex(fields.next())?, hex(fields.next())?));
+            }
+            "" => {}
+            _ => return Err(bad_line().into()),
+        }
+    }
+
+    Ok(SavedMemory::new(pid, dir.display().to_string(), parts, files, unreadable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::search::search_value;
+
+    const MARKER: &[u8] = b"lpm-dump-test-marker-0123456789";
+
+    fn region(start: u64, end: u64) -> MemoryRegion {
+        MemoryRegion {
+            start,
+            end,
+            permissions: "rw-p".to_string(),
+            offset: 0,
+            pathname: String::new(),
+        }
+    }
+
+    #[test]
+    fn sparse_offsets_shrink_large_gaps() {
+        let regions = [
+            region(0x7fff_0000_0000, 0x7fff_0000_2000),
+            region(0x5555_0000_0000, 0x5555_0000_1000),
+            region(0x5555_0000_3000, 0x5555_0000_4000),
+        ];
+        // Huecos pequeños se conservan; los grandes quedan en MAX_SPARSE_GAP
+        assert_eq!(sparse_offsets(&regions), [0x4000 + MAX_SPARSE_GAP, 0, 0x3000]);
+    }
+
+    /// Vuelca la región con el marcador y la de la pila (lejos una de otra) y la vuelve a abrir
+    fn round_trip(sparse: bool) {
+        let marker = MARKER.to_vec();
+        let value: u64 = 0x5eed_1234_abcd_0042;
+        let (heap, stack) = (marker.as_ptr() as u64, &value as *const u64 as u64);
+        let pid = std::process::id();
+        let dir = std::env::temp_dir().join(format!("lpm-test-{}-{}", pid, sparse));
+        let mut process = ProcessMemory::new(pid).unwrap();
+        let regions: Vec<MemoryRegion> = process
+            .regions
+            .iter()
+            .filter(|r| r.contains(heap) || r.contains(stack))
+            .cloned()
+            .collect();
+        assert_eq!(regions.len(), 2);
+
+        let stats = dump_regions(&mut process, &regions, &dir, sparse).unwrap();
+        assert_eq!(stats.regions, 2);
+        let manifest = fs::read_to_string(dir.join(MANIFEST_NAME)).unwrap();
+        assert_eq!(manifest.lines().next(), Some(MANIFEST_HEADER));
+        if sparse {
+            let lowest = regions.iter().map(|r| r.start).min().unwrap();
+            assert!(manifest.contains(&format!("\nbase {:x}\n", lowest)));
+            let size = fs::metadata(dir.join(SPARSE_NAME)).unwrap().len();
+            assert!(size <= stats.bytes + MAX_SPARSE_GAP);
+        }
+
+        let mut saved = open_dump(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(saved.pid, pid);
+        assert_eq!(saved.regions().len(), 2);
+        assert_eq!(saved.read_memory(heap, MARKER.len()).unwrap(), MARKER);
+        assert_eq!(saved.read_memory(stack, 8).unwrap(), value.to_le_bytes());
+        let matches = search_value(&mut saved, value).unwrap();
+        assert!(matches.iter().any(|r| r.is_ok_and(|(a, _)| a == stack)));
+        // Fuera de las regiones volcadas no hay nada
+        let outside = regions.iter().map(|r| r.end).max().unwrap();
+        assert!(saved.read_memory(outside, 1).is_err());
+    }
+
+    #[test]
+    fn dump_round_trip_per_region() {
+        round_trip(false);
+    }
+
+    #[test]
+    fn dump_round_trip_sparse() {
+        round_trip(true);
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::fs;
+use std::io;
+
+/// Qué se estaba haciendo cuando falló el acceso a memoria
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Attach,
+    Read,
+    Write,
+    ReadMaps,
+    Scan,
+    Trace,
+}
+
+impl Operation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Attach => "attach",
+            Operation::Read => "read",
+            Operation::Write => "write",
+            Operation::ReadMaps => "read memory maps",
+            Operation::Scan => "scan",
+            Operation::Trace => "ptrace",
+        }
+    }
+}
+
+/// Errores de la capa de memoria, con la operación y la dirección implicadas
+#[derive(Debug)]
+pub enum MemoryError {
+    /// EPERM/EACCES: falta root, CAP_SYS_PTRACE o lo impide ptrace_scope
+    PermissionDenied { pid: u32, operation: Operation, address: Option<u64> },
+    /// ESRCH/ENOENT: el proceso no existe o ya terminó
+    ProcessGone { pid: u32, operation: Operation },
+    /// EIO/EFAULT o lectura incompleta: la dirección no está mapeada (o no es accesible)
+    Unmapped { operation: Operation, address: u64, len: usize },
+    /// Escritura sobre un volcado o core
+    ReadOnly { address: u64 },
+    /// Operación que necesita un proceso vivo (ptrace) sobre un volcado o core
+    Offline { operation: Operation },
+    /// Un hilo se está ejecutando a mitad de las instrucciones que se quieren reescribir
+    CodeInUse { address: u64, tid: i32 },
+    /// Longitud de rango fuera de los límites admitidos
+    InvalidLength { len: usize, max: usize },
+    /// Fallo al leer un volcado o core guardado
+    Saved { address: u64, message: String },
+    /// Línea de /proc/<pid>/maps que no se entiende
+    Parse { what: &'static str, line: String },
+    Io { operation: Operation, address: Option<u64>, source: io::Error },
+}
+
+impl MemoryError {
+    /// Clasifica un error de E/S según su errno
+    pub fn from_io(error: io::Error, pid: u32, operation: Operation, address: Option<u64>, len: usize) -> MemoryError {
+        match (error.raw_os_error(), error.kind()) {
+            (Some(libc::EPERM) | Some(libc::EACCES), _) => MemoryError::PermissionDenied { pid, operation, address },
+            (Some(libc::ESRCH) | Some(libc::ENOENT), _) => MemoryError::ProcessGone { pid, operation },
+            (Some(libc::EIO) | Some(libc::EFAULT), _) | (_, io::ErrorKind::UnexpectedEof) => match address {
+                Some(address) => MemoryError::Unmapped { operation, address, len },
+                None => MemoryError::Io { operation, address, source: error },
+            },
+            _ => MemoryError::Io { operation, address, source: error },
+        }
+    }
+
+    /// Consejo para el usuario según la causa, si hay alguno útil
+    pub fn hint(&self) -> Option<String> {
+        match self {
+            MemoryError::PermissionDenied { pid, operation: Operation::Trace, .. } => match tracer_pid(*pid) {
+                Some(tracer) => Some(format!("PID {} is already being traced by PID {} (a debugger?); detach it first", pid, tracer)),
+                None => Some(permission_hint()),
+            },
+            MemoryError::PermissionDenied { .. } => Some(permission_hint()),
+            MemoryError::ProcessGone { operation: Operation::Attach, .. } => {
+                Some("Check the PID or find the process by name ([1] in process selection)".to_string())
+            }
+            MemoryError::ProcessGone { .. } => {
+                Some("The process is gone; reattach from the main menu ([R]) or change process".to_string())
+            }
+            MemoryError::Unmapped { .. } => {
+                Some("The address is not mapped or not accessible; check the region list ([1])".to_string())
+            }
+            MemoryError::ReadOnly { .. } => Some("Dumps and core files are read-only; attach to a live process to write".to_string()),
+            MemoryError::Offline { .. } => Some("Attach to a live process ([7] Change process)".to_string()),
+            MemoryError::CodeInUse { .. } => Some("Try again in a moment; the thread usually moves on quickly".to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Otro depurador conectado (TracerPid de /proc/<pid>/status); un proceso solo admite uno
+fn tracer_pid(pid: u32) -> Option<u32> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let tracer = status.lines().find_map(|l| l.strip_prefix("TracerPid:"))?.trim().parse().ok()?;
+    (tracer != 0).then_some(tracer)
+}
+
+/// Explica el bloqueo más probable: ptrace_scope, falta de root o SELinux
+fn permission_hint() -> String {
+    let root = unsafe { libc::geteuid() } == 0;
+    let scope = fs::read_to_string("/proc/sys/kernel/yama/ptrace_scope")
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok());
+    match scope {
+        Some(3) => "kernel.yama.ptrace_scope=3 disables ptrace until reboot, even for root".to_string(),
+        Some(2) if !root => "kernel.yama.ptrace_scope=2 allows only admins to attach; run as root".to_string(),
+        Some(1) if !root => {
+            "kernel.yama.ptrace_scope=1 blocks this; run as root (or sysctl kernel.yama.ptrace_scope=0)".to_string()
+        }
+        _ if !root => "Access denied; run as root or as the same user as the target".to_string(),
+        _ => "Access denied even as root; check SELinux (getenforce) or whether the target is dumpable".to_string(),
+    }
+}
+
+impl fmt::Display for MemoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MemoryError::PermissionDenied { pid, operation, address: Some(address) } => {
+                write!(f, "Permission denied to {} PID {} at 0x{:x}", operation.name(), pid, address)
+            }
+            MemoryError::PermissionDenied { pid, operation, address: None } => {
+                write!(f, "Permission denied to {} PID {}", operation.name(), pid)
+            }
+            MemoryError::ProcessGone { pid, operation: Operation::Attach } => write!(f, "Process {} does not exist", pid),
+            MemoryError::ProcessGone { pid, operation } => {
+                write!(f, "Cannot {}: process {} has exited", operation.name(), pid)
+            }
+            MemoryError::Unmapped { operation, address, len } => {
+                write!(f, "Cannot {} {} bytes at 0x{:x}: address not mapped", operation.name(), len, address)
+            }
+            MemoryError::ReadOnly { address } => write!(f, "Cannot write at 0x{:x}: offline memory is read-only", address),
+            MemoryError::Offline { operation } => write!(f, "Cannot {}: memory is offline (dump or core file)", operation.name()),
+            MemoryError::CodeInUse { address, tid } => {
+                write!(f, "Cannot patch 0x{:x}: thread {} is in the middle of those instructions", address, tid)
+            }
+            MemoryError::InvalidLength { len, max } => {
+                write!(f, "Invalid length {}: must be between 1 and {} bytes", len, max)
+            }
+            MemoryError::Saved { address, message } => write!(f, "Saved memory at 0x{:x}: {}", address, message),
+            MemoryError::Parse { what, line } => write!(f, "Invalid {}: {}", what, line),
+            MemoryError::Io { operation, address: Some(address), source } => {
+                write!(f, "Failed to {} at 0x{:x}: {}", operation.name(), address, source)
+            }
+            MemoryError::Io { operation, address: None, source } => write!(f, "Failed to {}: {}", operation.name(), source),
+        }
+    }
+}
+
+impl std::error::Error for MemoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MemoryError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Mensaje para la interfaz: el error y, si es un `MemoryError` con consejo, el consejo
+pub fn describe_error(error: &(dyn std::error::Error + 'static)) -> String {
+    match error.downcast_ref::<MemoryError>().and_then(|e| e.hint()) {
+        Some(hint) => format!("{}\n   💡 {}", error, hint),
+        None => error.to_string(),
+    }
+}
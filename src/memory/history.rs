@@ -0,0 +1,78 @@
+use super::results::ResultStore;
+use crate::types::{SearchFilter, ValueType};
+
+const MAX_HISTORY: usize = 32;
+
+/// Estado completo de un paso del escaneo (resultados y filtros que lo produjeron)
+#[derive(Debug)]
+pub struct ScanStep {
+    pub results: ResultStore,
+    pub value_type: Option<ValueType>,
+    pub filter_history: Vec<(SearchFilter, Option<Vec<u8>>)>,
+}
+
+impl ScanStep {
+    pub fn describe(&self) -> String {
+        describe_step(self.value_type, &self.filter_history)
+    }
+}
+
+/// Descripción de la operación que produjo un paso: la búsqueda inicial o el último filtro
+pub fn describe_step(
+    value_type: Option<ValueType>,
+    filter_history: &[(SearchFilter, Option<Vec<u8>>)],
+) -> String {
+    let type_name = value_type.map(|t| t.name()).unwrap_or("?");
+    match filter_history.last() {
+        Some((filter, Some(value))) if value.len() <= 16 => {
+            format!("filter {} {:02X?}", filter.name(), value)
+        }
+        Some((filter, _)) => format!("filter {}", filter.name()),
+        None => format!("search {}", type_name),
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ScanHistory {
+    undo: Vec<ScanStep>,
+    redo: Vec<ScanStep>,
+}
+
+impl ScanHistory {
+    /// Guarda el estado anterior a una búsqueda o filtro; invalida los pasos rehacibles
+    pub fn push(&mut self, step: ScanStep) {
+        self.redo.clear();
+        self.undo.push(step);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+    }
+
+    pub fn undo(&mut self, current: ScanStep) -> Result<ScanStep, ScanStep> {
+        match self.undo.pop() {
+            Some(previous) => {
+                self.redo.push(current);
+                Ok(previous)
+            }
+            None => Err(current),
+        }
+    }
+
+    pub fn redo(&mut self, current: ScanStep) -> Result<ScanStep, ScanStep> {
+        match self.redo.pop() {
+            Some(next) => {
+                self.undo.push(current);
+                Ok(next)
+            }
+            None => Err(current),
+        }
+    }
+
+    pub fn undo_steps(&self) -> &[ScanStep] {
+        &self.undo
+    }
+
+    pub fn redo_steps(&self) -> &[ScanStep] {
+        &self.redo
+    }
+}
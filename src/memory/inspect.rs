@@ -0,0 +1,194 @@
+use super::region::{find_region, module_offset, MemoryRegion};
+
+pub const INSPECT_BYTES: usize = 64; // Bytes leídos para decodificar (cadenas incluidas)
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InspectType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    Pointer,
+    Utf8,
+    Utf16,
+}
+
+impl InspectType {
+    pub const ALL: [InspectType; 13] = [
+        InspectType::I8,
+        InspectType::U8,
+        InspectType::I16,
+        InspectType::U16,
+        InspectType::I32,
+        InspectType::U32,
+        InspectType::I64,
+        InspectType::U64,
+        InspectType::F32,
+        InspectType::F64,
+        InspectType::Pointer,
+        InspectType::Utf8,
+        InspectType::Utf16,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            InspectType::I8 => "i8",
+            InspectType::U8 => "u8",
+            InspectType::I16 => "i16",
+            InspectType::U16 => "u16",
+            InspectType::I32 => "i32",
+            InspectType::U32 => "u32",
+            InspectType::I64 => "i64",
+            InspectType::U64 => "u64",
+            InspectType::F32 => "f32",
+            InspectType::F64 => "f64",
+            InspectType::Pointer => "ptr",
+            InspectType::Utf8 => "utf8",
+            InspectType::Utf16 => "utf16",
+        }
+    }
+
+    /// Los tipos de un byte y UTF-8 no dependen del orden de bytes
+    pub fn has_endian(&self) -> bool {
+        !matches!(self, InspectType::I8 | InspectType::U8 | InspectType::Utf8)
+    }
+
+    /// Interpreta "i32", "u16be", "f64le", "utf16be"...
+    pub fn parse_spec(spec: &str) -> Option<(InspectType, Endian)> {
+        let spec = spec.trim().to_ascii_lowercase();
+        let (name, endian) = if let Some(name) = spec.strip_suffix("be") {
+            (name, Endian::Big)
+        } else {
+            (spec.strip_suffix("le").unwrap_or(&spec), Endian::Little)
+        };
+        let kind = InspectType::ALL.into_iter().find(|t| t.name() == name)?;
+        if endian == Endian::Big && !kind.has_endian() {
+            return None;
+        }
+        Some((kind, endian))
+    }
+}
+
+macro_rules! int_bytes {
+    ($t:ty, $bytes:expr, $endian:expr) => {{
+        let raw: [u8; std::mem::size_of::<$t>()] = $bytes.get(..std::mem::size_of::<$t>())?.try_into().ok()?;
+        match $endian {
+            Endian::Little => <$t>::from_le_bytes(raw),
+            Endian::Big => <$t>::from_be_bytes(raw),
+        }
+    }};
+}
+
+pub fn read_pointer(bytes: &[u8], endian: Endian) -> Option<u64> {
+    Some(int_bytes!(u64, bytes, endian))
+}
+
+/// Decodifica los bytes como `kind`; `None` si no hay bytes suficientes
+pub fn decode(kind: InspectType, endian: Endian, bytes: &[u8]) -> Option<String> {
+    let text = match kind {
+        InspectType::I8 => (*bytes.first()? as i8).to_string(),
+        InspectType::U8 => bytes.first()?.to_string(),
+        InspectType::I16 => int_bytes!(i16, bytes, endian).to_string(),
+        InspectType::U16 => int_bytes!(u16, bytes, endian).to_string(),
+        InspectType::I32 => int_bytes!(i32, bytes, endian).to_string(),
+        InspectType::U32 => int_bytes!(u32, bytes, endian).to_string(),
+        InspectType::I64 => int_bytes!(i64, bytes, endian).to_string(),
+        InspectType::U64 => int_bytes!(u64, bytes, endian).to_string(),
+        InspectType::F32 => format_float(f32::from_bits(int_bytes!(u32, bytes, endian))),
+        InspectType::F64 => format_float(f64::from_bits(int_bytes!(u64, bytes, endian))),
+        InspectType::Pointer => format!("0x{:x}", read_pointer(bytes, endian)?),
+        InspectType::Utf8 => {
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            format!("{:?}", String::from_utf8_lossy(&bytes[..end]))
+        }
+        InspectType::Utf16 => {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|c| match endian {
+                    Endian::Little => u16::from_le_bytes([c[0], c[1]]),
+                    Endian::Big => u16::from_be_bytes([c[0], c[1]]),
+                })
+                .take_while(|&u| u != 0)
+                .collect();
+            format!("{:?}", String::from_utf16_lossy(&units))
+        }
+    };
+    Some(text)
+}
+
+/// Notación científica para valores muy grandes o muy pequeños (bits aleatorios como float)
+fn format_float<T: Copy + Into<f64> + std::fmt::Display + std::fmt::LowerExp>(value: T) -> String {
+    let magnitude = value.into().abs();
+    if magnitude != 0.0 && !(1e-4..1e12).contains(&magnitude) {
+        format!("{:e}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Región a la que apunta un puntero, como "libc.so+0x1234 r-xp" o "[heap] rw-p"
+pub fn describe_pointer(regions: &[MemoryRegion], pointer: u64) -> String {
+    match find_region(regions, pointer) {
+        Some(region) => match module_offset(regions, pointer) {
+            Some((module, offset)) => format!(
+                "{}+0x{:x} {}",
+                module.rsplit('/').next().unwrap_or(&module),
+                offset,
+                region.permissions
+            ),
+            None if region.pathname.is_empty() => format!("anon+0x{:x} {}", pointer - region.start, region.permissions),
+            None => format!("{}+0x{:x} {}", region.pathname, pointer - region.start, region.permissions),
+        },
+        None => "not mapped".to_string(),
+    }
+}
+
+/// Convierte el texto del usuario en los bytes de `kind`
+pub fn encode(kind: InspectType, endian: Endian, input: &str) -> Result<Vec<u8>, String> {
+    fn int<T: TryFrom<i128>>(input: &str) -> Result<T, String> {
+        let input = input.trim();
+        let value = match input.strip_prefix("0x") {
+            Some(hex) => i128::from_str_radix(hex, 16),
+            None => input.parse::<i128>(),
+        }
+        .map_err(|_| format!("Invalid number: {}", input))?;
+        T::try_from(value).map_err(|_| format!("Value out of range: {}", input))
+    }
+    macro_rules! to_bytes {
+        ($value:expr) => {{
+            let value = $value;
+            match endian {
+                Endian::Little => value.to_le_bytes().to_vec(),
+                Endian::Big => value.to_be_bytes().to_vec(),
+            }
+        }};
+    }
+    let float_error = |_| format!("Invalid float: {}", input);
+
+    Ok(match kind {
+        InspectType::I8 => vec![int::<i8>(input)? as u8],
+        InspectType::U8 => vec![int::<u8>(input)?],
+        InspectType::I16 => to_bytes!(int::<i16>(input)?),
+        InspectType::U16 => to_bytes!(int::<u16>(input)?),
+        InspectType::I32 => to_bytes!(int::<i32>(input)?),
+        InspectType::U32 => to_bytes!(int::<u32>(input)?),
+        InspectType::I64 => to_bytes!(int::<i64>(input)?),
+        InspectType::U64 | InspectType::Pointer => to_bytes!(int::<u64>(input)?),
+        InspectType::F32 => to_bytes!(input.trim().parse::<f32>().map_err(float_error)?),
+        InspectType::F64 => to_bytes!(input.trim().parse::<f64>().map_err(float_error)?),
+        InspectType::Utf8 => input.as_bytes().to_vec(),
+        InspectType::Utf16 => input.encode_utf16().flat_map(|u| to_bytes!(u)).collect(),
+    })
+}
@@ -1,4 +1,22 @@
-
+pub mod breakpoint;
+pub mod core;
+pub mod debugreg;
+pub mod disasm;
+pub mod dump;
+pub mod error;
+pub mod history;
+pub mod inspect;
+pub mod patch;
 pub mod process;
+pub mod ptrace;
 pub mod region;
+pub mod registers;
+pub mod results;
 pub mod search;
+pub mod session;
+pub mod snapshot;
+pub mod source;
+pub mod structs;
+pub mod symbols;
+pub mod table;
+pub mod watch;
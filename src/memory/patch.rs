@@ -0,0 +1,327 @@
+use super::breakpoint::armed_originals;
+use super::disasm::{decode, Instruction, MAX_INSTRUCTION};
+use super::error::MemoryError;
+use super::process::ProcessMemory;
+use super::region::{find_region, module_base, module_offset};
+use super::registers::Registers;
+use crate::utils::hex;
+use sha2::{Digest, Sha256};
+
+#[cfg(target_arch = "x86_64")]
+pub const NOP: &[u8] = &[0x90];
+#[cfg(not(target_arch = "x86_64"))]
+pub const NOP: &[u8] = &[0x1f, 0x20, 0x03, 0xd5]; // A64
+
+const CONTEXT: u64 = 16; // Bytes a cada lado que entran en la firma
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchState {
+    Original,
+    Patched,
+    Mismatch, // Ni los bytes originales ni los del parche: otra versión del módulo
+}
+
+/// Reemplazo de instrucciones completas. La firma es un sha256 de los bytes de alrededor
+/// (con los originales en medio) para comprobar que se aplica sobre el mismo código.
+#[derive(Debug, Clone)]
+pub struct Patch {
+    pub address: u64,
+    pub module: Option<(String, u64)>, // Módulo y offset, para sobrevivir a ASLR
+    pub original: Vec<u8>,
+    pub patched: Vec<u8>,
+    pub signature: String,
+    pub enabled: bool,
+    pub description: String,
+}
+
+impl Patch {
+    /// Prepara un parche (sin aplicarlo). Si los bytes nuevos acaban a mitad de una
+    /// instrucción se rellena con NOP hasta el final de esa instrucción.
+    pub fn new(process: &mut ProcessMemory, address: u64, bytes: &[u8], description: String) -> Result<Patch, String> {
+        if bytes.is_empty() {
+            return Err("Empty patch".to_string());
+        }
+        let instructions = instructions_covering(process, address, bytes.len())?;
+        let len = instructions.last().map_or(0, |i| i.next() - address) as usize;
+        let mut patched = bytes.to_vec();
+        while patched.len() < len {
+            patched.extend_from_slice(NOP);
+        }
+        if patched.len() != len {
+            return Err(format!("Patch must be a multiple of {} bytes", NOP.len()));
+        }
+        if process.patches.iter().any(|p| p.overlaps(address, len)) {
+            return Err(format!("There is already a patch overlapping 0x{:x}", address));
+        }
+        let original: Vec<u8> = instructions.iter().flat_map(|i| i.bytes.iter().copied()).collect();
+        let others = process.patches.clone();
+        let signature = signature(process, address, &original, &others).ok_or("Cannot read the bytes around the patch")?;
+        Ok(Patch {
+            address,
+            module: module_offset(&process.regions, address),
+            original,
+            patched,
+            signature,
+            enabled: false,
+            description,
+        })
+    }
+
+    /// NOP sobre `count` instrucciones completas desde `address`
+    pub fn nop(process: &mut ProcessMemory, address: u64, count: usize) -> Result<Patch, String> {
+        let mut instructions = Vec::new();
+        let mut at = address;
+        for _ in 0..count.max(1) {
+            let instruction = instructions_covering(process, at, 1)?.remove(0);
+            at = instruction.next();
+            instructions.push(instruction);
+        }
+        let len = (at - address) as usize;
+        let text: Vec<&str> = instructions.iter().map(|i| i.text.as_str()).collect();
+        Patch::new(process, address, &NOP.repeat(len / NOP.len()), format!("nop {}", text.join("; ")))
+    }
+
+    /// Convierte el salto condicional en `address` en uno incondicional al mismo destino
+    pub fn force_branch(process: &mut ProcessMemory, address: u64) -> Result<Patch, String> {
+        let instruction = instructions_covering(process, address, 1)?.remove(0);
+        let bytes = unconditional(&instruction).ok_or_else(|| format!("Not a conditional branch: {}", instruction.text))?;
+        Patch::new(process, address, &bytes, format!("always {}", instruction.text))
+    }
+
+    pub fn len(&self) -> usize {
+        self.original.len()
+    }
+
+    fn overlaps(&self, address: u64, len: usize) -> bool {
+        address < self.address + self.len() as u64 && self.address < address + len as u64
+    }
+
+    /// Qué hay ahora en memoria, comprobando además la firma del código de alrededor
+    pub fn state(&self, process: &mut ProcessMemory) -> PatchState {
+        let others = process.patches.clone();
+        self.state_among(process, &others)
+    }
+
+    /// Como `state`, con `others` como parches vecinos (puede incluir a este mismo)
+    fn state_among(&self, process: &mut ProcessMemory, others: &[Patch]) -> PatchState {
+        let Some(current) = self.current(process) else {
+            return PatchState::Mismatch;
+        };
+        let state = if current == self.original {
+            PatchState::Original
+        } else if current == self.patched {
+            PatchState::Patched
+        } else {
+            return PatchState::Mismatch;
+        };
+        match signature(process, self.address, &self.original, others) {
+            Some(signature) if signature == self.signature => state,
+            _ => PatchState::Mismatch,
+        }
+    }
+
+    /// Bytes en memoria ahora mismo, sin los traps de los puntos de ruptura armados
+    pub fn current(&self, process: &mut ProcessMemory) -> Option<Vec<u8>> {
+        let mut current = process.read_memory(self.address, self.len()).ok()?;
+        unpatch(process, self.address, &mut current, &[]);
+        Some(current)
+    }
+
+    pub fn apply(&mut self, process: &mut ProcessMemory) -> Result<(), MemoryError> {
+        write_code(process, self.address, &self.patched)?;
+        self.enabled = true;
+        Ok(())
+    }
+
+    pub fn revert(&mut self, process: &mut ProcessMemory) -> Result<(), MemoryError> {
+        write_code(process, self.address, &self.original)?;
+        self.enabled = false;
+        Ok(())
+    }
+
+    pub fn toggle(&mut self, process: &mut ProcessMemory) -> Result<(), MemoryError> {
+        if self.enabled {
+            self.revert(process)
+        } else {
+            self.apply(process)
+        }
+    }
+}
+
+/// Instrucciones completas desde `address` que cubren al menos `len` bytes, dentro de una
+/// región ejecutable
+fn instructions_covering(process: &mut ProcessMemory, address: u64, len: usize) -> Result<Vec<Instruction>, String> {
+    let region = find_region(&process.regions, address)
+        .filter(|r| r.permissions.contains('x'))
+        .ok_or_else(|| format!("0x{:x} is not in an executable region", address))?;
+    let end = region.end;
+    let size = ((len + MAX_INSTRUCTION) as u64).min(end - address) as usize;
+    let bytes = process.read_memory(address, size).map_err(|e| e.to_string())?;
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < len {
+        let instruction = bytes
+            .get(offset..)
+            .and_then(|rest| decode(rest, address + offset as u64))
+            .filter(|i| i.text != "(bad)")
+            .ok_or_else(|| format!("Cannot decode an instruction at 0x{:x}", address + offset as u64))?;
+        offset += instruction.bytes.len();
+        instructions.push(instruction);
+    }
+    Ok(instructions)
+}
+
+/// sha256 (primeros 8 bytes) de hasta CONTEXT bytes antes, los originales y CONTEXT después,
+/// sin salir de la región. Los bytes de otros parches y de puntos de ruptura cuentan con su
+/// valor original, para que la firma no dependa de si están puestos.
+fn signature(process: &mut ProcessMemory, address: u64, original: &[u8], others: &[Patch]) -> Option<String> {
+    let region = find_region(&process.regions, address)?;
+    let (region_start, region_end) = (region.start, region.end);
+    let end = address + original.len() as u64;
+    let start = address.saturating_sub(CONTEXT).max(region_start);
+    let others: Vec<Patch> = others.iter().filter(|p| p.address != address).cloned().collect();
+    let mut before = process.read_memory(start, (address - start) as usize).ok()?;
+    unpatch(process, start, &mut before, &others);
+    let mut after = process.read_memory(end, (end + CONTEXT).min(region_end).saturating_sub(end) as usize).ok()?;
+    unpatch(process, end, &mut after, &others);
+    let mut hasher = Sha256::new();
+    hasher.update(&before);
+    hasher.update(original);
+    hasher.update(&after);
+    Some(hex::encode(&hasher.finalize()[..8]))
+}
+
+/// Devuelve a `bytes` (leídos en `address`) los valores originales bajo los puntos de
+/// ruptura armados y, después, bajo los parches de `patches`
+fn unpatch(process: &ProcessMemory, address: u64, bytes: &mut [u8], patches: &[Patch]) {
+    let end = address + bytes.len() as u64;
+    let breakpoints = armed_originals(process.pid);
+    let originals = breakpoints
+        .iter()
+        .map(|(at, original)| (*at, original.as_slice()))
+        .chain(patches.iter().map(|p| (p.address, p.original.as_slice())));
+    for (at, original) in originals {
+        for (i, &byte) in original.iter().enumerate() {
+            let position = at + i as u64;
+            if position >= address && position < end {
+                bytes[(position - address) as usize] = byte;
+            }
+        }
+    }
+}
+
+/// Escribe código con todos los hilos detenidos (a través de /proc/pid/mem, que permite
+/// escribir en páginas de solo lectura). Se rechaza si un hilo está a mitad del rango.
+fn write_code(process: &mut ProcessMemory, address: u64, bytes: &[u8]) -> Result<(), MemoryError> {
+    let paused_here = !process.is_paused();
+    process.pause()?;
+    let tids: Vec<i32> = process.tracer().map_or(Vec::new(), |t| t.threads().iter().map(|t| t.tid).collect());
+    let inside = tids.into_iter().find(|&tid| {
+        Registers::read(tid).is_ok_and(|r| r.pc() > address && r.pc() < address + bytes.len() as u64)
+    });
+    let result = match inside {
+        Some(tid) => Err(MemoryError::CodeInUse { address, tid }),
+        None => process.write_batch(&[(address, bytes.to_vec())]),
+    };
+    if paused_here {
+        process.resume();
+    }
+    result
+}
+
+/// Quita de la memoria los parches puestos, del último al primero. En `process.patches` quedan
+/// solo los que no se pudieron revertir (un hilo a mitad de ellos), para no perderles la pista.
+pub fn revert_all(process: &mut ProcessMemory) {
+    let mut stuck = Vec::new();
+    while let Some(mut patch) = process.patches.pop() {
+        let written = patch.current(process).as_ref() == Some(&patch.patched);
+        if written && patch.revert(process).is_err() {
+            stuck.push(patch);
+        }
+    }
+    stuck.reverse();
+    process.patches = stuck;
+}
+
+/// Reubica parches de otra instancia o de una tabla: por módulo + offset si lo tienen, o en
+/// la misma dirección si el PID no cambió. Se quedan los que se pueden ubicar y verificar;
+/// `enabled` pasa a reflejar lo que hay en memoria, salvo que con `reapply` los marcados como
+/// activos que encuentran el código original se vuelven a aplicar. Se descartan los que se
+/// solapan con alguno que ya esté en `process.patches`.
+pub fn relocate(process: &mut ProcessMemory, patches: Vec<Patch>, same_pid: bool, reapply: bool) -> (Vec<Patch>, usize) {
+    let mut located = Vec::new();
+    let mut rejected = 0;
+    for mut patch in patches {
+        let address = match &patch.module {
+            Some((module, offset)) => module_base(&process.regions, module).map(|base| base + offset),
+            None if same_pid => Some(patch.address),
+            None => None,
+        };
+        let taken = |address| process.patches.iter().any(|p| p.overlaps(address, patch.len()));
+        match address.filter(|&address| !taken(address)) {
+            Some(address) => {
+                patch.address = address;
+                located.push(patch);
+            }
+            None => rejected += 1,
+        }
+    }
+    // Las firmas se comprueban con todos ya ubicados: los vecinos cuentan con sus bytes originales
+    let siblings: Vec<Patch> = located.iter().chain(&process.patches).cloned().collect();
+    let mut kept = Vec::new();
+    for mut patch in located {
+        let wanted = patch.enabled;
+        match patch.state_among(process, &siblings) {
+            PatchState::Original => {
+                patch.enabled = false;
+                if reapply && wanted {
+                    // Si falla queda desactivado, a la vista en la lista de parches
+                    let _ = patch.apply(process);
+                }
+            }
+            PatchState::Patched => patch.enabled = true,
+            PatchState::Mismatch => {
+                rejected += 1;
+                continue;
+            }
+        }
+        kept.push(patch);
+    }
+    (kept, rejected)
+}
+
+#[cfg(target_arch = "x86_64")]
+fn unconditional(instruction: &Instruction) -> Option<Vec<u8>> {
+    let target = instruction.target?;
+    let bytes = &instruction.bytes;
+    match bytes.as_slice() {
+        // jcc rel8 -> jmp rel8 (mismo tamaño, mismo desplazamiento)
+        [0x70..=0x7f, rel] => Some(vec![0xeb, *rel]),
+        // jcc rel32 (0f 8x) -> jmp rel32 + nop; el jmp ocupa un byte menos
+        [0x0f, 0x80..=0x8f, ..] if bytes.len() == 6 => {
+            let rel = target.wrapping_sub(instruction.address + 5) as i64;
+            let rel = i32::try_from(rel).ok()?;
+            let mut patched = vec![0xe9];
+            patched.extend_from_slice(&rel.to_le_bytes());
+            patched.push(0x90);
+            Some(patched)
+        }
+        _ => None,
+    }
+}
+
+/// b.cond, cbz/cbnz y tbz/tbnz -> b al mismo destino
+#[cfg(not(target_arch = "x86_64"))]
+fn unconditional(instruction: &Instruction) -> Option<Vec<u8>> {
+    let word = u32::from_le_bytes(instruction.bytes.get(..4)?.try_into().ok()?);
+    let conditional = word & 0xff000010 == 0x54000000 || word & 0x7e000000 == 0x34000000 || word & 0x7e000000 == 0x36000000;
+    if !conditional {
+        return None;
+    }
+    let offset = instruction.target?.wrapping_sub(instruction.address) as i64 / 4;
+    if !(-(1 << 25)..(1 << 25)).contains(&offset) {
+        return None;
+    }
+    let branch = 0x14000000 | (offset as u32 & 0x03ff_ffff);
+    Some(branch.to_le_bytes().to_vec())
+}
@@ -1,125 +1,500 @@
+use super::error::{MemoryError, Operation};
+use super::history::{ScanHistory, ScanStep};
+use super::breakpoint::Breakpoint;
+use super::patch::Patch;
+use super::ptrace::Tracer;
 use super::region::MemoryRegion;
 use super::search::{search_pattern, search_value};
-use crate::types::SearchFilter;
+use super::snapshot::Snapshot;
+use super::region::find_region;
+use super::results::ResultStore;
+use super::source::{MemorySource, SavedMemory};
+use super::structs::StructLayout;
+use super::session::RestoreStats;
+use super::table::CheatTable;
+use super::watch::MemoryWatch;
+use crate::types::{ScanValue, SearchFilter, ValueType};
+use crate::utils::procfs::{read_stat, stat_field, ProcessInfo};
+use std::collections::HashMap;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write, Seek, SeekFrom};
 use std::error::Error;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
 
+const FILTER_WINDOW: u64 = 64 * 1024; // Lectura por bloques al filtrar resultados cercanos
+
+/// Tipo y tamaño con los que se guardó una dirección
+pub type SavedType = (ValueType, usize);
+
+#[derive(Debug, Default)]
+pub struct FilterStats {
+    pub matches: usize,
+    pub unreadable: usize, // Resultados descartados porque ya no se podían leer
+}
+
+/// De dónde sale la memoria: /proc/<pid>/mem o un volcado/core guardado (solo lectura)
+#[derive(Debug)]
+enum Backend {
+    Live(File, Liveness),
+    Saved(SavedMemory),
+    Exited(String), // El proceso terminó; guarda la búsqueda para encontrar una nueva instancia
+}
+
+/// Cómo saber si el proceso sigue vivo y es el mismo: pidfd (Linux 5.3+) o, si no hay,
+/// la hora de inicio de /proc/<pid>/stat, que cambia si el PID se reutiliza
+#[derive(Debug)]
+struct Liveness {
+    pidfd: Option<OwnedFd>,
+    start_ticks: u64,
+    query: String, // "@paquete" en Android o "/^comm$/"
+}
+
+impl Liveness {
+    fn new(pid: u32) -> Liveness {
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        let pidfd = (fd >= 0).then(|| unsafe { OwnedFd::from_raw_fd(fd as i32) });
+        let info = ProcessInfo::read(pid, &HashMap::new()).ok();
+        let query = match &info {
+            Some(ProcessInfo { android: Some(app), .. }) => format!("@{}", app.process_name()),
+            Some(info) => format!("/^{}$/", regex::escape(&info.comm)),
+            None => format!("/^{}$/", pid),
+        };
+        Liveness {
+            pidfd,
+            start_ticks: info.map_or(0, |i| i.start_ticks),
+            query,
+        }
+    }
+
+    fn is_alive(&self, pid: u32) -> bool {
+        if let Some(pidfd) = &self.pidfd {
+            // El pidfd se vuelve legible cuando el proceso termina
+            let mut poll = libc::pollfd {
+                fd: pidfd.as_raw_fd(),
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            return unsafe { libc::poll(&mut poll, 1, 0) } == 0;
+        }
+        let stat = read_stat(&format!("/proc/{}/stat", pid));
+        !stat.is_empty() && stat[0] != "Z" && stat_field(&stat, 19) == self.start_ticks
+    }
+}
 
 #[derive(Debug)]
 pub struct ProcessMemory {
     pub pid: u32,
-    mem_file: File,
+    backend: Backend,
     pub regions: Vec<MemoryRegion>,
-    pub search_results: Vec<(u64, Vec<u8>)>, // Direcciones y valores encontrados
+    pub search_results: ResultStore, // Direcciones y valores encontrados
+    pub value_type: Option<ValueType>,
+    pub filter_history: Vec<(SearchFilter, Option<Vec<u8>>)>, // Filtros aplicados desde la última búsqueda
+    pub history: ScanHistory, // Pasos anteriores para deshacer/rehacer
     saved_addresses: Vec<(u64, String)>, // Direcciones guardadas con descripción
+    saved_types: Vec<Option<SavedType>>, // Tipo y tamaño de cada una, si se conocen
+    pub struct_layouts: Vec<StructLayout>, // Layouts de estructuras de la tabla de trucos
+    pub snapshots: Vec<Snapshot>, // Instantáneas en RAM para comparar
+    pub breakpoints: Vec<Breakpoint>, // Puntos de ruptura software (se escriben solo al vigilar)
+    pub patches: Vec<Patch>, // Parches de código, aplicados o no
+    tracer: Option<Tracer>, // Hilos detenidos mientras el proceso está en pausa
+    pub reattach_offered: bool, // Ya se ofreció reconectar desde que el proceso terminó
 }
 
 impl ProcessMemory {
-    pub fn new(pid: u32) -> Result<Self, Box<dyn Error>> {
+    pub fn new(pid: u32) -> Result<Self, MemoryError> {
         let mem_path = format!("/proc/{}/mem", pid);
         let mem_file = OpenOptions::new()
             .read(true)
             .write(true)
-            .open(&mem_path)?;
+            .open(&mem_path)
+            .map_err(|e| MemoryError::from_io(e, pid, Operation::Attach, None, 0))?;
 
         let regions = MemoryRegion::parse_maps(pid)?;
+        Ok(Self::with_backend(pid, Backend::Live(mem_file, Liveness::new(pid)), regions))
+    }
+
+    /// Escaneo sin conexión sobre un volcado o core; las escrituras fallan
+    pub fn open_saved(saved: SavedMemory) -> Self {
+        let regions = saved.regions().to_vec();
+        Self::with_backend(saved.pid, Backend::Saved(saved), regions)
+    }
 
-        Ok(ProcessMemory {
+    fn with_backend(pid: u32, backend: Backend, regions: Vec<MemoryRegion>) -> Self {
+        ProcessMemory {
             pid,
-            mem_file,
+            backend,
             regions,
-            search_results: Vec::new(),
+            search_results: ResultStore::default(),
+            value_type: None,
+            filter_history: Vec::new(),
+            history: ScanHistory::default(),
             saved_addresses: Vec::new(),
-        })
+            saved_types: Vec::new(),
+            struct_layouts: Vec::new(),
+            snapshots: Vec::new(),
+            breakpoints: Vec::new(),
+            patches: Vec::new(),
+            tracer: None,
+            reattach_offered: false,
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        matches!(self.backend, Backend::Live(..))
+    }
+
+    pub fn has_exited(&self) -> bool {
+        matches!(self.backend, Backend::Exited(_))
+    }
+
+    /// Comprueba si el proceso sigue vivo; si no, pasa al estado "terminado" y devuelve false
+    pub fn check_alive(&mut self) -> bool {
+        match &self.backend {
+            Backend::Live(_, liveness) if !liveness.is_alive(self.pid) => {
+                self.backend = Backend::Exited(liveness.query.clone());
+                self.tracer = None;
+                false
+            }
+            Backend::Exited(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Búsqueda (sintaxis del selector de procesos) para encontrar una nueva instancia
+    pub fn reattach_query(&self) -> Option<&str> {
+        match &self.backend {
+            Backend::Exited(query) => Some(query),
+            _ => None,
+        }
+    }
+
+    /// Conecta con otra instancia del programa: las direcciones guardadas se remapean por
+    /// módulo + offset, y los resultados e historial de escaneo se descartan (ya no son válidos)
+    pub fn reattach(&mut self, pid: u32) -> Result<RestoreStats, MemoryError> {
+        let fresh = ProcessMemory::new(pid)?;
+        self.tracer = None;
+        let old_regions = std::mem::replace(&mut self.regions, fresh.regions);
+        let old_pid = self.pid;
+        self.pid = pid;
+        self.backend = fresh.backend;
+        self.reattach_offered = false;
+        self.search_results = ResultStore::default();
+        self.value_type = None;
+        self.filter_history.clear();
+        self.history = ScanHistory::default();
+        Ok(CheatTable::remap(self, old_pid, &old_regions))
+    }
+
+    fn exited_error(&self, operation: Operation) -> MemoryError {
+        MemoryError::ProcessGone { pid: self.pid, operation }
+    }
+
+    /// "PID 1234" o "PID 1234 (offline: <ruta>)"
+    pub fn describe(&self) -> String {
+        match &self.backend {
+            Backend::Live(..) if self.is_paused() => format!("PID {} (paused)", self.pid),
+            Backend::Live(..) => format!("PID {}", self.pid),
+            Backend::Exited(_) => format!("PID {} (exited)", self.pid),
+            Backend::Saved(saved) => format!("PID {} (offline: {})", self.pid, saved.origin),
+        }
     }
 
-    pub fn read_memory(&mut self, address: u64, size: usize) -> Result<Vec<u8>, Box<dyn Error>> {
-        self.mem_file.seek(SeekFrom::Start(address))?;
+    pub fn read_memory(&mut self, address: u64, size: usize) -> Result<Vec<u8>, MemoryError> {
+        let mem_file = match &mut self.backend {
+            Backend::Live(mem_file, _) => mem_file,
+            Backend::Saved(saved) => {
+                return saved.read_memory(address, size).map_err(|e| MemoryError::Saved {
+                    address,
+                    message: e.to_string(),
+                })
+            }
+            Backend::Exited(_) => return Err(self.exited_error(Operation::Read)),
+        };
         let mut buffer = vec![0u8; size];
-        self.mem_file.read_exact(&mut buffer)?;
-        Ok(buffer)
+        let result = mem_file
+            .seek(SeekFrom::Start(address))
+            .and_then(|_| mem_file.read_exact(&mut buffer));
+        match result {
+            Ok(_) => Ok(buffer),
+            // Un error de E/S puede deberse a que el proceso terminó: mejor decirlo claramente
+            Err(_) if !self.check_alive() => Err(self.exited_error(Operation::Read)),
+            Err(e) => Err(MemoryError::from_io(e, self.pid, Operation::Read, Some(address), size)),
+        }
+    }
+
+    pub fn write_memory(&mut self, address: u64, data: &[u8]) -> Result<(), MemoryError> {
+        let mem_file = match &mut self.backend {
+            Backend::Live(mem_file, _) => mem_file,
+            Backend::Saved(_) => return Err(MemoryError::ReadOnly { address }),
+            Backend::Exited(_) => return Err(self.exited_error(Operation::Write)),
+        };
+        let result = mem_file
+            .seek(SeekFrom::Start(address))
+            .and_then(|_| mem_file.write_all(data))
+            .and_then(|_| mem_file.flush());
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) if !self.check_alive() => Err(self.exited_error(Operation::Write)),
+            Err(e) => Err(MemoryError::from_io(e, self.pid, Operation::Write, Some(address), data.len())),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.tracer.is_some()
     }
 
-    pub fn write_memory(&mut self, address: u64, data: &[u8]) -> Result<(), Box<dyn Error>> {
-        self.mem_file.seek(SeekFrom::Start(address))?;
-        self.mem_file.write_all(data)?;
-        self.mem_file.flush()?;
+    /// Hilos detenidos si el proceso está en pausa
+    pub fn tracer(&self) -> Option<&Tracer> {
+        self.tracer.as_ref()
+    }
+
+    pub fn tracer_mut(&mut self) -> Option<&mut Tracer> {
+        self.tracer.as_mut()
+    }
+
+    /// Detiene todos los hilos con ptrace hasta `resume`; devuelve cuántos hay detenidos
+    pub fn pause(&mut self) -> Result<usize, MemoryError> {
+        match &self.backend {
+            Backend::Live(..) => {}
+            Backend::Saved(_) => return Err(MemoryError::Offline { operation: Operation::Trace }),
+            Backend::Exited(_) => return Err(self.exited_error(Operation::Trace)),
+        }
+        if self.tracer.is_none() {
+            self.tracer = Some(Tracer::stop(self.pid)?);
+        }
+        Ok(self.tracer.as_ref().map_or(0, |t| t.threads().len()))
+    }
+
+    /// Suelta los hilos detenidos; devuelve false si no estaba en pausa
+    pub fn resume(&mut self) -> bool {
+        self.tracer.take().is_some()
+    }
+
+    /// Escribe varios valores con el proceso en pausa, para que ningún hilo vea un estado a
+    /// medias. Si una escritura falla se restauran las anteriores; si ya estaba en pausa, sigue.
+    pub fn write_batch(&mut self, writes: &[(u64, Vec<u8>)]) -> Result<(), MemoryError> {
+        let paused_here = !self.is_paused();
+        self.pause()?;
+        let result = self.write_all_or_restore(writes);
+        if paused_here {
+            self.resume();
+        }
+        result
+    }
+
+    fn write_all_or_restore(&mut self, writes: &[(u64, Vec<u8>)]) -> Result<(), MemoryError> {
+        let mut originals = Vec::with_capacity(writes.len());
+        for (address, data) in writes {
+            originals.push(self.read_memory(*address, data.len())?);
+        }
+        for (i, (address, data)) in writes.iter().enumerate() {
+            if let Err(e) = self.write_memory(*address, data) {
+                for ((address, _), original) in writes[..i].iter().zip(&originals).rev() {
+                    let _ = self.write_memory(*address, original);
+                }
+                return Err(e);
+            }
+        }
         Ok(())
     }
 
-    pub fn search_pattern(&mut self, pattern: &[u8]) -> Result<Vec<u64>, Box<dyn Error>> {
-        let matches = search_pattern(self, pattern)?;
-        self.search_results = matches.iter().map(|&addr| (addr, pattern.to_vec())).collect();
-        Ok(matches)
+    /// Relee /proc/<pid>/maps; sin conexión las regiones no cambian
+    pub fn refresh_regions(&mut self) -> Result<(), MemoryError> {
+        if self.is_live() {
+            self.regions = MemoryRegion::parse_maps(self.pid)?;
+        }
+        Ok(())
     }
 
-    pub fn search_value<T: bytemuck::Pod + Copy>(
+    pub fn search_pattern(
         &mut self,
-        value: T,
-    ) -> Result<Vec<u64>, Box<dyn Error>> {
+        pattern: &[u8],
+        value_type: ValueType,
+    ) -> Result<usize, MemoryError> {
+        let matches = search_pattern(self, pattern)?;
+        let count = matches.len();
+        self.load_scan_step(ScanStep {
+            results: matches,
+            value_type: Some(value_type),
+            filter_history: Vec::new(),
+        });
+        Ok(count)
+    }
+
+    pub fn search_value<T: ScanValue>(&mut self, value: T) -> Result<usize, MemoryError> {
         let matches = search_value(self, value)?;
-        let bytes = bytemuck::bytes_of(&value).to_vec();
-        self.search_results = matches.iter().map(|&addr| (addr, bytes.clone())).collect();
-        Ok(matches)
+        let count = matches.len();
+        self.load_scan_step(ScanStep {
+            results: matches,
+            value_type: Some(T::TYPE),
+            filter_history: Vec::new(),
+        });
+        Ok(count)
+    }
+
+    fn take_scan_step(&mut self) -> ScanStep {
+        ScanStep {
+            results: std::mem::take(&mut self.search_results),
+            value_type: self.value_type,
+            filter_history: std::mem::take(&mut self.filter_history),
+        }
+    }
+
+    fn set_scan_step(&mut self, step: ScanStep) {
+        self.search_results = step.results;
+        self.value_type = step.value_type;
+        self.filter_history = step.filter_history;
+    }
+
+    /// Reemplaza el estado del escaneo guardando el anterior en el historial
+    pub fn load_scan_step(&mut self, step: ScanStep) {
+        let previous = self.take_scan_step();
+        if !previous.results.is_empty() {
+            self.history.push(previous);
+        }
+        self.set_scan_step(step);
+    }
+
+    pub fn undo_scan(&mut self) -> bool {
+        let current = self.take_scan_step();
+        match self.history.undo(current) {
+            Ok(previous) => {
+                self.set_scan_step(previous);
+                true
+            }
+            Err(current) => {
+                self.set_scan_step(current);
+                false
+            }
+        }
+    }
+
+    pub fn redo_scan(&mut self) -> bool {
+        let current = self.take_scan_step();
+        match self.history.redo(current) {
+            Ok(next) => {
+                self.set_scan_step(next);
+                true
+            }
+            Err(current) => {
+                self.set_scan_step(current);
+                false
+            }
+        }
     }
 
     pub fn filter_results(
         &mut self,
         filter: SearchFilter,
         value: Option<Vec<u8>>,
-    ) -> Result<Vec<u64>, Box<dyn Error>> {
-        let mut new_results = Vec::new();
-        let search_results = self.search_results.clone(); // Clone to avoid borrowing conflicts
-        for (addr, old_value) in search_results.iter() {
-            let current_value = self.read_memory(*addr, old_value.len())?;
-            match filter {
-                SearchFilter::Exact => {
-                    if let Some(ref val) = value {
-                        if current_value == *val {
-                            new_results.push((*addr, current_value));
-                        }
-                    }
-                }
-                SearchFilter::Changed => {
-                    if current_value != *old_value {
-                        new_results.push((*addr, current_value));
-                    }
-                }
-                SearchFilter::Unchanged => {
-                    if current_value == *old_value {
-                        new_results.push((*addr, current_value));
-                    }
-                }
-                SearchFilter::Increased => {
-                    if current_value > *old_value {
-                        new_results.push((*addr, current_value));
-                    }
-                }
-                SearchFilter::Decreased => {
-                    if current_value < *old_value {
-                        new_results.push((*addr, current_value));
-                    }
-                }
-                SearchFilter::Range(min, max) => {
-                    if let Ok(val) = bytemuck::try_pod_read_unaligned::<f64>(&current_value) {
-                        if val >= min && val <= max {
-                            new_results.push((*addr, current_value));
-                        }
+    ) -> Result<FilterStats, MemoryError> {
+        let previous = self.take_scan_step();
+        match self.apply_filter(&previous.results, previous.value_type, &filter, value.as_deref()) {
+            Ok((new_results, unreadable)) => {
+                self.search_results = new_results;
+                self.value_type = previous.value_type;
+                self.filter_history = previous.filter_history.clone();
+                self.filter_history.push((filter, value));
+                self.history.push(previous);
+                Ok(FilterStats {
+                    matches: self.search_results.len(),
+                    unreadable,
+                })
+            }
+            Err(e) => {
+                self.set_scan_step(previous);
+                Err(e)
+            }
+        }
+    }
+
+    fn apply_filter(
+        &mut self,
+        search_results: &ResultStore,
+        value_type: Option<ValueType>,
+        filter: &SearchFilter,
+        value: Option<&[u8]>,
+    ) -> Result<(ResultStore, usize), MemoryError> {
+        let scan_error = |address, source| MemoryError::Io {
+            operation: Operation::Scan,
+            address,
+            source,
+        };
+        let size = search_results.value_size();
+        let mut new_results = ResultStore::new(size);
+        let mut window_start = 0u64;
+        let mut window: Vec<u8> = Vec::new();
+        let mut unreadable = 0;
+
+        for entry in search_results.iter() {
+            let (addr, old_value) = entry.map_err(|e| scan_error(None, e))?;
+            let end = addr + size as u64;
+            if addr < window_start || end > window_start + window.len() as u64 {
+                // Una página desmapeada desde el escaneo solo descarta sus resultados
+                match self.read_window(addr, size) {
+                    Ok(data) => window = data,
+                    Err(e @ MemoryError::ProcessGone { .. }) => return Err(e),
+                    Err(_) => {
+                        unreadable += 1;
+                        window.clear();
+                        continue;
                     }
                 }
+                window_start = addr;
+            }
+            let offset = (addr - window_start) as usize;
+            let current_value = &window[offset..offset + size];
+
+            if filter.matches(value_type, &old_value, current_value, value) {
+                new_results.push(addr, current_value).map_err(|e| scan_error(Some(addr), e))?;
             }
         }
-        self.search_results = new_results;
-        Ok(self.search_results.iter().map(|(addr, _)| *addr).collect())
+        Ok((new_results, unreadable))
     }
 
-    pub fn save_address(&mut self, address: u64, description: String) {
+    /// Lee un bloque a partir de `address` sin salir de su región; si falla, solo `size` bytes
+    fn read_window(&mut self, address: u64, size: usize) -> Result<Vec<u8>, MemoryError> {
+        let window_end = find_region(&self.regions, address)
+            .map(|r| r.end.min(address + FILTER_WINDOW))
+            .unwrap_or(address);
+        if window_end > address + size as u64 {
+            if let Ok(data) = self.read_memory(address, (window_end - address) as usize) {
+                return Ok(data);
+            }
+        }
+        self.read_memory(address, size)
+    }
+
+    pub fn watch(&mut self, start: u64, len: usize) -> Result<MemoryWatch, MemoryError> {
+        MemoryWatch::new(self, start, len)
+    }
+
+    /// Guarda una dirección; `value` es el tipo y el tamaño con que se muestra su valor
+    pub fn save_address(&mut self, address: u64, description: String, value: Option<SavedType>) {
         self.saved_addresses.push((address, description));
+        self.saved_types.push(value);
     }
 
     pub fn get_saved_addresses(&self) -> &[(u64, String)] {
         &self.saved_addresses
     }
+
+    pub fn saved_type(&self, index: usize) -> Option<SavedType> {
+        self.saved_types.get(index).copied().flatten()
+    }
+
+    pub fn clear_saved_addresses(&mut self) {
+        self.saved_addresses.clear();
+        self.saved_types.clear();
+    }
 }
 
+impl MemorySource for ProcessMemory {
+    fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    fn read_memory(&mut self, address: u64, size: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(ProcessMemory::read_memory(self, address, size)?)
+    }
+}
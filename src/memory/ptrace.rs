@@ -0,0 +1,353 @@
+use super::debugreg::{self, Watchpoint, STEP_OVER_HIT};
+use super::error::{MemoryError, Operation};
+use crate::utils::procfs::list_threads;
+use std::collections::HashSet;
+use std::io;
+
+#[cfg(target_arch = "x86_64")]
+pub const GP_REGS_SIZE: usize = 27 * 8; // user_regs_struct
+#[cfg(target_arch = "aarch64")]
+pub const GP_REGS_SIZE: usize = 34 * 8; // user_pt_regs
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub const GP_REGS_SIZE: usize = 0;
+
+pub const NT_PRSTATUS: u32 = 1;
+
+/// Un hilo seguido con ptrace; al continuarlo o soltarlo se le entrega `signal`
+#[derive(Debug)]
+pub struct TracedThread {
+    pub tid: i32,
+    stopped: bool,
+    signal: i32,
+}
+
+/// Lo que pasó en el proceso mientras se ejecutaba bajo ptrace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEvent {
+    Trap(i32), // SIGTRAP (watchpoint o punto de ruptura); el hilo queda detenido
+    ThreadExited(i32),
+}
+
+/// Hilos del proceso seguidos con PTRACE_SEIZE. Se crea con todos detenidos (pausa);
+/// `run` los deja correr avisando de eventos con `poll`, e `interrupt_all` los vuelve a
+/// parar. Al destruirse se quitan los watchpoints y se sueltan (PTRACE_DETACH).
+#[derive(Debug)]
+pub struct Tracer {
+    threads: Vec<TracedThread>,
+    watchpoints: Vec<Watchpoint>,
+}
+
+impl Tracer {
+    /// Detiene todos los hilos de /proc/<pid>/task. Falla si no se puede detener el hilo
+    /// principal; los hilos que terminan mientras tanto se ignoran.
+    pub fn stop(pid: u32) -> Result<Tracer, MemoryError> {
+        let mut tracer = Tracer {
+            threads: Vec::new(),
+            watchpoints: Vec::new(),
+        };
+        let mut seen = HashSet::new();
+        // Se repite por si aparecen hilos nuevos mientras se detienen los demás
+        loop {
+            let mut new_threads = false;
+            for tid in list_threads(pid) {
+                if !seen.insert(tid) {
+                    continue;
+                }
+                new_threads = true;
+                match seize_and_interrupt(tid) {
+                    Ok(signal) => tracer.threads.push(TracedThread { tid, stopped: true, signal }),
+                    Err(e) if tid == pid as i32 => {
+                        return Err(MemoryError::from_io(e, pid, Operation::Trace, None, 0));
+                    }
+                    Err(_) => {}
+                }
+            }
+            if !new_threads {
+                break;
+            }
+        }
+        if tracer.threads.is_empty() {
+            return Err(MemoryError::ProcessGone { pid, operation: Operation::Trace });
+        }
+        Ok(tracer)
+    }
+
+    pub fn threads(&self) -> &[TracedThread] {
+        &self.threads
+    }
+
+    pub fn is_stopped(&self, tid: i32) -> bool {
+        self.threads.iter().any(|t| t.tid == tid && t.stopped)
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Programa los watchpoints en todos los hilos (deben estar detenidos); los hilos
+    /// que se creen después los reciben al arrancar
+    pub fn set_watchpoints(&mut self, watchpoints: Vec<Watchpoint>) -> io::Result<()> {
+        if let Some(thread) = self.threads.first() {
+            let slots = debugreg::slots(thread.tid);
+            if watchpoints.len() > slots {
+                return Err(io::Error::other(format!("Only {} hardware watchpoints available", slots)));
+            }
+        }
+        for thread in self.threads.iter().filter(|t| t.stopped) {
+            if let Err(e) = debugreg::apply(thread.tid, &watchpoints) {
+                // No dejar unos hilos con watchpoints y otros sin ellos
+                for thread in self.threads.iter().filter(|t| t.stopped) {
+                    let _ = debugreg::apply(thread.tid, &self.watchpoints);
+                }
+                return Err(e);
+            }
+        }
+        self.watchpoints = watchpoints;
+        Ok(())
+    }
+
+    /// Deja correr todos los hilos; los hilos nuevos se siguen (PTRACE_O_TRACECLONE)
+    pub fn run(&mut self) {
+        let tids: Vec<i32> = self.threads.iter().filter(|t| t.stopped).map(|t| t.tid).collect();
+        for tid in tids {
+            unsafe {
+                libc::ptrace(libc::PTRACE_SETOPTIONS, tid, 0, libc::PTRACE_O_TRACECLONE as libc::c_long);
+            }
+            self.cont(tid);
+        }
+    }
+
+    /// Continúa un hilo detenido entregándole su señal pendiente
+    pub fn cont(&mut self, tid: i32) {
+        if let Some(thread) = self.threads.iter_mut().find(|t| t.tid == tid && t.stopped) {
+            unsafe {
+                libc::ptrace(libc::PTRACE_CONT, tid, 0, thread.signal as libc::c_long);
+            }
+            thread.stopped = false;
+            thread.signal = 0;
+        }
+    }
+
+    /// El SIGTRAP no era nuestro: se le entregará al hilo al continuarlo o soltarlo
+    pub fn forward_trap(&mut self, tid: i32) {
+        if let Some(thread) = self.threads.iter_mut().find(|t| t.tid == tid) {
+            thread.signal = libc::SIGTRAP;
+        }
+    }
+
+    /// Continúa tras un watchpoint. En aarch64 el acceso aún no ocurrió: se ejecuta esa
+    /// instrucción paso a paso sin los watchpoints y luego se vuelven a poner.
+    pub fn cont_after_watch(&mut self, tid: i32) {
+        if STEP_OVER_HIT {
+            let _ = debugreg::apply(tid, &[]);
+            if !self.single_step(tid) {
+                return;
+            }
+            let _ = debugreg::apply(tid, &self.watchpoints);
+        }
+        self.cont(tid);
+    }
+
+    /// Ejecuta una sola instrucción del hilo (detenido) y espera a que vuelva a parar.
+    /// Devuelve false si el hilo terminó entretanto.
+    pub fn single_step(&mut self, tid: i32) -> bool {
+        let mut status = 0;
+        let waited = unsafe {
+            libc::ptrace(libc::PTRACE_SINGLESTEP, tid, 0, 0);
+            libc::waitpid(tid, &mut status, libc::__WALL)
+        };
+        if waited != tid || !libc::WIFSTOPPED(status) {
+            self.threads.retain(|t| t.tid != tid);
+            return false;
+        }
+        // Una señal que llegó durante el paso se entrega al continuar
+        let signal = libc::WSTOPSIG(status);
+        if signal != libc::SIGTRAP && status >> 16 == 0 {
+            self.mark_stopped(tid, signal);
+        }
+        true
+    }
+
+    /// Siguiente evento sin bloquear. Las demás paradas (señales, hilos nuevos) se
+    /// atienden aquí mismo y el hilo sigue.
+    pub fn poll(&mut self) -> Option<TraceEvent> {
+        loop {
+            let mut status = 0;
+            let tid = unsafe { libc::waitpid(-1, &mut status, libc::__WALL | libc::WNOHANG) };
+            if tid <= 0 {
+                return None;
+            }
+            if libc::WIFEXITED(status) || libc::WIFSIGNALED(status) {
+                self.threads.retain(|t| t.tid != tid);
+                return Some(TraceEvent::ThreadExited(tid));
+            }
+            if !libc::WIFSTOPPED(status) {
+                continue;
+            }
+            if !self.threads.iter().any(|t| t.tid == tid) {
+                self.threads.push(TracedThread { tid, stopped: true, signal: 0 });
+            }
+            let signal = libc::WSTOPSIG(status);
+            match status >> 16 {
+                libc::PTRACE_EVENT_CLONE => {
+                    self.mark_stopped(tid, 0);
+                    self.add_clone_child(tid);
+                    self.cont(tid);
+                }
+                libc::PTRACE_EVENT_STOP => {
+                    // Primera parada de un hilo nuevo (o parada de grupo): se le ponen los
+                    // watchpoints, que no se heredan, y sigue
+                    self.mark_stopped(tid, 0);
+                    let _ = debugreg::apply(tid, &self.watchpoints);
+                    self.cont(tid);
+                }
+                0 if signal == libc::SIGTRAP => {
+                    self.mark_stopped(tid, 0);
+                    return Some(TraceEvent::Trap(tid));
+                }
+                _ => {
+                    self.mark_stopped(tid, signal);
+                    self.cont(tid);
+                }
+            }
+        }
+    }
+
+    /// Vuelve a detener todos los hilos; devuelve los que pararon por SIGTRAP mientras
+    /// tanto (quedan sin señal pendiente; `forward_trap` la devuelve si no era nuestra)
+    pub fn interrupt_all(&mut self) -> Vec<i32> {
+        let mut traps = Vec::new();
+        let mut interrupted = HashSet::new();
+        while self.threads.iter().any(|t| !t.stopped) {
+            for thread in self.threads.iter().filter(|t| !t.stopped) {
+                if interrupted.insert(thread.tid) {
+                    unsafe {
+                        libc::ptrace(libc::PTRACE_INTERRUPT, thread.tid, 0, 0);
+                    }
+                }
+            }
+            let mut status = 0;
+            let tid = unsafe { libc::waitpid(-1, &mut status, libc::__WALL) };
+            if tid <= 0 {
+                // No queda nadie a quien esperar: los hilos que faltan ya no existen
+                self.threads.retain(|t| t.stopped);
+                break;
+            }
+            if libc::WIFEXITED(status) || libc::WIFSIGNALED(status) {
+                self.threads.retain(|t| t.tid != tid);
+                continue;
+            }
+            if !libc::WIFSTOPPED(status) {
+                continue;
+            }
+            if !self.threads.iter().any(|t| t.tid == tid) {
+                self.threads.push(TracedThread { tid, stopped: true, signal: 0 });
+            }
+            let signal = libc::WSTOPSIG(status);
+            match status >> 16 {
+                libc::PTRACE_EVENT_CLONE => {
+                    self.mark_stopped(tid, 0);
+                    self.add_clone_child(tid);
+                }
+                0 if signal == libc::SIGTRAP => {
+                    self.mark_stopped(tid, 0);
+                    traps.push(tid);
+                }
+                0 => self.mark_stopped(tid, signal),
+                _ => self.mark_stopped(tid, 0),
+            }
+        }
+        traps
+    }
+
+    /// El hilo creado por clone() ya está seguido; se apunta hasta que llegue su primera parada
+    fn add_clone_child(&mut self, parent: i32) {
+        let mut child: libc::c_ulong = 0;
+        unsafe {
+            libc::ptrace(libc::PTRACE_GETEVENTMSG, parent, 0, &mut child as *mut libc::c_ulong);
+        }
+        let child = child as i32;
+        if child > 0 && !self.threads.iter().any(|t| t.tid == child) {
+            self.threads.push(TracedThread { tid: child, stopped: false, signal: 0 });
+        }
+    }
+
+    fn mark_stopped(&mut self, tid: i32, signal: i32) {
+        if let Some(thread) = self.threads.iter_mut().find(|t| t.tid == tid) {
+            thread.stopped = true;
+            if signal != 0 {
+                thread.signal = signal;
+            }
+        }
+    }
+}
+
+impl Drop for Tracer {
+    fn drop(&mut self) {
+        self.interrupt_all();
+        for thread in &self.threads {
+            // Un watchpoint olvidado mataría al proceso con SIGTRAP después de soltarlo
+            if !self.watchpoints.is_empty() {
+                let _ = debugreg::apply(thread.tid, &[]);
+            }
+            unsafe {
+                libc::ptrace(libc::PTRACE_DETACH, thread.tid, 0, thread.signal as libc::c_long);
+            }
+        }
+    }
+}
+
+/// Devuelve la señal a reinyectar al soltar el hilo
+fn seize_and_interrupt(tid: i32) -> io::Result<i32> {
+    unsafe {
+        if libc::ptrace(libc::PTRACE_SEIZE, tid, 0, 0) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if libc::ptrace(libc::PTRACE_INTERRUPT, tid, 0, 0) != 0 {
+            let error = io::Error::last_os_error();
+            libc::ptrace(libc::PTRACE_DETACH, tid, 0, 0);
+            return Err(error);
+        }
+        let mut status = 0;
+        if libc::waitpid(tid, &mut status, libc::__WALL) != tid || !libc::WIFSTOPPED(status) {
+            return Err(io::Error::from_raw_os_error(libc::ESRCH));
+        }
+        // Parada por PTRACE_INTERRUPT (evento STOP) o una señal que hay que devolver al hilo
+        let event = status >> 16;
+        Ok(if event == libc::PTRACE_EVENT_STOP { 0 } else { libc::WSTOPSIG(status) })
+    }
+}
+
+/// Registros generales de un hilo detenido (NT_PRSTATUS), para el core
+pub fn read_registers(tid: i32) -> Option<Vec<u8>> {
+    let mut regs = get_regset(tid, NT_PRSTATUS, GP_REGS_SIZE).ok()?;
+    regs.resize(GP_REGS_SIZE, 0);
+    Some(regs)
+}
+
+/// PTRACE_GETREGSET: hasta `size` bytes del conjunto de registros `kind` (NT_*)
+pub fn get_regset(tid: i32, kind: u32, size: usize) -> io::Result<Vec<u8>> {
+    let mut regs = vec![0u8; size];
+    let mut iov = libc::iovec {
+        iov_base: regs.as_mut_ptr() as *mut libc::c_void,
+        iov_len: regs.len(),
+    };
+    let result = unsafe { libc::ptrace(libc::PTRACE_GETREGSET, tid, kind as usize, &mut iov as *mut libc::iovec) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    regs.truncate(iov.iov_len);
+    Ok(regs)
+}
+
+pub fn set_regset(tid: i32, kind: u32, regs: &[u8]) -> io::Result<()> {
+    let mut iov = libc::iovec {
+        iov_base: regs.as_ptr() as *mut libc::c_void,
+        iov_len: regs.len(),
+    };
+    let result = unsafe { libc::ptrace(libc::PTRACE_SETREGSET, tid, kind as usize, &mut iov as *mut libc::iovec) };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
@@ -1,31 +1,35 @@
+use super::error::{MemoryError, Operation};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::error::Error;
 
 #[derive(Debug, Clone)]
 pub struct MemoryRegion {
     pub start: u64,
     pub end: u64,
     pub permissions: String,
+    pub offset: u64, // Offset dentro del fichero mapeado
     pub pathname: String,
 }
 
 impl MemoryRegion {
-    pub fn parse_maps(pid: u32) -> Result<Vec<MemoryRegion>, Box<dyn Error>> {
+    pub fn parse_maps(pid: u32) -> Result<Vec<MemoryRegion>, MemoryError> {
+        let io_error = |e| MemoryError::from_io(e, pid, Operation::ReadMaps, None, 0);
         let maps_path = format!("/proc/{}/maps", pid);
-        let file = File::open(maps_path)?;
+        let file = File::open(maps_path).map_err(io_error)?;
         let reader = BufReader::new(file);
         let mut regions = Vec::new();
 
         for line in reader.lines() {
-            let line = line?;
+            let line = line.map_err(io_error)?;
+            let bad_line = || MemoryError::Parse { what: "maps line", line: line.clone() };
             let parts: Vec<&str> = line.split_whitespace().collect();
             if parts.len() >= 2 {
                 let addr_range: Vec<&str> = parts[0].split('-').collect();
                 if addr_range.len() == 2 {
-                    let start = u64::from_str_radix(addr_range[0], 16)?;
-                    let end = u64::from_str_radix(addr_range[1], 16)?;
+                    let start = u64::from_str_radix(addr_range[0], 16).map_err(|_| bad_line())?;
+                    let end = u64::from_str_radix(addr_range[1], 16).map_err(|_| bad_line())?;
                     let permissions = parts[1].to_string();
+                    let offset = parts.get(2).and_then(|o| u64::from_str_radix(o, 16).ok()).unwrap_or(0);
                     let pathname = if parts.len() > 5 {
                         parts[5..].join(" ")
                     } else {
@@ -36,6 +40,7 @@ impl MemoryRegion {
                         start,
                         end,
                         permissions,
+                        offset,
                         pathname,
                     });
                 }
@@ -45,3 +50,48 @@ impl MemoryRegion {
         Ok(regions)
     }
 }
+
+impl MemoryRegion {
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.start && address < self.end
+    }
+
+    /// Regiones respaldadas por un archivo (librerías, ejecutable), no [heap], [stack], etc.
+    pub fn is_module(&self) -> bool {
+        !self.pathname.is_empty() && !self.pathname.starts_with('[')
+    }
+}
+
+pub fn find_region(regions: &[MemoryRegion], address: u64) -> Option<&MemoryRegion> {
+    regions.iter().find(|r| r.contains(address))
+}
+
+/// Dirección de carga de un módulo: el inicio de su primera región mapeada
+pub fn module_base(regions: &[MemoryRegion], pathname: &str) -> Option<u64> {
+    regions
+        .iter()
+        .filter(|r| r.pathname == pathname)
+        .map(|r| r.start)
+        .min()
+}
+
+/// Convierte una dirección absoluta en (módulo, offset) para sobrevivir a ASLR
+pub fn module_offset(regions: &[MemoryRegion], address: u64) -> Option<(String, u64)> {
+    let region = find_region(regions, address)?;
+    if !region.is_module() {
+        return None;
+    }
+    let base = module_base(regions, &region.pathname)?;
+    Some((region.pathname.clone(), address - base))
+}
+
+/// Dirección de código como "libc.so.6+0x1234" (o "[vdso]+0x10"), o en hexadecimal si no está mapeada
+pub fn code_location(regions: &[MemoryRegion], address: u64) -> String {
+    match (module_offset(regions, address), find_region(regions, address)) {
+        (Some((module, offset)), _) => format!("{}+0x{:x}", module.rsplit('/').next().unwrap_or(&module), offset),
+        (None, Some(region)) if !region.pathname.is_empty() => {
+            format!("{}+0x{:x}", region.pathname, address - region.start)
+        }
+        _ => format!("0x{:x}", address),
+    }
+}
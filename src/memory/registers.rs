@@ -0,0 +1,208 @@
+use super::error::{MemoryError, Operation};
+use super::ptrace::{get_regset, set_regset, GP_REGS_SIZE, NT_PRSTATUS};
+
+const NT_PRFPREG: u32 = 2;
+
+// Registros generales: (nombre, índice de 8 bytes en user_regs_struct), en orden de pantalla
+#[cfg(target_arch = "x86_64")]
+const GP_REGISTERS: &[(&str, usize)] = &[
+    ("rax", 10), ("rbx", 5), ("rcx", 11), ("rdx", 12), ("rsi", 13), ("rdi", 14), ("rbp", 4), ("rsp", 19),
+    ("r8", 9), ("r9", 8), ("r10", 7), ("r11", 6), ("r12", 3), ("r13", 2), ("r14", 1), ("r15", 0),
+    ("rip", 16), ("eflags", 18), ("orig_rax", 15), ("fs_base", 21), ("gs_base", 22),
+    ("cs", 17), ("ss", 20), ("ds", 23), ("es", 24), ("fs", 25), ("gs", 26),
+];
+#[cfg(target_arch = "x86_64")]
+pub const PC_INDEX: usize = 16;
+#[cfg(target_arch = "x86_64")]
+const SP_INDEX: usize = 19;
+#[cfg(target_arch = "x86_64")]
+const FP_REGS_SIZE: usize = 512; // user_fpregs_struct (FXSAVE)
+
+// user_pt_regs: x0-x30, sp, pc, pstate
+#[cfg(target_arch = "aarch64")]
+const GP_REGISTERS: &[(&str, usize)] = &[
+    ("x0", 0), ("x1", 1), ("x2", 2), ("x3", 3), ("x4", 4), ("x5", 5), ("x6", 6), ("x7", 7),
+    ("x8", 8), ("x9", 9), ("x10", 10), ("x11", 11), ("x12", 12), ("x13", 13), ("x14", 14), ("x15", 15),
+    ("x16", 16), ("x17", 17), ("x18", 18), ("x19", 19), ("x20", 20), ("x21", 21), ("x22", 22), ("x23", 23),
+    ("x24", 24), ("x25", 25), ("x26", 26), ("x27", 27), ("x28", 28), ("x29", 29), ("x30", 30),
+    ("sp", 31), ("pc", 32), ("pstate", 33),
+];
+#[cfg(target_arch = "aarch64")]
+pub const PC_INDEX: usize = 32;
+#[cfg(target_arch = "aarch64")]
+const SP_INDEX: usize = 31;
+#[cfg(target_arch = "aarch64")]
+const FP_REGS_SIZE: usize = 528; // user_fpsimd_state: v0-v31, fpsr, fpcr
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const GP_REGISTERS: &[(&str, usize)] = &[];
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub const PC_INDEX: usize = 0;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const SP_INDEX: usize = 0;
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const FP_REGS_SIZE: usize = 0;
+
+/// Registro de coma flotante / vectorial: nombre, offset y tamaño dentro del bloque FP
+#[derive(Debug, Clone)]
+pub struct FpRegister {
+    pub name: String,
+    offset: usize,
+    pub size: usize,
+}
+
+#[cfg(target_arch = "x86_64")]
+fn fp_layout() -> Vec<FpRegister> {
+    let field = |name: String, offset, size| FpRegister { name, offset, size };
+    let mut layout = vec![
+        field("fcw".to_string(), 0, 2),
+        field("fsw".to_string(), 2, 2),
+        field("mxcsr".to_string(), 24, 4),
+    ];
+    layout.extend((0..8).map(|i| field(format!("st{}", i), 32 + 16 * i, 10)));
+    layout.extend((0..16).map(|i| field(format!("xmm{}", i), 160 + 16 * i, 16)));
+    layout
+}
+
+#[cfg(target_arch = "aarch64")]
+fn fp_layout() -> Vec<FpRegister> {
+    let field = |name: String, offset, size| FpRegister { name, offset, size };
+    let mut layout: Vec<FpRegister> = (0..32).map(|i| field(format!("v{}", i), 16 * i, 16)).collect();
+    layout.push(field("fpsr".to_string(), 512, 4));
+    layout.push(field("fpcr".to_string(), 516, 4));
+    layout
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn fp_layout() -> Vec<FpRegister> {
+    Vec::new()
+}
+
+/// Registros de un hilo detenido con ptrace; se modifican en memoria y se aplican con `write`
+#[derive(Debug, Clone)]
+pub struct Registers {
+    pub tid: i32,
+    gp: Vec<u8>,
+    fp: Option<Vec<u8>>, // Algunos kernels o hilos no dan el bloque FP
+}
+
+impl Registers {
+    pub fn read(tid: i32) -> Result<Registers, MemoryError> {
+        if GP_REGS_SIZE == 0 {
+            return Err(unsupported());
+        }
+        let error = |e| MemoryError::from_io(e, tid as u32, Operation::Trace, None, 0);
+        let mut gp = get_regset(tid, NT_PRSTATUS, GP_REGS_SIZE).map_err(error)?;
+        gp.resize(GP_REGS_SIZE, 0);
+        let fp = get_regset(tid, NT_PRFPREG, FP_REGS_SIZE).ok().filter(|fp| fp.len() == FP_REGS_SIZE);
+        Ok(Registers { tid, gp, fp })
+    }
+
+    pub fn write(&self) -> Result<(), MemoryError> {
+        let error = |e| MemoryError::from_io(e, self.tid as u32, Operation::Trace, None, 0);
+        set_regset(self.tid, NT_PRSTATUS, &self.gp).map_err(error)?;
+        if let Some(fp) = &self.fp {
+            set_regset(self.tid, NT_PRFPREG, fp).map_err(error)?;
+        }
+        Ok(())
+    }
+
+    fn slot(&self, index: usize) -> u64 {
+        u64::from_ne_bytes(self.gp[index * 8..index * 8 + 8].try_into().unwrap())
+    }
+
+    fn set_slot(&mut self, index: usize, value: u64) {
+        self.gp[index * 8..index * 8 + 8].copy_from_slice(&value.to_ne_bytes());
+    }
+
+    /// Contador de programa (rip / pc)
+    pub fn pc(&self) -> u64 {
+        self.slot(PC_INDEX)
+    }
+
+    pub fn set_pc(&mut self, value: u64) {
+        self.set_slot(PC_INDEX, value);
+    }
+
+    pub fn sp(&self) -> u64 {
+        self.slot(SP_INDEX)
+    }
+
+    /// ¿Es `name` un registro general de esta arquitectura?
+    pub fn is_general(name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        GP_REGISTERS.iter().any(|(n, _)| *n == name)
+    }
+
+    /// Registros generales en orden de pantalla
+    pub fn general(&self) -> Vec<(&'static str, u64)> {
+        GP_REGISTERS.iter().map(|&(name, index)| (name, self.slot(index))).collect()
+    }
+
+    /// Registro general por nombre (para las condiciones de los puntos de ruptura)
+    pub fn get(&self, name: &str) -> Option<u64> {
+        let name = name.to_ascii_lowercase();
+        GP_REGISTERS.iter().find(|(n, _)| *n == name).map(|&(_, index)| self.slot(index))
+    }
+
+    /// Registros FP/vectoriales con sus bytes, o vacío si no hay bloque FP
+    pub fn floating(&self) -> Vec<(FpRegister, &[u8])> {
+        let Some(fp) = &self.fp else {
+            return Vec::new();
+        };
+        fp_layout()
+            .into_iter()
+            .map(|reg| {
+                let bytes = &fp[reg.offset..reg.offset + reg.size];
+                (reg, bytes)
+            })
+            .collect()
+    }
+
+    /// Cambia un registro: generales con un entero (decimal, negativo o 0x..); FP con 0x.. o,
+    /// en el carril bajo, "f32:1.5" / "f64:1.5"
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let name = name.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if let Some(&(_, index)) = GP_REGISTERS.iter().find(|(n, _)| *n == name) {
+            let number = parse_integer(value).ok_or_else(|| format!("Invalid value for {}: {}", name, value))?;
+            self.set_slot(index, number as u64);
+            return Ok(());
+        }
+        let reg = fp_layout()
+            .into_iter()
+            .find(|r| r.name == name)
+            .ok_or_else(|| format!("Unknown register: {}", name))?;
+        let fp = self.fp.as_mut().ok_or("FP registers are not available for this thread")?;
+        let bytes = match (value.strip_prefix("f32:"), value.strip_prefix("f64:")) {
+            (Some(float), _) if reg.size >= 4 => {
+                float.parse::<f32>().map_err(|_| format!("Invalid float: {}", float))?.to_le_bytes().to_vec()
+            }
+            (_, Some(float)) if reg.size >= 8 => {
+                float.parse::<f64>().map_err(|_| format!("Invalid float: {}", float))?.to_le_bytes().to_vec()
+            }
+            _ => {
+                let number = parse_integer(value).ok_or_else(|| format!("Invalid value for {}: {}", name, value))?;
+                number.to_le_bytes()[..reg.size].to_vec()
+            }
+        };
+        fp[reg.offset..reg.offset + bytes.len()].copy_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+/// Entero con signo o sin él, decimal o 0x.., como u128 (los negativos en complemento a dos)
+pub fn parse_integer(value: &str) -> Option<u128> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16).ok(),
+        None => value.parse::<u128>().ok().or_else(|| value.parse::<i128>().ok().map(|v| v as u128)),
+    }
+}
+
+fn unsupported() -> MemoryError {
+    MemoryError::Io {
+        operation: Operation::Trace,
+        address: None,
+        source: std::io::Error::new(std::io::ErrorKind::Unsupported, "registers are only supported on x86_64 and aarch64"),
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::fs::FileExt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const SPILL_THRESHOLD: usize = 64 * 1024 * 1024; // Bytes en RAM antes de volcar a disco
+const READ_CHUNK: usize = 1024 * 1024;
+
+static SPILL_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Tramo de resultados cuyas direcciones se guardan como offset u32 desde `base`
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    base: u64,
+    first: usize, // Índice del primer registro del tramo
+}
+
+/// Almacén compacto de resultados de búsqueda.
+///
+/// Cada registro ocupa `4 + value_size` bytes (offset dentro del tramo + valor de ancho fijo).
+/// Cuando el buffer en memoria supera `SPILL_THRESHOLD` se vuelca a un archivo temporal
+/// que se borra del disco nada más crearse, así no quedan restos si la herramienta muere.
+#[derive(Debug, Default)]
+pub struct ResultStore {
+    value_size: usize,
+    segments: Vec<Segment>,
+    spill: Option<File>,
+    spilled: usize, // Registros [0, spilled) están en el archivo
+    buffer: Vec<u8>, // Registros [spilled, len)
+    len: usize,
+}
+
+impl ResultStore {
+    pub fn new(value_size: usize) -> ResultStore {
+        ResultStore {
+            value_size,
+            ..Default::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn value_size(&self) -> usize {
+        self.value_size
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        self.spill.is_some()
+    }
+
+    fn record_size(&self) -> usize {
+        4 + self.value_size
+    }
+
+    pub fn push(&mut self, address: u64, value: &[u8]) -> io::Result<()> {
+        debug_assert_eq!(value.len(), self.value_size);
+
+        let segment = match self.segments.last() {
+            Some(s) if address >= s.base && address - s.base <= u32::MAX as u64 => *s,
+            _ => {
+                let s = Segment {
+                    base: address,
+                    first: self.len,
+                };
+                self.segments.push(s);
+                s
+            }
+        };
+
+        let offset = (address - segment.base) as u32;
+        self.buffer.extend_from_slice(&offset.to_le_bytes());
+        self.buffer.extend_from_slice(value);
+        self.len += 1;
+
+        if self.buffer.len() >= SPILL_THRESHOLD {
+            self.flush_to_disk()?;
+        }
+        Ok(())
+    }
+
+    fn flush_to_disk(&mut self) -> io::Result<()> {
+        if self.spill.is_none() {
+            let path = std::env::temp_dir().join(format!(
+                "lpm-results-{}-{}",
+                std::process::id(),
+                SPILL_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create_new(true)
+                .open(&path)?;
+            std::fs::remove_file(&path)?;
+            self.spill = Some(file);
+        }
+
+        let file = self.spill.as_ref().unwrap();
+        file.write_all_at(&self.buffer, (self.spilled * self.record_size()) as u64)?;
+        self.spilled = self.len;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    fn address_of(&self, index: usize, offset: u32) -> u64 {
+        let segment = self.segments.partition_point(|s| s.first <= index) - 1;
+        self.segments[segment].base + offset as u64
+    }
+
+    fn decode(&self, index: usize, record: &[u8]) -> (u64, Vec<u8>) {
+        let offset = u32::from_le_bytes(record[..4].try_into().unwrap());
+        (self.address_of(index, offset), record[4..].to_vec())
+    }
+
+    pub fn get(&self, index: usize) -> io::Result<(u64, Vec<u8>)> {
+        if index >= self.len {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "result index out of range"));
+        }
+        let record_size = self.record_size();
+        if index < self.spilled {
+            let mut record = vec![0u8; record_size];
+            self.spill
+                .as_ref()
+                .unwrap()
+                .read_exact_at(&mut record, (index * record_size) as u64)?;
+            Ok(self.decode(index, &record))
+        } else {
+            let start = (index - self.spilled) * record_size;
+            Ok(self.decode(index, &self.buffer[start..start + record_size]))
+        }
+    }
+
+    /// Recorre todos los resultados en orden, leyendo el archivo temporal por bloques
+    pub fn iter(&self) -> ResultIter<'_> {
+        ResultIter {
+            store: self,
+            index: 0,
+            chunk: Vec::new(),
+            chunk_first: 0,
+        }
+    }
+}
+
+pub struct ResultIter<'a> {
+    store: &'a ResultStore,
+    index: usize,
+    chunk: Vec<u8>,
+    chunk_first: usize,
+}
+
+impl Iterator for ResultIter<'_> {
+    type Item = io::Result<(u64, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let store = self.store;
+        if self.index >= store.len {
+            return None;
+        }
+        let record_size = store.record_size();
+        let index = self.index;
+        self.index += 1;
+
+        if index >= store.spilled {
+            let start = (index - store.spilled) * record_size;
+            return Some(Ok(store.decode(index, &store.buffer[start..start + record_size])));
+        }
+
+        let chunk_records = self.chunk.len() / record_size;
+        if index < self.chunk_first || index >= self.chunk_first + chunk_records {
+            let count = (READ_CHUNK / record_size).max(1).min(store.spilled - index);
+            self.chunk.resize(count * record_size, 0);
+            self.chunk_first = index;
+            if let Err(e) = store
+                .spill
+                .as_ref()
+                .unwrap()
+                .read_exact_at(&mut self.chunk, (index * record_size) as u64)
+            {
+                self.index = store.len;
+                return Some(Err(e));
+            }
+        }
+
+        let start = (index - self.chunk_first) * record_size;
+        Some(Ok(store.decode(index, &self.chunk[start..start + record_size])))
+    }
+}
@@ -1,16 +1,17 @@
-use super::process::ProcessMemory;
-use std::error::Error;
+use super::error::{MemoryError, Operation};
+use super::source::MemorySource;
+use super::results::ResultStore;
 
-pub fn search_pattern(
-    process: &mut ProcessMemory,
+pub fn search_pattern<S: MemorySource + ?Sized>(
+    process: &mut S,
     pattern: &[u8],
-) -> Result<Vec<u64>, Box<dyn Error>> {
+) -> Result<ResultStore, MemoryError> {
     let max_region_size = 100 * 1024 * 1024; // 100 MB
 
     // Clone regions to avoid borrowing conflicts
-    let regions = process.regions.clone();
+    let regions = process.regions().to_vec();
 
-    let mut all_matches = Vec::new();
+    let mut all_matches = ResultStore::new(pattern.len());
     for region in &regions {
         if !region.permissions.contains('r') {
             continue;
@@ -23,7 +24,12 @@ pub fn search_pattern(
             Ok(data) => {
                 for (i, window) in data.windows(pattern.len()).enumerate() {
                     if window == pattern {
-                        all_matches.push(region.start + i as u64);
+                        let address = region.start + i as u64;
+                        all_matches.push(address, pattern).map_err(|source| MemoryError::Io {
+                            operation: Operation::Scan,
+                            address: Some(address),
+                            source,
+                        })?;
                     }
                 }
             }
@@ -34,10 +40,40 @@ pub fn search_pattern(
     Ok(all_matches)
 }
 
-pub fn search_value<T: bytemuck::Pod + Copy>(
-    process: &mut ProcessMemory,
+pub fn search_value<S: MemorySource + ?Sized, T: bytemuck::Pod + Copy>(
+    process: &mut S,
     value: T,
-) -> Result<Vec<u64>, Box<dyn Error>> {
+) -> Result<ResultStore, MemoryError> {
     let bytes = bytemuck::bytes_of(&value);
     search_pattern(process, bytes)
-}
\ No newline at end of file
+}
+
+/// Busca la siguiente aparición de `pattern` a partir de `from` (inclusive), región a región
+pub fn find_next<S: MemorySource + ?Sized>(
+    process: &mut S,
+    from: u64,
+    pattern: &[u8],
+) -> Result<Option<u64>, MemoryError> {
+    const CHUNK: u64 = 1024 * 1024;
+    if pattern.is_empty() {
+        return Ok(None);
+    }
+
+    let regions = process.regions().to_vec();
+    for region in regions.iter().filter(|r| r.end > from && r.permissions.contains('r')) {
+        let mut start = region.start.max(from);
+        while start < region.end {
+            // Solapamos pattern.len() - 1 bytes para no perder coincidencias entre bloques
+            let end = (start + CHUNK + pattern.len() as u64 - 1).min(region.end);
+            let data = match process.read_memory(start, (end - start) as usize) {
+                Ok(data) => data,
+                Err(_) => break,
+            };
+            if let Some(i) = data.windows(pattern.len()).position(|w| w == pattern) {
+                return Ok(Some(start + i as u64));
+            }
+            start += CHUNK;
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,136 @@
+use super::history::ScanStep;
+use super::process::ProcessMemory;
+use super::region::{module_base, module_offset};
+use super::results::ResultStore;
+use crate::types::{SearchFilter, ValueType};
+use crate::utils::hex;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+const SESSION_HEADER: &str = "# linux-proc-modifier scan session v1";
+
+#[derive(Debug, Default)]
+pub struct RestoreStats {
+    pub saved_pid: u32,
+    pub absolute: usize, // Restauradas con la misma dirección (mismo PID)
+    pub remapped: usize, // Recalculadas a partir de módulo + offset
+    pub dropped: usize,  // Sin forma de ubicarlas en el proceso actual
+    pub patches: usize, // Parches de código ubicados y verificados por su firma
+    pub patches_dropped: usize,
+}
+
+/// Sesión de escaneo guardada en disco: resultados (con el valor de la última lectura,
+/// necesario para Changed/Unchanged), tipo de valor e historial de filtros.
+/// Los resultados se escriben y se leen en streaming para no cargarlos enteros en RAM.
+pub struct ScanSession;
+
+impl ScanSession {
+    pub fn save(process: &ProcessMemory, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{}", SESSION_HEADER)?;
+        writeln!(out, "pid {}", process.pid)?;
+        if let Some(value_type) = process.value_type {
+            writeln!(out, "type {}", value_type.name())?;
+        }
+        writeln!(out, "size {}", process.search_results.value_size())?;
+        for (filter, value) in &process.filter_history {
+            match value {
+                Some(value) => writeln!(out, "filter {} ={}", filter.name(), hex::encode(value))?,
+                None => writeln!(out, "filter {}", filter.name())?,
+            }
+        }
+        // result <dirección> <valor> [<offset> <módulo>]; el módulo va al final porque puede tener espacios
+        for entry in process.search_results.iter() {
+            let (address, value) = entry?;
+            write!(out, "result {:x} {}", address, hex::encode(&value))?;
+            if let Some((module, offset)) = module_offset(&process.regions, address) {
+                write!(out, " {:x} {}", offset, module)?;
+            }
+            writeln!(out)?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Carga la sesión en el proceso. Las direcciones dentro de módulos se recalculan
+    /// con la base actual del módulo; las anónimas solo se conservan si el PID coincide.
+    pub fn restore(process: &mut ProcessMemory, path: &str) -> Result<RestoreStats, Box<dyn Error>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut lines = reader.lines();
+
+        match lines.next() {
+            Some(Ok(line)) if line == SESSION_HEADER => {}
+            _ => return Err("Not a scan session file".into()),
+        }
+
+        process.refresh_regions()?;
+        let mut stats = RestoreStats::default();
+        let mut value_type = None;
+        let mut filter_history = Vec::new();
+        let mut results: Option<ResultStore> = None;
+
+        for (number, line) in lines.enumerate() {
+            let line = line?;
+            let bad_line = || format!("Invalid session line {}: {}", number + 2, line);
+            let (key, rest) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+            match key {
+                "pid" => stats.saved_pid = rest.parse().map_err(|_| bad_line())?,
+                "type" => value_type = Some(ValueType::from_name(rest).ok_or_else(bad_line)?),
+                "size" => results = Some(ResultStore::new(rest.parse().map_err(|_| bad_line())?)),
+                "filter" => {
+                    let (name, value) = match rest.split_once(" =") {
+                        Some((name, value)) => (name, Some(hex::decode(value).ok_or_else(bad_line)?)),
+                        None => (rest, None),
+                    };
+                    let filter = SearchFilter::from_name(name).ok_or_else(bad_line)?;
+                    filter_history.push((filter, value));
+                }
+                "result" => {
+                    let mut parts = rest.splitn(4, ' ');
+                    let address = parts
+                        .next()
+                        .and_then(|s| u64::from_str_radix(s, 16).ok())
+                        .ok_or_else(bad_line)?;
+                    let value = parts.next().and_then(hex::decode).ok_or_else(bad_line)?;
+                    let remapped = match (parts.next(), parts.next()) {
+                        (Some(offset), Some(module)) => {
+                            let offset = u64::from_str_radix(offset, 16).map_err(|_| bad_line())?;
+                            module_base(&process.regions, module).map(|base| base + offset)
+                        }
+                        _ => None,
+                    };
+
+                    let results = results.get_or_insert_with(|| ResultStore::new(value.len()));
+                    if value.len() != results.value_size() {
+                        return Err(bad_line().into());
+                    }
+                    match remapped {
+                        Some(new_address) => {
+                            if new_address == address && process.pid == stats.saved_pid {
+                                stats.absolute += 1;
+                            } else {
+                                stats.remapped += 1;
+                            }
+                            results.push(new_address, &value)?;
+                        }
+                        None if process.pid == stats.saved_pid => {
+                            stats.absolute += 1;
+                            results.push(address, &value)?;
+                        }
+                        None => stats.dropped += 1,
+                    }
+                }
+                "" => {}
+                _ => return Err(bad_line().into()),
+            }
+        }
+
+        process.load_scan_step(ScanStep {
+            results: results.unwrap_or_default(),
+            value_type,
+            filter_history,
+        });
+        Ok(stats)
+    }
+}
@@ -0,0 +1,202 @@
+use super::dump::read_pages;
+use super::region::MemoryRegion;
+use super::results::ResultStore;
+use super::source::MemorySource;
+use crate::types::{SearchFilter, ValueType};
+use std::error::Error;
+use std::time::Instant;
+
+const MAX_SNAPSHOT_REGION: u64 = 100 * 1024 * 1024; // Igual que el límite de búsqueda
+const DIFF_CHUNK: u64 = 1024 * 1024;
+const RANGE_GAP: u64 = 8; // Cambios separados por menos bytes se agrupan en un solo rango
+
+/// Copia en RAM de las regiones legibles de una fuente de memoria
+#[derive(Debug)]
+pub struct Snapshot {
+    pub label: String,
+    pub taken: Instant,
+    regions: Vec<MemoryRegion>,
+    data: Vec<Vec<u8>>, // Uno por región, en el mismo orden
+    unreadable: Vec<(u64, u64)>,
+}
+
+impl Snapshot {
+    pub fn capture<S: MemorySource + ?Sized>(
+        source: &mut S,
+        label: &str,
+        writable_only: bool,
+    ) -> Result<Snapshot, Box<dyn Error>> {
+        let mut regions = Vec::new();
+        let mut data = Vec::new();
+        let mut unreadable = Vec::new();
+        for region in source.regions().to_vec() {
+            let len = region.end - region.start;
+            if !region.permissions.contains('r') || len > MAX_SNAPSHOT_REGION {
+                continue;
+            }
+            if writable_only && !region.permissions.contains('w') {
+                continue;
+            }
+            let bytes = match source.read_memory(region.start, len as usize) {
+                Ok(bytes) => bytes,
+                Err(_) => read_pages(source, region.start, len, &mut unreadable),
+            };
+            regions.push(region);
+            data.push(bytes);
+        }
+        if regions.is_empty() {
+            return Err("No readable regions to snapshot".into());
+        }
+
+        Ok(Snapshot {
+            label: label.to_string(),
+            taken: Instant::now(),
+            regions,
+            data,
+            unreadable,
+        })
+    }
+
+    pub fn size(&self) -> u64 {
+        self.data.iter().map(|d| d.len() as u64).sum()
+    }
+}
+
+impl MemorySource for Snapshot {
+    fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    fn read_memory(&mut self, address: u64, size: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+        let end = address
+            .checked_add(size as u64)
+            .ok_or_else(|| format!("Invalid read at 0x{:x}", address))?;
+        if self.unreadable.iter().any(|&(start, stop)| start < end && address < stop) {
+            return Err(format!("Memory at 0x{:x} was unreadable in the snapshot", address).into());
+        }
+        let index = self
+            .regions
+            .iter()
+            .position(|r| r.contains(address) && end <= r.end)
+            .ok_or_else(|| format!("Address 0x{:x} is not in the snapshot", address))?;
+        let offset = (address - self.regions[index].start) as usize;
+        Ok(self.data[index][offset..offset + size].to_vec())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Changed,
+    Resized(u64), // Mismo inicio, nuevo final; los rangos cubren la parte común
+    Added,
+    Removed,
+}
+
+/// Diferencias de una región entre dos fuentes; las regiones iguales no aparecen
+#[derive(Debug)]
+pub struct RegionDiff {
+    pub region: MemoryRegion,
+    pub kind: DiffKind,
+    pub ranges: Vec<(u64, u64)>, // [inicio, fin) de los bytes cambiados
+}
+
+impl RegionDiff {
+    pub fn changed_bytes(&self) -> u64 {
+        self.ranges.iter().map(|(start, end)| end - start).sum()
+    }
+}
+
+/// Regiones legibles emparejadas por dirección de inicio: (región de `a`, región de `b`)
+fn pair_regions(a: &[MemoryRegion], b: &[MemoryRegion]) -> Vec<(Option<MemoryRegion>, Option<MemoryRegion>)> {
+    let readable = |r: &&MemoryRegion| r.permissions.contains('r');
+    let mut pairs: Vec<_> = a
+        .iter()
+        .filter(readable)
+        .map(|old| (Some(old.clone()), b.iter().filter(readable).find(|new| new.start == old.start).cloned()))
+        .collect();
+    for new in b.iter().filter(readable) {
+        if !a.iter().filter(readable).any(|old| old.start == new.start) {
+            pairs.push((None, Some(new.clone())));
+        }
+    }
+    pairs.sort_by_key(|(old, new)| old.as_ref().or(new.as_ref()).map(|r| r.start));
+    pairs
+}
+
+/// Lista, región a región, los rangos de bytes que difieren entre `a` y `b`
+pub fn diff_sources<A: MemorySource + ?Sized, B: MemorySource + ?Sized>(
+    a: &mut A,
+    b: &mut B,
+) -> Result<Vec<RegionDiff>, Box<dyn Error>> {
+    let mut diffs = Vec::new();
+    for pair in pair_regions(a.regions(), b.regions()) {
+        let (old, new) = match pair {
+            (Some(old), Some(new)) => (old, new),
+            (Some(old), None) => {
+                diffs.push(RegionDiff { region: old, kind: DiffKind::Removed, ranges: Vec::new() });
+                continue;
+            }
+            (None, Some(new)) => {
+                diffs.push(RegionDiff { region: new, kind: DiffKind::Added, ranges: Vec::new() });
+                continue;
+            }
+            (None, None) => continue,
+        };
+
+        let end = old.end.min(new.end);
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        let mut start = old.start;
+        while start < end {
+            let len = DIFF_CHUNK.min(end - start) as usize;
+            // Los bloques que no se pueden leer en alguno de los lados se omiten
+            if let (Ok(before), Ok(after)) = (a.read_memory(start, len), b.read_memory(start, len)) {
+                for (i, _) in before.iter().zip(&after).enumerate().filter(|(_, (x, y))| x != y) {
+                    let address = start + i as u64;
+                    match ranges.last_mut() {
+                        Some(last) if address - last.1 < RANGE_GAP => last.1 = address + 1,
+                        _ => ranges.push((address, address + 1)),
+                    }
+                }
+            }
+            start += len as u64;
+        }
+
+        let kind = if old.end != new.end { DiffKind::Resized(new.end) } else { DiffKind::Changed };
+        if !ranges.is_empty() || kind != DiffKind::Changed {
+            diffs.push(RegionDiff { region: old, kind, ranges });
+        }
+    }
+    Ok(diffs)
+}
+
+/// Valores alineados de `value_type` que cumplen `filter` entre `a` (antes) y `b` (después).
+/// Los resultados guardan el valor de `b`.
+pub fn filter_changes<A: MemorySource + ?Sized, B: MemorySource + ?Sized>(
+    a: &mut A,
+    b: &mut B,
+    value_type: ValueType,
+    filter: &SearchFilter,
+    exact: Option<&[u8]>,
+) -> Result<ResultStore, Box<dyn Error>> {
+    let size = value_type
+        .size()
+        .ok_or("Snapshot filters need a numeric value type")?;
+    let mut results = ResultStore::new(size);
+    for pair in pair_regions(a.regions(), b.regions()) {
+        let (Some(old), Some(new)) = pair else { continue };
+        let end = old.end.min(new.end);
+        let mut start = old.start;
+        while start + size as u64 <= end {
+            let len = (DIFF_CHUNK.min(end - start) as usize) / size * size;
+            if let (Ok(before), Ok(after)) = (a.read_memory(start, len), b.read_memory(start, len)) {
+                for (i, (x, y)) in before.chunks_exact(size).zip(after.chunks_exact(size)).enumerate() {
+                    if filter.matches(Some(value_type), x, y, exact) {
+                        results.push(start + (i * size) as u64, y)?;
+                    }
+                }
+            }
+            start += len as u64;
+        }
+    }
+    Ok(results)
+}
@@ -0,0 +1,100 @@
+use super::core::open_core;
+use super::dump::open_dump;
+use super::region::MemoryRegion;
+use std::error::Error;
+use std::fs::File;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+/// Origen de la memoria que se escanea: el proceso vivo, un volcado o un fichero core
+pub trait MemorySource {
+    fn regions(&self) -> &[MemoryRegion];
+    fn read_memory(&mut self, address: u64, size: usize) -> Result<Vec<u8>, Box<dyn Error>>;
+}
+
+/// Dónde están guardados los bytes de una región dentro de los ficheros
+#[derive(Debug)]
+pub struct Segment {
+    pub file: usize,
+    pub offset: u64,
+    pub file_size: u64, // Bytes guardados; el resto de la región no está disponible
+}
+
+/// Memoria guardada en disco (volcado o core), de solo lectura
+#[derive(Debug)]
+pub struct SavedMemory {
+    pub pid: u32,
+    pub origin: String,
+    regions: Vec<MemoryRegion>,
+    segments: Vec<Segment>, // Uno por región, en el mismo orden
+    files: Vec<File>,
+    unreadable: Vec<(u64, u64)>, // Páginas que no se pudieron leer al guardar
+}
+
+impl SavedMemory {
+    pub fn new(
+        pid: u32,
+        origin: String,
+        mut parts: Vec<(MemoryRegion, Segment)>,
+        files: Vec<File>,
+        unreadable: Vec<(u64, u64)>,
+    ) -> SavedMemory {
+        parts.sort_by_key(|(region, _)| region.start);
+        let (regions, segments) = parts.into_iter().unzip();
+        SavedMemory {
+            pid,
+            origin,
+            regions,
+            segments,
+            files,
+            unreadable,
+        }
+    }
+}
+
+impl MemorySource for SavedMemory {
+    fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    /// Lee como /proc/<pid>/mem: puede cruzar regiones contiguas, pero falla en huecos,
+    /// páginas ilegibles o bytes que no se guardaron
+    fn read_memory(&mut self, address: u64, size: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+        let end = address
+            .checked_add(size as u64)
+            .ok_or_else(|| format!("Invalid read at 0x{:x}", address))?;
+        if self.unreadable.iter().any(|&(start, stop)| start < end && address < stop) {
+            return Err(format!("Memory at 0x{:x} was unreadable when saved", address).into());
+        }
+
+        let mut buffer = vec![0u8; size];
+        let mut cursor = address;
+        while cursor < end {
+            let index = self
+                .regions
+                .iter()
+                .position(|r| r.contains(cursor))
+                .ok_or_else(|| format!("Address 0x{:x} is not mapped", cursor))?;
+            let (region, segment) = (&self.regions[index], &self.segments[index]);
+            let region_offset = cursor - region.start;
+            let len = (end.min(region.end) - cursor) as usize;
+            if region_offset + len as u64 > segment.file_size {
+                return Err(format!("Memory at 0x{:x} was not saved", cursor).into());
+            }
+            let at = (cursor - address) as usize;
+            self.files[segment.file].read_exact_at(&mut buffer[at..at + len], segment.offset + region_offset)?;
+            cursor += len as u64;
+        }
+        Ok(buffer)
+    }
+}
+
+/// Abre un directorio de volcado (con manifiesto) o un fichero core
+pub fn open_saved(path: &str) -> Result<SavedMemory, Box<dyn Error>> {
+    let path = Path::new(path);
+    if path.is_dir() {
+        open_dump(path)
+    } else {
+        open_core(path)
+    }
+}
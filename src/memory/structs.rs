@@ -0,0 +1,196 @@
+use super::inspect::{decode, describe_pointer, read_pointer, Endian, InspectType, INSPECT_BYTES};
+use super::process::ProcessMemory;
+use super::region::find_region;
+
+const MAX_POINTER_DEPTH: usize = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    Value(InspectType, Endian),
+    StructPointer(String), // Puntero a otra estructura definida por el usuario
+}
+
+impl FieldType {
+    pub fn parse(spec: &str) -> Option<FieldType> {
+        match spec.strip_prefix("ptr:") {
+            Some(target) if !target.is_empty() => Some(FieldType::StructPointer(target.to_string())),
+            Some(_) => None,
+            None => InspectType::parse_spec(spec).map(|(kind, endian)| FieldType::Value(kind, endian)),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            FieldType::Value(kind, Endian::Big) => format!("{}be", kind.name()),
+            FieldType::Value(kind, Endian::Little) => kind.name().to_string(),
+            FieldType::StructPointer(target) => format!("ptr:{}", target),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StructField {
+    pub offset: u64,
+    pub name: String,
+    pub field_type: FieldType,
+}
+
+impl StructField {
+    /// Interpreta "<offset> <nombre> <tipo>", p. ej. "0x10 health i32" o "0x18 weapon ptr:Weapon"
+    pub fn parse(line: &str) -> Option<StructField> {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [offset, name, spec] = parts.as_slice() else {
+            return None;
+        };
+        let offset = match offset.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok()?,
+            None => offset.parse().ok()?,
+        };
+        Some(StructField {
+            offset,
+            name: name.to_string(),
+            field_type: FieldType::parse(spec)?,
+        })
+    }
+
+    pub fn to_line(&self) -> String {
+        format!("0x{:x} {} {}", self.offset, self.name, self.field_type.name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StructLayout {
+    pub name: String,
+    pub fields: Vec<StructField>,
+}
+
+/// Un campo ya leído de memoria; `depth` > 0 para campos de estructuras apuntadas
+#[derive(Debug, Clone)]
+pub struct DissectedField {
+    pub depth: usize,
+    pub address: u64,
+    pub offset: u64,
+    pub name: String,
+    pub type_name: String,
+    pub value: String,
+}
+
+/// Aplica `layout` en `address` leyendo los valores actuales y siguiendo punteros a
+/// otras estructuras hasta `MAX_POINTER_DEPTH` niveles
+pub fn dissect(
+    process: &mut ProcessMemory,
+    layouts: &[StructLayout],
+    layout: &StructLayout,
+    address: u64,
+) -> Vec<DissectedField> {
+    let mut fields = Vec::new();
+    dissect_into(process, layouts, layout, address, 0, &mut fields);
+    fields
+}
+
+fn dissect_into(
+    process: &mut ProcessMemory,
+    layouts: &[StructLayout],
+    layout: &StructLayout,
+    address: u64,
+    depth: usize,
+    out: &mut Vec<DissectedField>,
+) {
+    let mut fields: Vec<&StructField> = layout.fields.iter().collect();
+    fields.sort_by_key(|f| f.offset);
+
+    for field in fields {
+        let field_address = address.wrapping_add(field.offset);
+        let bytes = process
+            .read_memory(field_address, INSPECT_BYTES)
+            .or_else(|_| process.read_memory(field_address, 8));
+        let mut target = None;
+        let value = match (&bytes, &field.field_type) {
+            (Err(_), _) => "??".to_string(),
+            (Ok(bytes), FieldType::Value(kind, endian)) => {
+                decode(*kind, *endian, bytes).unwrap_or_else(|| "??".to_string())
+            }
+            (Ok(bytes), FieldType::StructPointer(name)) => match read_pointer(bytes, Endian::Little) {
+                Some(pointer) => {
+                    target = layouts.iter().find(|l| &l.name == name).map(|l| (l, pointer));
+                    format!("0x{:x} → {}", pointer, describe_pointer(&process.regions, pointer))
+                }
+                None => "??".to_string(),
+            },
+        };
+        out.push(DissectedField {
+            depth,
+            address: field_address,
+            offset: field.offset,
+            name: field.name.clone(),
+            type_name: field.field_type.name(),
+            value,
+        });
+
+        if let Some((target, pointer)) = target {
+            if depth + 1 < MAX_POINTER_DEPTH && find_region(&process.regions, pointer).is_some() {
+                dissect_into(process, layouts, target, pointer, depth + 1, out);
+            }
+        }
+    }
+}
+
+/// Propone un layout a partir de los bytes: punteros a regiones mapeadas, cadenas,
+/// floats plausibles y enteros, en celdas alineadas de 4/8 bytes
+pub fn guess_layout(process: &ProcessMemory, name: &str, bytes: &[u8]) -> StructLayout {
+    let mut fields = Vec::new();
+    let mut offset = 0usize;
+
+    while offset + 4 <= bytes.len() {
+        let rest = &bytes[offset..];
+        let field_type = if offset.is_multiple_of(8) && rest.len() >= 8 && looks_like_pointer(process, rest) {
+            Some((InspectType::Pointer, 8))
+        } else if let Some(len) = printable_run(rest) {
+            Some((InspectType::Utf8, len.div_ceil(4) * 4))
+        } else {
+            let int = i32::from_le_bytes(rest[..4].try_into().unwrap());
+            let float = f32::from_le_bytes(rest[..4].try_into().unwrap());
+            if int == 0 {
+                None // Relleno o campo a cero: no aporta información
+            } else if looks_like_float(float) && int.unsigned_abs() > 0x0010_0000 {
+                Some((InspectType::F32, 4))
+            } else {
+                Some((InspectType::I32, 4))
+            }
+        };
+
+        match field_type {
+            Some((kind, size)) => {
+                fields.push(StructField {
+                    offset: offset as u64,
+                    name: format!("field_{:x}", offset),
+                    field_type: FieldType::Value(kind, Endian::Little),
+                });
+                offset += size;
+            }
+            None => offset += 4,
+        }
+    }
+
+    StructLayout {
+        name: name.to_string(),
+        fields,
+    }
+}
+
+fn looks_like_pointer(process: &ProcessMemory, bytes: &[u8]) -> bool {
+    match read_pointer(bytes, Endian::Little) {
+        Some(pointer) => pointer != 0 && find_region(&process.regions, pointer).is_some(),
+        None => false,
+    }
+}
+
+fn looks_like_float(value: f32) -> bool {
+    value.is_finite() && (1e-3..1e7).contains(&value.abs())
+}
+
+/// Longitud de una cadena ASCII imprimible de al menos 4 caracteres al inicio de `bytes`
+fn printable_run(bytes: &[u8]) -> Option<usize> {
+    let len = bytes.iter().take_while(|b| (32..=126).contains(*b)).count();
+    (len >= 4).then_some(len)
+}
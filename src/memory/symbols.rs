@@ -0,0 +1,138 @@
+use super::core::{u16_at, u32_at, u64_at};
+use super::region::{module_base, MemoryRegion};
+use std::collections::HashMap;
+use std::fs;
+
+const PT_LOAD: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_DYNSYM: u32 = 11;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const SYM_SIZE: usize = 24;
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub address: u64, // Dirección virtual dentro del fichero ELF
+    pub size: u64,
+}
+
+/// Funciones y variables de un módulo ELF64, de .symtab y .dynsym
+#[derive(Debug, Default)]
+pub struct ModuleSymbols {
+    symbols: Vec<Symbol>, // Ordenados por dirección
+    first_vaddr: u64, // Dirección del primer PT_LOAD: se mapea en la base del módulo
+}
+
+impl ModuleSymbols {
+    pub fn load(path: &str) -> Option<ModuleSymbols> {
+        let data = fs::read(path).ok()?;
+        if data.get(..6) != Some(&[0x7f, b'E', b'L', b'F', 2, 1]) {
+            return None;
+        }
+
+        let phoff = u64_at(&data, 0x20) as usize;
+        let phentsize = u16_at(&data, 0x36) as usize;
+        let first_vaddr = (0..u16_at(&data, 0x38) as usize)
+            .map(|i| phoff + i * phentsize)
+            .filter(|&at| u32_at(&data, at) == PT_LOAD)
+            .map(|at| u64_at(&data, at + 0x10) & !0xfff)
+            .min()
+            .unwrap_or(0);
+
+        let shoff = u64_at(&data, 0x28) as usize;
+        let shentsize = u16_at(&data, 0x3a) as usize;
+        let section = |i: usize| shoff + i * shentsize;
+        let mut symbols = Vec::new();
+        for i in 0..u16_at(&data, 0x3c) as usize {
+            let at = section(i);
+            let kind = u32_at(&data, at + 4);
+            if kind != SHT_SYMTAB && kind != SHT_DYNSYM {
+                continue;
+            }
+            let offset = u64_at(&data, at + 0x18) as usize;
+            let size = u64_at(&data, at + 0x20) as usize;
+            let strtab = section(u32_at(&data, at + 0x28) as usize);
+            let strings = data.get(u64_at(&data, strtab + 0x18) as usize..).unwrap_or_default();
+            for sym in (offset..offset + size).step_by(SYM_SIZE) {
+                let kind = data.get(sym + 4).map_or(0, |info| info & 0xf);
+                let address = u64_at(&data, sym + 8);
+                if !(kind == STT_FUNC || kind == STT_OBJECT) || address == 0 || u16_at(&data, sym + 6) == 0 {
+                    continue;
+                }
+                let name_at = u32_at(&data, sym) as usize;
+                let Some(name) = strings.get(name_at..).and_then(|s| s.split(|&b| b == 0).next()) else {
+                    continue;
+                };
+                if !name.is_empty() {
+                    symbols.push(Symbol {
+                        name: String::from_utf8_lossy(name).into_owned(),
+                        address,
+                        size: u64_at(&data, sym + 16),
+                    });
+                }
+            }
+        }
+        // .symtab y .dynsym repiten los exportados
+        symbols.sort_by(|a, b| a.address.cmp(&b.address).then_with(|| a.name.cmp(&b.name)));
+        symbols.dedup_by(|a, b| a.address == b.address && a.name == b.name);
+        Some(ModuleSymbols { symbols, first_vaddr })
+    }
+
+    /// Símbolo que contiene la dirección virtual del fichero y el desplazamiento dentro de él
+    fn lookup(&self, vaddr: u64) -> Option<(&Symbol, u64)> {
+        let index = self.symbols.partition_point(|s| s.address <= vaddr).checked_sub(1)?;
+        let symbol = &self.symbols[index];
+        let offset = vaddr - symbol.address;
+        (offset < symbol.size.max(1)).then_some((symbol, offset))
+    }
+}
+
+/// Símbolos de los módulos mapeados, cargados la primera vez que se necesitan
+#[derive(Default)]
+pub struct SymbolCache {
+    modules: HashMap<String, Option<ModuleSymbols>>,
+}
+
+impl SymbolCache {
+    fn module(&mut self, pathname: &str) -> Option<&ModuleSymbols> {
+        self.modules
+            .entry(pathname.to_string())
+            .or_insert_with(|| ModuleSymbols::load(pathname))
+            .as_ref()
+    }
+
+    /// "nombre" o "nombre+0x10" para una dirección del proceso
+    pub fn resolve(&mut self, regions: &[MemoryRegion], address: u64) -> Option<String> {
+        let region = regions.iter().find(|r| r.contains(address) && r.pathname.starts_with('/'))?;
+        let base = module_base(regions, &region.pathname)?;
+        let module = self.module(&region.pathname)?;
+        let (symbol, offset) = module.lookup(address - base + module.first_vaddr)?;
+        Some(if offset == 0 {
+            symbol.name.clone()
+        } else {
+            format!("{}+0x{:x}", symbol.name, offset)
+        })
+    }
+
+    /// Dirección en el proceso de un símbolo por nombre, buscándolo en todos los módulos
+    pub fn find(&mut self, regions: &[MemoryRegion], name: &str) -> Option<u64> {
+        let mut paths: Vec<&str> = regions
+            .iter()
+            .filter(|r| r.pathname.starts_with('/') && r.permissions.contains('x'))
+            .map(|r| r.pathname.as_str())
+            .collect();
+        paths.dedup();
+        for path in paths {
+            let Some(base) = module_base(regions, path) else {
+                continue;
+            };
+            if let Some(module) = self.module(path) {
+                if let Some(symbol) = module.symbols.iter().find(|s| s.name == name) {
+                    return Some(symbol.address - module.first_vaddr + base);
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,220 @@
+use super::patch::{relocate, revert_all, Patch};
+use super::process::{ProcessMemory, SavedType};
+use super::region::{module_base, module_offset, MemoryRegion};
+use super::session::RestoreStats;
+use crate::utils::hex;
+use super::structs::{StructField, StructLayout};
+use crate::types::ValueType;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+
+const TABLE_HEADER: &str = "# linux-proc-modifier cheat table v1";
+
+/// Tabla de trucos: direcciones guardadas (como módulo + offset cuando es posible,
+/// para sobrevivir a ASLR), layouts de estructuras definidos por el usuario y parches de código.
+pub struct CheatTable;
+
+impl CheatTable {
+    pub fn save(process: &ProcessMemory, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{}", TABLE_HEADER)?;
+        writeln!(out, "pid {}", process.pid)?;
+        // address <dirección> <descripción>, seguida de "type <tipo> <tamaño>" si se conoce
+        // y de "module <offset> <ruta>" si está en un módulo
+        for (i, (address, description)) in process.get_saved_addresses().iter().enumerate() {
+            writeln!(out, "address {:x} {}", address, description)?;
+            if let Some((value_type, len)) = process.saved_type(i) {
+                writeln!(out, "type {} {}", value_type.name(), len)?;
+            }
+            if let Some((module, offset)) = module_offset(&process.regions, *address) {
+                writeln!(out, "module {:x} {}", offset, module)?;
+            }
+        }
+        // patch <dirección> <originales> <parche> <firma> <on|off> <descripción>, y "module" igual
+        for patch in &process.patches {
+            writeln!(
+                out,
+                "patch {:x} {} {} {} {} {}",
+                patch.address,
+                hex::encode(&patch.original),
+                hex::encode(&patch.patched),
+                patch.signature,
+                if patch.enabled { "on" } else { "off" },
+                patch.description
+            )?;
+            if let Some((module, offset)) = &patch.module {
+                writeln!(out, "module {:x} {}", offset, module)?;
+            }
+        }
+        for layout in &process.struct_layouts {
+            writeln!(out, "struct {}", layout.name)?;
+            for field in &layout.fields {
+                writeln!(out, "field {}", field.to_line())?;
+            }
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Reemplaza las direcciones guardadas, los layouts y los parches del proceso por los de la
+    /// tabla. Los parches activos se vuelven a aplicar si su firma coincide.
+    pub fn load(process: &mut ProcessMemory, path: &str) -> Result<RestoreStats, Box<dyn Error>> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut lines = reader.lines();
+
+        match lines.next() {
+            Some(Ok(line)) if line == TABLE_HEADER => {}
+            _ => return Err("Not a cheat table file".into()),
+        }
+
+        process.refresh_regions()?;
+        let mut saved_pid = 0;
+        let mut addresses: Vec<(u64, String, Option<u64>, Option<SavedType>)> = Vec::new();
+        let mut layouts: Vec<StructLayout> = Vec::new();
+        let mut patches: Vec<Patch> = Vec::new();
+        let mut module_for_patch = false; // La línea "module" va con la última address o patch
+
+        for (number, line) in lines.enumerate() {
+            let line = line?;
+            let bad_line = || format!("Invalid table line {}: {}", number + 2, line);
+            let (key, rest) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+            match key {
+                "pid" => saved_pid = rest.parse().map_err(|_| bad_line())?,
+                "address" => {
+                    let (address, description) = rest.split_once(' ').unwrap_or((rest, ""));
+                    let address = u64::from_str_radix(address, 16).map_err(|_| bad_line())?;
+                    addresses.push((address, description.to_string(), None, None));
+                    module_for_patch = false;
+                }
+                "type" => {
+                    let (name, len) = rest.split_once(' ').ok_or_else(bad_line)?;
+                    let value_type = ValueType::from_name(name).ok_or_else(bad_line)?;
+                    let len = len.parse().map_err(|_| bad_line())?;
+                    addresses.last_mut().ok_or_else(bad_line)?.3 = Some((value_type, len));
+                }
+                "patch" => {
+                    patches.push(parse_patch(rest).ok_or_else(bad_line)?);
+                    module_for_patch = true;
+                }
+                "module" => {
+                    let (offset, module) = rest.split_once(' ').ok_or_else(bad_line)?;
+                    let offset = u64::from_str_radix(offset, 16).map_err(|_| bad_line())?;
+                    if module_for_patch {
+                        patches.last_mut().ok_or_else(bad_line)?.module = Some((module.to_string(), offset));
+                    } else {
+                        let entry = addresses.last_mut().ok_or_else(bad_line)?;
+                        entry.2 = module_base(&process.regions, module).map(|base| base + offset);
+                    }
+                }
+                "struct" if !rest.is_empty() => layouts.push(StructLayout {
+                    name: rest.to_string(),
+                    fields: Vec::new(),
+                }),
+                "field" => {
+                    let field = StructField::parse(rest).ok_or_else(bad_line)?;
+                    layouts.last_mut().ok_or_else(bad_line)?.fields.push(field);
+                }
+                "" => {}
+                _ => return Err(bad_line().into()),
+            }
+        }
+
+        let mut stats = RestoreStats {
+            saved_pid,
+            ..RestoreStats::default()
+        };
+        process.clear_saved_addresses();
+        for (address, description, remapped, value) in addresses {
+            match remapped {
+                Some(new_address) => {
+                    if new_address == address && process.pid == saved_pid {
+                        stats.absolute += 1;
+                    } else {
+                        stats.remapped += 1;
+                    }
+                    process.save_address(new_address, description, value);
+                }
+                None if process.pid == saved_pid => {
+                    stats.absolute += 1;
+                    process.save_address(address, description, value);
+                }
+                None => stats.dropped += 1,
+            }
+        }
+        process.struct_layouts = layouts;
+        // Los parches de antes se quitan de la memoria; si alguno no se puede, se conserva
+        revert_all(process);
+        let (patches, dropped) = relocate(process, patches, process.pid == saved_pid, true);
+        stats.patches = patches.len();
+        stats.patches_dropped = dropped;
+        process.patches.extend(patches);
+        Ok(stats)
+    }
+
+    /// Recoloca las direcciones guardadas tras cambiar de proceso: las que están en un módulo
+    /// se recalculan con la nueva base; el resto solo se conserva si el PID es el mismo
+    pub fn remap(process: &mut ProcessMemory, old_pid: u32, old_regions: &[MemoryRegion]) -> RestoreStats {
+        let mut stats = RestoreStats {
+            saved_pid: old_pid,
+            ..RestoreStats::default()
+        };
+        let addresses: Vec<(u64, String, Option<SavedType>)> = process
+            .get_saved_addresses()
+            .iter()
+            .enumerate()
+            .map(|(i, (address, description))| (*address, description.clone(), process.saved_type(i)))
+            .collect();
+        process.clear_saved_addresses();
+        for (address, description, value) in addresses {
+            let remapped = module_offset(old_regions, address)
+                .and_then(|(module, offset)| Some(module_base(&process.regions, &module)? + offset));
+            match remapped {
+                Some(new_address) => {
+                    if new_address == address {
+                        stats.absolute += 1;
+                    } else {
+                        stats.remapped += 1;
+                    }
+                    process.save_address(new_address, description, value);
+                }
+                None if process.pid == old_pid => {
+                    stats.absolute += 1;
+                    process.save_address(address, description, value);
+                }
+                None => stats.dropped += 1,
+            }
+        }
+        let patches = std::mem::take(&mut process.patches);
+        let (patches, dropped) = relocate(process, patches, process.pid == old_pid, false);
+        stats.patches = patches.len();
+        stats.patches_dropped = dropped;
+        process.patches = patches;
+        stats
+    }
+}
+
+fn parse_patch(rest: &str) -> Option<Patch> {
+    let mut fields = rest.splitn(6, ' ');
+    let address = u64::from_str_radix(fields.next()?, 16).ok()?;
+    let original = hex::decode(fields.next()?)?;
+    let patched = hex::decode(fields.next()?)?;
+    let signature = fields.next()?.to_string();
+    let enabled = match fields.next()? {
+        "on" => true,
+        "off" => false,
+        _ => return None,
+    };
+    if original.is_empty() || original.len() != patched.len() {
+        return None;
+    }
+    Some(Patch {
+        address,
+        module: None,
+        original,
+        patched,
+        signature,
+        enabled,
+        description: fields.next().unwrap_or("").to_string(),
+    })
+}
@@ -0,0 +1,110 @@
+use super::error::MemoryError;
+use super::process::ProcessMemory;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub const MAX_WATCH_LEN: usize = 64 * 1024;
+const MAX_CHANGES: usize = 10_000; // Se descartan los cambios más antiguos
+
+/// Bytes contiguos que cambiaron entre dos muestras
+#[derive(Debug, Clone)]
+pub struct WatchChange {
+    pub elapsed: Duration, // Desde el inicio de la vigilancia
+    pub offset: usize,
+    pub old: Vec<u8>,
+    pub new: Vec<u8>,
+}
+
+/// Vigila un rango de memoria: cada muestra se compara con la anterior y los cambios
+/// se registran con su instante, valor viejo y valor nuevo
+#[derive(Debug)]
+pub struct MemoryWatch {
+    pub start: u64,
+    started: Instant,
+    last: Vec<u8>,
+    pub change_counts: Vec<u32>, // Veces que cambió cada byte
+    pub changes: VecDeque<WatchChange>,
+    pub samples: u64,
+}
+
+impl MemoryWatch {
+    /// Empieza a vigilar `len` bytes a partir de `start` tomando la primera muestra
+    pub fn new(process: &mut ProcessMemory, start: u64, len: usize) -> Result<MemoryWatch, MemoryError> {
+        if len == 0 || len > MAX_WATCH_LEN {
+            return Err(MemoryError::InvalidLength { len, max: MAX_WATCH_LEN });
+        }
+        let last = process.read_memory(start, len)?;
+        Ok(MemoryWatch {
+            start,
+            started: Instant::now(),
+            change_counts: vec![0; last.len()],
+            last,
+            changes: VecDeque::new(),
+            samples: 1,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.last.len()
+    }
+
+    /// Último contenido leído del rango
+    pub fn current(&self) -> &[u8] {
+        &self.last
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Lee de nuevo el rango y registra los cambios; devuelve cuántos bloques cambiaron
+    pub fn sample(&mut self, process: &mut ProcessMemory) -> Result<usize, MemoryError> {
+        let data = process.read_memory(self.start, self.last.len())?;
+        let elapsed = self.started.elapsed();
+        self.samples += 1;
+
+        let mut found = 0;
+        let mut i = 0;
+        while i < data.len() {
+            if data[i] == self.last[i] {
+                i += 1;
+                continue;
+            }
+            let run_start = i;
+            while i < data.len() && data[i] != self.last[i] {
+                self.change_counts[i] = self.change_counts[i].saturating_add(1);
+                i += 1;
+            }
+            if self.changes.len() == MAX_CHANGES {
+                self.changes.pop_front();
+            }
+            self.changes.push_back(WatchChange {
+                elapsed,
+                offset: run_start,
+                old: self.last[run_start..i].to_vec(),
+                new: data[run_start..i].to_vec(),
+            });
+            found += 1;
+        }
+        self.last = data;
+        Ok(found)
+    }
+
+    pub fn clear(&mut self) {
+        self.changes.clear();
+        self.change_counts.iter_mut().for_each(|c| *c = 0);
+    }
+
+    /// Offsets de los bytes que cambiaron, de más a menos cambios
+    pub fn hot_offsets(&self) -> Vec<(usize, u32)> {
+        let mut hot: Vec<(usize, u32)> = self
+            .change_counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(offset, &count)| (offset, count))
+            .collect();
+        hot.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        hot
+    }
+}
@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 #[derive(Debug, Clone)]
 pub enum SearchFilter {
     Exact,
@@ -5,5 +7,222 @@ pub enum SearchFilter {
     Unchanged,
     Increased,
     Decreased,
+    IncreasedBy(f64), // Subió exactamente esta cantidad
+    DecreasedBy(f64), // Bajó exactamente esta cantidad
     Range(f64, f64), // Para buscar valores en un rango
 }
+
+impl SearchFilter {
+    pub fn name(&self) -> String {
+        match self {
+            SearchFilter::Exact => "exact".to_string(),
+            SearchFilter::Changed => "changed".to_string(),
+            SearchFilter::Unchanged => "unchanged".to_string(),
+            SearchFilter::Increased => "increased".to_string(),
+            SearchFilter::Decreased => "decreased".to_string(),
+            SearchFilter::IncreasedBy(delta) => format!("increased_by {}", delta),
+            SearchFilter::DecreasedBy(delta) => format!("decreased_by {}", delta),
+            SearchFilter::Range(min, max) => format!("range {} {}", min, max),
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<SearchFilter> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
+        match parts.as_slice() {
+            ["exact"] => Some(SearchFilter::Exact),
+            ["changed"] => Some(SearchFilter::Changed),
+            ["unchanged"] => Some(SearchFilter::Unchanged),
+            ["increased"] => Some(SearchFilter::Increased),
+            ["decreased"] => Some(SearchFilter::Decreased),
+            ["increased_by", delta] => Some(SearchFilter::IncreasedBy(delta.parse().ok()?)),
+            ["decreased_by", delta] => Some(SearchFilter::DecreasedBy(delta.parse().ok()?)),
+            ["range", min, max] => Some(SearchFilter::Range(min.parse().ok()?, max.parse().ok()?)),
+            _ => None,
+        }
+    }
+
+    /// ¿Pasa el filtro un valor que valía `old` y ahora vale `current`? Las comparaciones
+    /// usan el tipo del valor; sin tipo numérico se comparan los bytes
+    pub fn matches(&self, value_type: Option<ValueType>, old: &[u8], current: &[u8], exact: Option<&[u8]>) -> bool {
+        let value_type = value_type.unwrap_or(ValueType::Bytes);
+        match self {
+            SearchFilter::Exact => exact.is_some_and(|value| current == value),
+            SearchFilter::Changed => current != old,
+            SearchFilter::Unchanged => current == old,
+            SearchFilter::Increased => value_type.compare(current, old) == Ordering::Greater,
+            SearchFilter::Decreased => value_type.compare(current, old) == Ordering::Less,
+            SearchFilter::IncreasedBy(delta) => value_type.changed_by(old, current, *delta),
+            SearchFilter::DecreasedBy(delta) => value_type.changed_by(old, current, -delta),
+            SearchFilter::Range(min, max) => value_type.read_f64(current).is_some_and(|v| v >= *min && v <= *max),
+        }
+    }
+}
+
+/// Tipo del valor buscado en la sesión de escaneo actual
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    I32,
+    I64,
+    U32,
+    U64,
+    F32,
+    F64,
+    String,
+    Bytes,
+}
+
+impl ValueType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueType::I32 => "i32",
+            ValueType::I64 => "i64",
+            ValueType::U32 => "u32",
+            ValueType::U64 => "u64",
+            ValueType::F32 => "f32",
+            ValueType::F64 => "f64",
+            ValueType::String => "string",
+            ValueType::Bytes => "bytes",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Option<ValueType> {
+        match s {
+            "i32" => Some(ValueType::I32),
+            "i64" => Some(ValueType::I64),
+            "u32" => Some(ValueType::U32),
+            "u64" => Some(ValueType::U64),
+            "f32" => Some(ValueType::F32),
+            "f64" => Some(ValueType::F64),
+            "string" => Some(ValueType::String),
+            "bytes" => Some(ValueType::Bytes),
+            _ => None,
+        }
+    }
+}
+
+/// Tipos numéricos que se pueden buscar con `search_value`
+pub trait ScanValue: bytemuck::Pod {
+    const TYPE: ValueType;
+}
+
+impl ScanValue for i32 {
+    const TYPE: ValueType = ValueType::I32;
+}
+
+impl ScanValue for i64 {
+    const TYPE: ValueType = ValueType::I64;
+}
+
+impl ScanValue for u32 {
+    const TYPE: ValueType = ValueType::U32;
+}
+
+impl ScanValue for u64 {
+    const TYPE: ValueType = ValueType::U64;
+}
+
+impl ScanValue for f32 {
+    const TYPE: ValueType = ValueType::F32;
+}
+
+impl ScanValue for f64 {
+    const TYPE: ValueType = ValueType::F64;
+}
+
+fn read_pod<T: bytemuck::Pod>(bytes: &[u8]) -> Option<T> {
+    bytemuck::try_pod_read_unaligned(bytes).ok()
+}
+
+impl ValueType {
+    pub fn format(&self, bytes: &[u8]) -> String {
+        let formatted = match self {
+            ValueType::I32 => read_pod::<i32>(bytes).map(|v| v.to_string()),
+            ValueType::I64 => read_pod::<i64>(bytes).map(|v| v.to_string()),
+            ValueType::U32 => read_pod::<u32>(bytes).map(|v| v.to_string()),
+            ValueType::U64 => read_pod::<u64>(bytes).map(|v| v.to_string()),
+            ValueType::F32 => read_pod::<f32>(bytes).map(|v| v.to_string()),
+            ValueType::F64 => read_pod::<f64>(bytes).map(|v| v.to_string()),
+            ValueType::String => Some(format!("\"{}\"", String::from_utf8_lossy(bytes))),
+            ValueType::Bytes => None,
+        };
+        formatted.unwrap_or_else(|| crate::utils::hex::encode(bytes))
+    }
+
+    /// Tamaño fijo de los tipos numéricos; None para cadenas y bytes
+    pub fn size(&self) -> Option<usize> {
+        match self {
+            ValueType::I32 | ValueType::U32 | ValueType::F32 => Some(4),
+            ValueType::I64 | ValueType::U64 | ValueType::F64 => Some(8),
+            ValueType::String | ValueType::Bytes => None,
+        }
+    }
+
+    /// Bytes de un valor escrito por el usuario; los bytes se dan en hexadecimal
+    pub fn parse(&self, s: &str) -> Option<Vec<u8>> {
+        let s = s.trim();
+        match self {
+            ValueType::I32 => s.parse::<i32>().ok().map(|v| bytemuck::bytes_of(&v).to_vec()),
+            ValueType::I64 => s.parse::<i64>().ok().map(|v| bytemuck::bytes_of(&v).to_vec()),
+            ValueType::U32 => s.parse::<u32>().ok().map(|v| bytemuck::bytes_of(&v).to_vec()),
+            ValueType::U64 => s.parse::<u64>().ok().map(|v| bytemuck::bytes_of(&v).to_vec()),
+            ValueType::F32 => s.parse::<f32>().ok().map(|v| bytemuck::bytes_of(&v).to_vec()),
+            ValueType::F64 => s.parse::<f64>().ok().map(|v| bytemuck::bytes_of(&v).to_vec()),
+            ValueType::String => Some(s.as_bytes().to_vec()),
+            ValueType::Bytes => crate::utils::hex::decode(&s.replace(' ', "")),
+        }
+    }
+
+    /// Orden según el tipo (little-endian); cadenas y bytes se comparan byte a byte
+    pub fn compare(&self, a: &[u8], b: &[u8]) -> Ordering {
+        fn typed<T: bytemuck::Pod + PartialOrd>(a: &[u8], b: &[u8]) -> Option<Ordering> {
+            read_pod::<T>(a)?.partial_cmp(&read_pod::<T>(b)?)
+        }
+        let ordering = match self {
+            ValueType::I32 => typed::<i32>(a, b),
+            ValueType::I64 => typed::<i64>(a, b),
+            ValueType::U32 => typed::<u32>(a, b),
+            ValueType::U64 => typed::<u64>(a, b),
+            ValueType::F32 => read_pod::<f32>(a).zip(read_pod::<f32>(b)).map(|(a, b)| a.total_cmp(&b)),
+            ValueType::F64 => read_pod::<f64>(a).zip(read_pod::<f64>(b)).map(|(a, b)| a.total_cmp(&b)),
+            ValueType::String | ValueType::Bytes => None,
+        };
+        ordering.unwrap_or_else(|| a.cmp(b))
+    }
+
+    /// Valor numérico como f64; `None` para cadenas y bytes
+    pub fn read_f64(&self, bytes: &[u8]) -> Option<f64> {
+        match self {
+            ValueType::I32 => read_pod::<i32>(bytes).map(|v| v as f64),
+            ValueType::I64 => read_pod::<i64>(bytes).map(|v| v as f64),
+            ValueType::U32 => read_pod::<u32>(bytes).map(|v| v as f64),
+            ValueType::U64 => read_pod::<u64>(bytes).map(|v| v as f64),
+            ValueType::F32 => read_pod::<f32>(bytes).map(|v| v as f64),
+            ValueType::F64 => read_pod::<f64>(bytes),
+            ValueType::String | ValueType::Bytes => None,
+        }
+    }
+
+    /// ¿`new - old == delta`? Exacto para enteros; con tolerancia relativa para floats
+    pub fn changed_by(&self, old: &[u8], new: &[u8], delta: f64) -> bool {
+        fn int<T: bytemuck::Pod + Into<i128>>(old: &[u8], new: &[u8], delta: f64) -> bool {
+            match (read_pod::<T>(old), read_pod::<T>(new)) {
+                (Some(old), Some(new)) => delta.fract() == 0.0 && new.into() - old.into() == delta as i128,
+                _ => false,
+            }
+        }
+        match self {
+            ValueType::I32 => int::<i32>(old, new, delta),
+            ValueType::I64 => int::<i64>(old, new, delta),
+            ValueType::U32 => int::<u32>(old, new, delta),
+            ValueType::U64 => int::<u64>(old, new, delta),
+            ValueType::F32 | ValueType::F64 => match (self.read_f64(old), self.read_f64(new)) {
+                (Some(old), Some(new)) => {
+                    let tolerance = if *self == ValueType::F32 { 1e-5 } else { 1e-9 };
+                    ((new - old) - delta).abs() <= tolerance * delta.abs().max(old.abs()).max(1.0)
+                }
+                _ => false,
+            },
+            ValueType::String | ValueType::Bytes => false,
+        }
+    }
+}
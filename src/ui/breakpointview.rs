@@ -0,0 +1,324 @@
+use super::super::memory::breakpoint::{ArmedBreakpoints, BreakHit, Breakpoint, Condition};
+use super::super::memory::error::describe_error;
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::ptrace::TraceEvent;
+use super::super::memory::region::{code_location, find_region, MemoryRegion};
+use super::super::utils::input::{get_input, parse_address, parse_position};
+use super::display::{clear_screen, print_header};
+use super::term::{print_at, FullScreen};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::{Color, ResetColor, SetForegroundColor},
+    terminal, ExecutableCommand,
+};
+use std::error::Error;
+use std::fs::File;
+use std::io::{stdout, BufWriter, Stdout, Write};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Puntos de ruptura software (int3/BRK) con registro de los registros en cada disparo
+pub fn breakpoint_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+
+        stdout()
+            .execute(SetForegroundColor(Color::Magenta))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║{:^80}║", format!("SOFTWARE BREAKPOINTS - {}", process_mem.describe()));
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ [1] ➕ Add breakpoint                                                          ║");
+        println!("║ [2] ➖ Remove breakpoint                                                       ║");
+        println!("║ [3] ▶️  Run and log hits                                                        ║");
+        println!("║ [0] ⬅️  Back                                                                   ║");
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+        print_breakpoints(process_mem);
+
+        match get_input("\n> Enter your choice: ").as_str() {
+            "1" => add_breakpoint(process_mem)?,
+            "2" => remove_breakpoint(process_mem),
+            "3" => {
+                run_breakpoints(process_mem)?;
+                continue;
+            }
+            "0" => return Ok(()),
+            _ => println!("❌ Invalid choice"),
+        }
+        get_input("\nPress Enter to continue...");
+    }
+}
+
+fn print_breakpoints(process_mem: &ProcessMemory) {
+    if process_mem.breakpoints.is_empty() {
+        println!("\nNo breakpoints defined.");
+        return;
+    }
+    println!("\n{:<4} {:<18} {:<32} {:>8}  Condition", "#", "Address", "Location", "Hits");
+    for (i, breakpoint) in process_mem.breakpoints.iter().enumerate() {
+        println!(
+            "{:<4} 0x{:<16x} {:<32} {:>8}  {}",
+            i + 1,
+            breakpoint.address,
+            code_location(&process_mem.regions, breakpoint.address),
+            breakpoint.hits,
+            breakpoint.condition.as_ref().map_or("-".to_string(), |c| c.to_string())
+        );
+    }
+}
+
+fn add_breakpoint(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    let saved = process_mem.get_saved_addresses().to_vec();
+    for (i, (address, description)) in saved.iter().enumerate() {
+        println!("#{:<3} {:016x}  {}", i + 1, address, description);
+    }
+    let input = get_input("\nCode address (hex) or saved address #N: ");
+    let address = match input.strip_prefix('#') {
+        Some(position) => parse_position(position, saved.len()).map(|i| saved[i].0),
+        None => parse_address(&input),
+    };
+    let Some(address) = address else {
+        println!("❌ Invalid address");
+        return Ok(());
+    };
+    process_mem.refresh_regions()?;
+    if !find_region(&process_mem.regions, address).is_some_and(|r| r.permissions.contains('x')) {
+        println!("❌ 0x{:x} is not in an executable region", address);
+        return Ok(());
+    }
+    if process_mem.breakpoints.iter().any(|b| b.address == address) {
+        println!("❌ There is already a breakpoint at 0x{:x}", address);
+        return Ok(());
+    }
+
+    let text = get_input("Condition (e.g. rdi == 5, x0 > 0x10; Enter for always): ");
+    let condition = if text.is_empty() {
+        None
+    } else {
+        match Condition::parse(&text) {
+            Ok(condition) => Some(condition),
+            Err(e) => {
+                println!("❌ {}", e);
+                return Ok(());
+            }
+        }
+    };
+    println!("✅ Breakpoint at {}", code_location(&process_mem.regions, address));
+    process_mem.breakpoints.push(Breakpoint { address, condition, hits: 0 });
+    Ok(())
+}
+
+fn remove_breakpoint(process_mem: &mut ProcessMemory) {
+    match parse_position(&get_input("Breakpoint # to remove: "), process_mem.breakpoints.len()) {
+        Some(index) => {
+            let breakpoint = process_mem.breakpoints.remove(index);
+            println!("✅ Removed breakpoint at 0x{:x}", breakpoint.address);
+        }
+        None => println!("❌ Invalid selection"),
+    }
+}
+
+fn run_breakpoints(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    if process_mem.breakpoints.is_empty() {
+        println!("❌ Add a breakpoint first");
+        get_input("Press Enter to continue...");
+        return Ok(());
+    }
+    let paused_here = !process_mem.is_paused();
+    process_mem.pause()?;
+    let result = log_hits(process_mem);
+    if paused_here {
+        process_mem.resume();
+    }
+    let (log, skipped) = match result {
+        Ok(result) => result,
+        Err(e) => {
+            println!("❌ Cannot set breakpoints: {}", describe_error(e.as_ref()));
+            get_input("Press Enter to continue...");
+            return Ok(());
+        }
+    };
+
+    println!("\n{} hits logged, {} skipped by their condition", log.len(), skipped);
+    for hit in log.iter().rev().take(10).rev() {
+        println!("\n{:.3}s TID {} at {}", hit.elapsed.as_secs_f64(), hit.tid, code_location(&process_mem.regions, hit.address));
+        for row in hit.registers.chunks(4) {
+            let line: Vec<String> = row.iter().map(|(name, value)| format!("{:>6} 0x{:016x}", name, value)).collect();
+            println!("{}", line.join("  "));
+        }
+    }
+    if !log.is_empty() {
+        let path = get_input("\nExport register log to CSV (path, or Enter to skip): ");
+        if !path.is_empty() {
+            match export_hits(&log, &path) {
+                Ok(_) => println!("✅ Exported {} hits to {}", log.len(), path),
+                Err(e) => println!("❌ Error exporting hits: {}", e),
+            }
+        }
+    }
+    get_input("\nPress Enter to continue...");
+    Ok(())
+}
+
+/// Escribe los traps, deja correr el proceso y registra los disparos hasta que se pulse Esc.
+/// Al salir los hilos quedan detenidos y los bytes originales restaurados.
+fn log_hits(process_mem: &mut ProcessMemory) -> Result<(Vec<BreakHit>, u64), Box<dyn Error>> {
+    process_mem.refresh_regions()?;
+    let regions = process_mem.regions.clone();
+    let mut breakpoints = std::mem::take(&mut process_mem.breakpoints);
+    let mut armed = match ArmedBreakpoints::arm(process_mem, &breakpoints) {
+        Ok(armed) => armed,
+        Err(e) => {
+            process_mem.breakpoints = breakpoints;
+            return Err(e.into());
+        }
+    };
+    if let Some(tracer) = process_mem.tracer_mut() {
+        tracer.run();
+    }
+
+    let started = Instant::now();
+    let mut status = String::new();
+    // Los errores de terminal no cortan con return: antes hay que restaurar los bytes
+    let screen = FullScreen::enter();
+    let mut out = stdout();
+    let mut result: Result<(), Box<dyn Error>> = match &screen {
+        Ok(_) => crossterm::execute!(out, terminal::Clear(terminal::ClearType::All)).map_err(Into::into),
+        Err(e) => Err(e.to_string().into()),
+    };
+
+    while result.is_ok() {
+        let mut trapped = Vec::new();
+        if let Some(tracer) = process_mem.tracer_mut() {
+            while let Some(event) = tracer.poll() {
+                match event {
+                    TraceEvent::Trap(tid) => trapped.push(tid),
+                    TraceEvent::ThreadExited(tid) => status = format!("Thread {} exited", tid),
+                }
+            }
+        }
+        for tid in trapped {
+            if !armed.handle_trap(process_mem, &mut breakpoints, tid) {
+                if let Some(tracer) = process_mem.tracer_mut() {
+                    tracer.forward_trap(tid);
+                    tracer.cont(tid);
+                }
+            }
+        }
+        let threads = process_mem.tracer().map_or(0, |t| t.threads().len());
+        if threads == 0 {
+            status = "Process exited".to_string();
+        }
+
+        result = draw_log(&mut out, process_mem.pid, threads, started, &armed, &breakpoints, &regions, &status);
+        if result.is_err() || threads == 0 {
+            break;
+        }
+        match event::poll(POLL_INTERVAL) {
+            Ok(true) => match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press && matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) => break,
+                Ok(Event::Resize(_, _)) => {
+                    let _ = crossterm::execute!(out, terminal::Clear(terminal::ClearType::All));
+                }
+                Ok(_) => {}
+                Err(e) => result = Err(e.into()),
+            },
+            Ok(false) => {}
+            Err(e) => result = Err(e.into()),
+        }
+    }
+    drop(screen);
+
+    // Un trap sin atender mataría el proceso: los hilos parados encima se retroceden
+    if let Some(tracer) = process_mem.tracer_mut() {
+        for tid in tracer.interrupt_all() {
+            if !armed.rewind(tid) {
+                tracer.forward_trap(tid);
+            }
+        }
+    }
+    armed.disarm(process_mem);
+    process_mem.breakpoints = breakpoints;
+    result.map(|_| (std::mem::take(&mut armed.log), armed.skipped))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_log(
+    out: &mut Stdout,
+    pid: u32,
+    threads: usize,
+    started: Instant,
+    armed: &ArmedBreakpoints,
+    breakpoints: &[Breakpoint],
+    regions: &[MemoryRegion],
+    status: &str,
+) -> Result<(), Box<dyn Error>> {
+    let (width, height) = terminal::size()?;
+    let title = format!(
+        " BREAKPOINTS {} │ PID {} │ {} threads │ {:.0}s │ {} hits, {} skipped",
+        breakpoints.len(),
+        pid,
+        threads,
+        started.elapsed().as_secs_f64(),
+        armed.log.len(),
+        armed.skipped
+    );
+    print_at(out, 0, 0, width, &title, Color::Cyan)?;
+    let mut y = 1;
+    for breakpoint in breakpoints {
+        let text = format!(
+            "{:>8}  {:<32} {}",
+            breakpoint.hits,
+            code_location(regions, breakpoint.address),
+            breakpoint.condition.as_ref().map_or(String::new(), |c| format!("if {}", c))
+        );
+        print_at(out, 0, y, width, &text, Color::Yellow)?;
+        y += 1;
+    }
+    print_at(out, 0, y, width, &format!("{:>9} {:<8} {:<20} Registers", "Time", "TID", "Location"), Color::DarkCyan)?;
+    y += 1;
+
+    // Últimos disparos abajo, con los primeros registros generales
+    let rows = height.saturating_sub(y + 1) as usize;
+    let recent = &armed.log[armed.log.len().saturating_sub(rows)..];
+    for row in 0..rows {
+        let text = recent
+            .get(row)
+            .map(|hit| {
+                let registers: Vec<String> = hit.registers.iter().take(6).map(|(name, value)| format!("{}={:x}", name, value)).collect();
+                format!(
+                    "{:>8.3}s {:<8} {:<20} {}",
+                    hit.elapsed.as_secs_f64(),
+                    hit.tid,
+                    code_location(regions, hit.address),
+                    registers.join(" ")
+                )
+            })
+            .unwrap_or_default();
+        print_at(out, 0, y + row as u16, width, &text, Color::White)?;
+    }
+    let help = "Esc/q: stop and restore original bytes";
+    print_at(out, 0, height - 1, width, if status.is_empty() { help } else { status }, Color::DarkGrey)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn export_hits(log: &[BreakHit], path: &str) -> Result<(), Box<dyn Error>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let Some(first) = log.first() else {
+        return Ok(());
+    };
+    let names: Vec<&str> = first.registers.iter().map(|(name, _)| *name).collect();
+    writeln!(out, "seconds,tid,address,{}", names.join(","))?;
+    for hit in log {
+        let values: Vec<String> = hit.registers.iter().map(|(_, value)| format!("{:x}", value)).collect();
+        writeln!(out, "{:.6},{},{:x},{}", hit.elapsed.as_secs_f64(), hit.tid, hit.address, values.join(","))?;
+    }
+    out.flush()?;
+    Ok(())
+}
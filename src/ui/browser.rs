@@ -0,0 +1,199 @@
+use super::super::memory::process::ProcessMemory;
+use super::super::types::ValueType;
+use super::display::{clear_screen, print_header, truncate};
+use super::hexview::hex_editor;
+use super::super::utils::input::{get_input, parse_position, parse_selection};
+use std::error::Error;
+use std::io::stdout;
+use crossterm::{ExecutableCommand, style::{Color, SetForegroundColor, ResetColor}};
+
+const PAGE_SIZE: usize = 20;
+const MAX_SORTED_RESULTS: usize = 20_000_000; // Ordenar necesita un índice en RAM
+const MAX_BULK_SAVE: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortOrder {
+    Address,
+    ValueAscending,
+    ValueDescending,
+}
+
+/// Navegador paginado de los resultados de búsqueda con valor actual y anterior
+pub fn result_browser(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    if process_mem.search_results.is_empty() {
+        println!("❌ No search results to browse");
+        get_input("Press Enter to continue...");
+        return Ok(());
+    }
+
+    let value_type = process_mem.value_type.unwrap_or(ValueType::Bytes);
+    let total = process_mem.search_results.len();
+    let pages = total.div_ceil(PAGE_SIZE);
+    let mut page = 0;
+    let mut sort = SortOrder::Address;
+    let mut order: Option<Vec<usize>> = None; // Permutación de índices cuando no se ordena por dirección
+
+    loop {
+        clear_screen();
+        print_header();
+
+        let first = page * PAGE_SIZE;
+        let last = (first + PAGE_SIZE).min(total);
+
+        stdout()
+            .execute(SetForegroundColor(Color::Magenta))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║                              SEARCH RESULTS                                   ║");
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!(
+            "║ {} results │ type {} │ page {}/{} │ sorted by {}",
+            total,
+            value_type.name(),
+            page + 1,
+            pages,
+            match sort {
+                SortOrder::Address => "address",
+                SortOrder::ValueAscending => "value ↑",
+                SortOrder::ValueDescending => "value ↓",
+            }
+        );
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ {:<8} │ {:<16} │ {:<22} │ Previous", "#", "Address", "Current");
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        for position in first..last {
+            let index = order.as_ref().map_or(position, |o| o[position]);
+            let (addr, previous) = process_mem.search_results.get(index)?;
+            let current = match process_mem.read_memory(addr, previous.len()) {
+                Ok(bytes) => value_type.format(&bytes),
+                Err(_) => "??".to_string(),
+            };
+            let marker = if current == value_type.format(&previous) { " " } else { "*" };
+            println!(
+                "║ {:<8} │ {:016x} │ {:<21}{} │ {}",
+                position + 1,
+                addr,
+                truncate(&current, 21),
+                marker,
+                truncate(&value_type.format(&previous), 22)
+            );
+        }
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ [n] Next page  [p] Previous page  [g] Go to page  [r] Refresh values          ║");
+        println!("║ [s] Sort  [v] Open hit in hex editor  [a] Add hits to saved addresses  [0] Back║");
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+
+        let choice = get_input("\n> Enter choice: ");
+
+        match choice.as_str() {
+            "n" => page = (page + 1).min(pages - 1),
+            "p" => page = page.saturating_sub(1),
+            "g" => match get_input(&format!("Go to page (1-{}): ", pages)).parse::<usize>() {
+                Ok(n) if n >= 1 && n <= pages => page = n - 1,
+                _ => {
+                    println!("❌ Invalid page");
+                    get_input("Press Enter to continue...");
+                }
+            },
+            "r" | "" => {}
+            "s" => {
+                println!("\n[1] Address  [2] Value ascending  [3] Value descending");
+                let new_sort = match get_input("> Sort by: ").as_str() {
+                    "1" => SortOrder::Address,
+                    "2" => SortOrder::ValueAscending,
+                    "3" => SortOrder::ValueDescending,
+                    _ => continue,
+                };
+                if new_sort != SortOrder::Address && total > MAX_SORTED_RESULTS {
+                    println!(
+                        "❌ Too many results to sort by value ({} > {}), filter first",
+                        total, MAX_SORTED_RESULTS
+                    );
+                    get_input("Press Enter to continue...");
+                    continue;
+                }
+                println!("⏳ Sorting {} results...", total);
+                order = sort_results(process_mem, value_type, new_sort)?;
+                sort = new_sort;
+                page = 0;
+            }
+            "v" => {
+                let Some(position) = parse_position(&get_input("Result # to view: "), total) else {
+                    continue;
+                };
+                let index = order.as_ref().map_or(position, |o| o[position]);
+                let (addr, _) = process_mem.search_results.get(index)?;
+                hex_editor(process_mem, addr)?;
+            }
+            "a" => {
+                let selection = get_input("Result #s to save (e.g. 1,3,5-9, 'page' or 'all'): ");
+                let positions = match selection.as_str() {
+                    "page" => (first..last).collect(),
+                    "all" => (0..total).collect(),
+                    _ => match parse_selection(&selection, total) {
+                        Some(positions) => positions,
+                        None => {
+                            println!("❌ Invalid selection");
+                            get_input("Press Enter to continue...");
+                            continue;
+                        }
+                    },
+                };
+                if positions.len() > MAX_BULK_SAVE {
+                    println!("❌ Too many addresses to save at once (max {})", MAX_BULK_SAVE);
+                    get_input("Press Enter to continue...");
+                    continue;
+                }
+                let desc = get_input("Description for saved addresses: ");
+                let desc = if desc.is_empty() { "Search result".to_string() } else { desc };
+                let value = process_mem.value_type.map(|t| (t, process_mem.search_results.value_size()));
+                for position in &positions {
+                    let index = order.as_ref().map_or(*position, |o| o[*position]);
+                    let (addr, _) = process_mem.search_results.get(index)?;
+                    let description = if positions.len() > 1 {
+                        format!("{} #{}", desc, position + 1)
+                    } else {
+                        desc.clone()
+                    };
+                    process_mem.save_address(addr, description, value);
+                }
+                println!("✅ Saved {} addresses", positions.len());
+                get_input("Press Enter to continue...");
+            }
+            "0" => return Ok(()),
+            _ => {
+                println!("❌ Invalid choice");
+                get_input("Press Enter to continue...");
+            }
+        }
+    }
+}
+
+fn sort_results(
+    process_mem: &ProcessMemory,
+    value_type: ValueType,
+    sort: SortOrder,
+) -> Result<Option<Vec<usize>>, Box<dyn Error>> {
+    if sort == SortOrder::Address {
+        return Ok(None);
+    }
+    let size = process_mem.search_results.value_size();
+    let mut values = Vec::with_capacity(process_mem.search_results.len() * size);
+    for entry in process_mem.search_results.iter() {
+        let (_, value) = entry?;
+        values.extend_from_slice(&value);
+    }
+    let mut order: Vec<usize> = (0..process_mem.search_results.len()).collect();
+    order.sort_by(|&a, &b| {
+        let ordering = value_type.compare(&values[a * size..(a + 1) * size], &values[b * size..(b + 1) * size]);
+        if sort == SortOrder::ValueDescending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    Ok(Some(order))
+}
@@ -0,0 +1,240 @@
+use super::super::memory::error::describe_error;
+use super::super::memory::inspect::{encode, InspectType};
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::region::code_location;
+use super::super::memory::registers::Registers;
+use super::super::utils::input::{get_input, parse_position, parse_selection};
+use super::super::utils::procfs::{list_thread_info, state_name, ThreadInfo};
+use super::breakpointview::breakpoint_menu;
+use super::display::{clear_screen, print_header, truncate};
+use super::watchpointview::watchpoint_menu;
+use crossterm::{ExecutableCommand, style::{Color, SetForegroundColor, ResetColor}};
+use std::error::Error;
+use std::io::stdout;
+
+/// Control del proceso con ptrace: pausa/reanudación, escrituras atómicas, hilos, registros,
+/// watchpoints y puntos de ruptura
+pub fn debug_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+
+        stdout()
+            .execute(SetForegroundColor(Color::Yellow))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║{:^80}║", format!("PROCESS CONTROL - {}", process_mem.describe()));
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        if process_mem.is_paused() {
+            println!("║ [1] ▶️  Resume process                                                          ║");
+        } else {
+            println!("║ [1] ⏸️  Pause process (stop all threads)                                        ║");
+        }
+        println!("║ [2] 📝 Atomic batch write to saved addresses                                   ║");
+        println!("║ [3] 🧵 Threads & registers                                                     ║");
+        println!("║ [4] 🎯 Find what writes to an address (hardware watchpoint)                    ║");
+        println!("║ [5] 🛑 Software breakpoints (log registers on hit)                             ║");
+        println!("║ [0] ⬅️  Back to main menu                                                      ║");
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+
+        match get_input("\n> Enter your choice: ").as_str() {
+            "1" => toggle_pause(process_mem),
+            "2" => batch_write(process_mem),
+            "3" => {
+                threads_menu(process_mem)?;
+                continue;
+            }
+            "4" => {
+                watchpoint_menu(process_mem)?;
+                continue;
+            }
+            "5" => {
+                breakpoint_menu(process_mem)?;
+                continue;
+            }
+            "0" => return Ok(()),
+            _ => println!("❌ Invalid choice"),
+        }
+        get_input("\nPress Enter to continue...");
+    }
+}
+
+/// Lista de hilos con dónde se ejecuta cada uno; los registros exigen tener el proceso en
+/// pausa, así que se pausa mientras se está aquí si no lo estaba ya
+fn threads_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    // El estado se lee antes de pausar: después todos aparecerían como "traced"
+    let threads = list_thread_info(process_mem.pid);
+    let paused_here = !process_mem.is_paused();
+    process_mem.pause()?;
+    process_mem.refresh_regions()?;
+    let result = threads_loop(process_mem, &threads);
+    if paused_here {
+        process_mem.resume();
+    }
+    result
+}
+
+fn threads_loop(process_mem: &mut ProcessMemory, threads: &[ThreadInfo]) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║{:^80}║", format!("THREADS - {} ({})", process_mem.describe(), threads.len()));
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        println!("{:<4} {:<8} {:<16} {:<11} {:<18} Location", "#", "TID", "Name", "State", "PC");
+        for (i, thread) in threads.iter().enumerate() {
+            let (pc, location) = match Registers::read(thread.tid) {
+                Ok(registers) => (
+                    format!("0x{:x}", registers.pc()),
+                    code_location(&process_mem.regions, registers.pc()),
+                ),
+                Err(_) => ("-".to_string(), "(not stopped)".to_string()),
+            };
+            println!(
+                "{:<4} {:<8} {:<16} {:<11} {:<18} {}",
+                i + 1,
+                thread.tid,
+                truncate(&thread.name, 16),
+                state_name(thread.state),
+                pc,
+                location
+            );
+        }
+
+        let input = get_input("\n> # to view/edit registers, Enter to go back: ");
+        if input.is_empty() {
+            return Ok(());
+        }
+        match parse_position(&input, threads.len()) {
+            Some(index) => registers_view(process_mem, &threads[index])?,
+            None => {
+                println!("❌ Invalid selection");
+                get_input("Press Enter to continue...");
+            }
+        }
+    }
+}
+
+fn registers_view(process_mem: &mut ProcessMemory, thread: &ThreadInfo) -> Result<(), Box<dyn Error>> {
+    let mut registers = Registers::read(thread.tid)?;
+    loop {
+        clear_screen();
+        print_header();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║{:^80}║", format!("REGISTERS - TID {} ({})", thread.tid, thread.name));
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        println!("PC {}   SP 0x{:x}", code_location(&process_mem.regions, registers.pc()), registers.sp());
+        println!();
+        for row in registers.general().chunks(3) {
+            let line: Vec<String> = row.iter().map(|(name, value)| format!("{:>8} 0x{:016x}", name, value)).collect();
+            println!("{}", line.join("   "));
+        }
+
+        let floating = registers.floating();
+        if floating.is_empty() {
+            println!("\nFP registers not available");
+        } else {
+            println!();
+            for (reg, bytes) in &floating {
+                let hex: String = bytes.iter().rev().map(|b| format!("{:02x}", b)).collect();
+                // Los vectoriales se muestran también como dos f64
+                let lanes = if reg.size == 16 {
+                    let lane = |at: usize| f64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+                    format!("  f64 [{:?}, {:?}]", lane(0), lane(8))
+                } else {
+                    String::new()
+                };
+                println!("{:>8} 0x{}{}", reg.name, hex, lanes);
+            }
+        }
+
+        let input = get_input("\nSet register (e.g. rax=0x10, xmm0=f64:1.5) or Enter to return: ");
+        if input.is_empty() {
+            return Ok(());
+        }
+        let Some((name, value)) = input.split_once('=') else {
+            println!("❌ Use name=value");
+            get_input("Press Enter to continue...");
+            continue;
+        };
+        let mut changed = registers.clone();
+        match changed.set(name, value) {
+            Ok(_) => match changed.write() {
+                Ok(_) => registers = Registers::read(thread.tid)?,
+                Err(e) => {
+                    println!("❌ {}", describe_error(&e));
+                    get_input("Press Enter to continue...");
+                }
+            },
+            Err(e) => {
+                println!("❌ {}", e);
+                get_input("Press Enter to continue...");
+            }
+        }
+    }
+}
+
+fn toggle_pause(process_mem: &mut ProcessMemory) {
+    if process_mem.resume() {
+        println!("▶️  Process {} resumed", process_mem.pid);
+        return;
+    }
+    match process_mem.pause() {
+        Ok(threads) => {
+            println!("⏸️  Process {} paused ({} threads stopped)", process_mem.pid, threads);
+            println!("It stays paused while you use other menus; resume here or it resumes on exit.");
+        }
+        Err(e) => println!("❌ {}", describe_error(&e)),
+    }
+}
+
+/// Pide un valor para cada dirección elegida y los escribe todos con el proceso en pausa
+fn batch_write(process_mem: &mut ProcessMemory) {
+    let saved = process_mem.get_saved_addresses().to_vec();
+    if saved.is_empty() {
+        println!("❌ No saved addresses; save some from the search results or write menu first");
+        return;
+    }
+    println!("\n{:<4} │ {:<16} │ Description", "ID", "Address");
+    for (i, (address, description)) in saved.iter().enumerate() {
+        println!("{:<4} │ {:016x} │ {}", i + 1, address, description);
+    }
+
+    let Some(selection) = parse_selection(&get_input("\nAddresses to write (e.g. 1,3,5-7): "), saved.len()) else {
+        println!("❌ Invalid selection");
+        return;
+    };
+    let spec = get_input("Value type for all of them (e.g. i32, u16be, f64, utf8): ");
+    let Some((kind, endian)) = InspectType::parse_spec(&spec) else {
+        println!("❌ Unknown type: {}", spec);
+        return;
+    };
+
+    let mut writes = Vec::new();
+    for index in selection {
+        let (address, description) = &saved[index];
+        let value = get_input(&format!("  #{} 0x{:x} {} = (Enter to skip) ", index + 1, address, description));
+        if value.is_empty() {
+            continue;
+        }
+        match encode(kind, endian, &value) {
+            Ok(bytes) => writes.push((*address, bytes)),
+            Err(e) => {
+                println!("❌ {}; nothing was written", e);
+                return;
+            }
+        }
+    }
+    if writes.is_empty() {
+        println!("Nothing to write.");
+        return;
+    }
+
+    match process_mem.write_batch(&writes) {
+        Ok(_) => println!("✅ Wrote {} values with all threads stopped", writes.len()),
+        Err(e) => println!("❌ Batch write failed, earlier writes were undone: {}", describe_error(&e)),
+    }
+}
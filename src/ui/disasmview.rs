@@ -0,0 +1,316 @@
+use super::super::memory::disasm::{decode, decode_all, previous_start, Instruction, MAX_INSTRUCTION};
+use super::super::memory::patch::Patch;
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::region::{code_location, find_region};
+use super::super::memory::symbols::SymbolCache;
+use super::super::utils::input::parse_address;
+use super::hexview::hex_editor;
+use super::patchview::{add_patch, remove_patch, toggle_patch};
+use super::term::{print_at, print_highlighted, read_line, FullScreen};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    style::Color,
+    terminal,
+};
+use std::error::Error;
+use std::io::{stdout, Stdout, Write};
+use std::time::Duration;
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+const SHOWN_BYTES: usize = 8; // Bytes por fila; los de instrucciones más largas se cortan con "+"
+const LOOK_BEHIND: u64 = 64; // Bytes que se redecodifican para encontrar la instrucción anterior
+
+enum Row {
+    Label(String),
+    Code(Instruction),
+}
+
+struct DisasmView<'a> {
+    process_mem: &'a mut ProcessMemory,
+    top: u64,
+    cursor: u64,
+    rows: usize,
+    visible: Vec<u64>, // Direcciones de las instrucciones en pantalla
+    back: Vec<u64>, // Direcciones desde las que se siguió un salto
+    symbols: SymbolCache,
+    status: String,
+    quit: bool,
+}
+
+/// Desensamblado navegable de una región ejecutable, con símbolos y destinos de salto
+pub fn disasm_view(process_mem: &mut ProcessMemory, address: u64) -> Result<(), Box<dyn Error>> {
+    let mut view = DisasmView {
+        process_mem,
+        top: address,
+        cursor: address,
+        rows: 1,
+        visible: Vec::new(),
+        back: Vec::new(),
+        symbols: SymbolCache::default(),
+        status: String::new(),
+        quit: false,
+    };
+
+    let _screen = FullScreen::enter()?;
+    let mut out = stdout();
+    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+
+    while !view.quit {
+        view.draw(&mut out)?;
+        if event::poll(REFRESH_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    view.handle_key(key.code, key.modifiers)?;
+                }
+                Event::Resize(_, _) => {
+                    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+impl DisasmView<'_> {
+    /// Límite de la región ejecutable que contiene la dirección (no se decodifica fuera de ella)
+    fn region_bounds(&self, address: u64) -> Option<(u64, u64)> {
+        find_region(&self.process_mem.regions, address)
+            .filter(|r| r.permissions.contains('x'))
+            .map(|r| (r.start, r.end))
+    }
+
+    fn decode_at(&mut self, address: u64) -> Option<Instruction> {
+        let (_, end) = self.region_bounds(address)?;
+        let len = (end - address).min(MAX_INSTRUCTION as u64) as usize;
+        let bytes = self.process_mem.read_memory(address, len).ok()?;
+        decode(&bytes, address)
+    }
+
+    fn next_of(&mut self, address: u64) -> u64 {
+        self.decode_at(address).map_or(address, |i| i.next())
+    }
+
+    fn previous_of(&mut self, address: u64) -> u64 {
+        let Some((start, _)) = self.region_bounds(address) else {
+            return address;
+        };
+        let from = address.saturating_sub(LOOK_BEHIND).max(start);
+        let Ok(bytes) = self.process_mem.read_memory(from, (address - from) as usize) else {
+            return address;
+        };
+        previous_start(&bytes, address).unwrap_or(address)
+    }
+
+    fn jump_to(&mut self, address: u64) -> bool {
+        if self.region_bounds(address).is_none() {
+            self.status = format!("0x{:x} is not in an executable region", address);
+            return false;
+        }
+        self.top = address;
+        self.cursor = address;
+        true
+    }
+
+    fn keep_cursor_visible(&mut self) {
+        if self.cursor < self.top {
+            self.top = self.cursor;
+        } else if !self.visible.is_empty() && !self.visible.contains(&self.cursor) {
+            // Por debajo: se baja la vista hasta que entre (las etiquetas ocupan filas)
+            let last = *self.visible.last().unwrap();
+            if self.cursor > last {
+                let mut top = self.top;
+                for _ in 0..self.rows {
+                    top = self.next_of(top);
+                    if self.lines(top).iter().any(|row| matches!(row, Row::Code(i) if i.address == self.cursor)) {
+                        break;
+                    }
+                }
+                self.top = top;
+            } else {
+                self.top = self.cursor;
+            }
+        }
+    }
+
+    /// Filas a mostrar desde `top`: instrucciones y una etiqueta al principio de cada símbolo
+    fn lines(&mut self, top: u64) -> Vec<Row> {
+        let mut rows = Vec::new();
+        let Some((_, end)) = self.region_bounds(top) else {
+            return rows;
+        };
+        let len = (end - top).min((self.rows * MAX_INSTRUCTION) as u64) as usize;
+        let Ok(bytes) = self.process_mem.read_memory(top, len) else {
+            return rows;
+        };
+        for instruction in decode_all(&bytes, top) {
+            if rows.len() >= self.rows || instruction.next() > top + len as u64 {
+                break;
+            }
+            if let Some(name) = self.symbols.resolve(&self.process_mem.regions, instruction.address) {
+                if !name.contains('+') && rows.len() + 1 < self.rows {
+                    rows.push(Row::Label(format!("<{}>:", name)));
+                }
+            }
+            rows.push(Row::Code(instruction));
+        }
+        rows
+    }
+
+    fn patch_at(&self, address: u64) -> Option<usize> {
+        self.process_mem
+            .patches
+            .iter()
+            .position(|p| address >= p.address && address < p.address + p.len() as u64)
+    }
+
+    fn describe(&mut self, address: u64) -> String {
+        let location = code_location(&self.process_mem.regions, address);
+        match self.symbols.resolve(&self.process_mem.regions, address) {
+            Some(symbol) => format!("{} <{}>", location, symbol),
+            None => location,
+        }
+    }
+
+    fn prompt(&mut self, label: &str) -> Result<Option<String>, Box<dyn Error>> {
+        let (_, height) = terminal::size()?;
+        Ok(read_line(label, height - 1)?)
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<(), Box<dyn Error>> {
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        self.status.clear();
+        match code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('q') if ctrl => self.quit = true,
+            KeyCode::Down => self.cursor = self.next_of(self.cursor),
+            KeyCode::Up => self.cursor = self.previous_of(self.cursor),
+            KeyCode::PageDown => {
+                for _ in 0..self.rows {
+                    self.cursor = self.next_of(self.cursor);
+                }
+                self.top = self.cursor;
+            }
+            KeyCode::PageUp => {
+                for _ in 0..self.rows {
+                    self.cursor = self.previous_of(self.cursor);
+                }
+                self.top = self.cursor;
+            }
+            KeyCode::Enter => match self.decode_at(self.cursor) {
+                Some(Instruction { branch: true, target: Some(target), .. }) => {
+                    let from = self.cursor;
+                    if self.jump_to(target) {
+                        self.back.push(from);
+                    }
+                }
+                _ => self.status = "Not a direct branch".to_string(),
+            },
+            KeyCode::Backspace => match self.back.pop() {
+                Some(address) => {
+                    self.cursor = address;
+                    self.top = address;
+                }
+                None => self.status = "No previous location".to_string(),
+            },
+            KeyCode::Char('g') if ctrl => {
+                if let Some(input) = self.prompt("Go to address or symbol: ")? {
+                    let address = parse_address(&input).or_else(|| self.symbols.find(&self.process_mem.regions, input.trim()));
+                    match address {
+                        Some(address) => {
+                            let from = self.cursor;
+                            if self.jump_to(address) {
+                                self.back.push(from);
+                            }
+                        }
+                        None => self.status = format!("Unknown address or symbol: {}", input),
+                    }
+                }
+            }
+            KeyCode::Char('n') if !ctrl => {
+                self.status = match Patch::nop(self.process_mem, self.cursor, 1) {
+                    Ok(patch) => add_patch(self.process_mem, patch, true),
+                    Err(e) => e,
+                };
+            }
+            KeyCode::Char('j') if !ctrl => {
+                self.status = match Patch::force_branch(self.process_mem, self.cursor) {
+                    Ok(patch) => add_patch(self.process_mem, patch, true),
+                    Err(e) => e,
+                };
+            }
+            KeyCode::Char('t') | KeyCode::Char('r') if !ctrl => match self.patch_at(self.cursor) {
+                Some(index) if code == KeyCode::Char('t') => self.status = toggle_patch(self.process_mem, index),
+                Some(index) => self.status = remove_patch(self.process_mem, index),
+                None => self.status = "No patch at the cursor".to_string(),
+            },
+            KeyCode::Char('e') if ctrl => {
+                hex_editor(self.process_mem, self.cursor)?;
+                crossterm::execute!(stdout(), terminal::Clear(terminal::ClearType::All))?;
+            }
+            _ => {}
+        }
+        self.keep_cursor_visible();
+        Ok(())
+    }
+
+    fn draw(&mut self, out: &mut Stdout) -> Result<(), Box<dyn Error>> {
+        let (width, height) = terminal::size()?;
+        self.rows = height.saturating_sub(2).max(1) as usize;
+        self.keep_cursor_visible();
+
+        let region = find_region(&self.process_mem.regions, self.cursor)
+            .map(|r| format!("{:x}-{:x} {} {}", r.start, r.end, r.permissions, r.pathname))
+            .unwrap_or_else(|| "unmapped".to_string());
+        let location = self.describe(self.cursor);
+        let title = format!(" DISASSEMBLY │ PID {} │ {} │ {}", self.process_mem.pid, location, region);
+        print_at(out, 0, 0, width, &title, Color::Cyan)?;
+
+        let rows = self.lines(self.top);
+        self.visible = rows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Code(instruction) => Some(instruction.address),
+                Row::Label(_) => None,
+            })
+            .collect();
+        let breakpoints: Vec<u64> = self.process_mem.breakpoints.iter().map(|b| b.address).collect();
+        for y in 0..self.rows {
+            let line_y = y as u16 + 1;
+            match rows.get(y) {
+                Some(Row::Label(label)) => print_at(out, 0, line_y, width, label, Color::Yellow)?,
+                Some(Row::Code(instruction)) => {
+                    let bytes: Vec<String> = instruction.bytes.iter().take(SHOWN_BYTES).map(|b| format!("{:02x}", b)).collect();
+                    let mut shown = bytes.join(" ");
+                    if instruction.bytes.len() > SHOWN_BYTES {
+                        shown.push('+');
+                    }
+                    let mark = if breakpoints.contains(&instruction.address) {
+                        '●'
+                    } else if self.patch_at(instruction.address).is_some() {
+                        '*'
+                    } else {
+                        ' '
+                    };
+                    let mut text = format!("{}{:016x}  {:<24} {:<40}", mark, instruction.address, shown, instruction.text);
+                    if let Some(target) = instruction.target {
+                        text.push_str(&format!(" ; {}", self.describe(target)));
+                    }
+                    if instruction.address == self.cursor {
+                        print_highlighted(out, 0, line_y, width, &text)?;
+                    } else {
+                        let color = if instruction.branch { Color::Green } else { Color::White };
+                        print_at(out, 0, line_y, width, &text, color)?;
+                    }
+                }
+                None => print_at(out, 0, line_y, width, "", Color::White)?,
+            }
+        }
+
+        let help = "↑↓ PgUp/PgDn  Enter: follow  Bksp: back  ^G: goto  ^E: hex  n: nop  j: force jump  t/r: toggle/revert patch  Esc";
+        let status = if self.status.is_empty() { help } else { self.status.as_str() };
+        print_at(out, 0, height - 1, width, status, Color::DarkGrey)?;
+        out.flush()?;
+        Ok(())
+    }
+}
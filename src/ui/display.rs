@@ -3,6 +3,7 @@ use crossterm::{
     ExecutableCommand,
 };
 use std::io::stdout;
+use super::super::memory::inspect::{decode, describe_pointer, read_pointer, Endian, InspectType};
 use super::super::memory::region::MemoryRegion;
 
 pub fn clear_screen() {
@@ -37,12 +38,13 @@ pub fn list_regions(pid: u32, regions: &[MemoryRegion]) {
     println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
     println!("║                            MEMORY REGIONS FOR PID {}                            ║", pid);
     println!("╠════════════════════════════════════════════════════════════════════════════════╣");
-    println!("║ {:<16} │ {:<16} │ {:<8} │ {}", "Start", "End", "Perms", "Path");
+    println!("║ {:<4} │ {:<16} │ {:<16} │ {:<8} │ Path", "ID", "Start", "End", "Perms");
     println!("╠════════════════════════════════════════════════════════════════════════════════╣");
 
-    for region in regions {
+    for (i, region) in regions.iter().enumerate() {
         println!(
-            "║ {:016x} │ {:016x} │ {:<8} │ {}",
+            "║ {:<4} │ {:016x} │ {:016x} │ {:<8} │ {}",
+            i + 1,
             region.start,
             region.end,
             region.permissions,
@@ -58,3 +60,66 @@ pub fn list_regions(pid: u32, regions: &[MemoryRegion]) {
         .execute(ResetColor)
         .unwrap();
 }
+
+pub fn print_hex_dump(addr: u64, data: &[u8]) {
+    stdout()
+        .execute(SetForegroundColor(Color::Yellow))
+        .unwrap();
+    println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+    println!("║                              MEMORY CONTENTS                                  ║");
+    println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+    for (i, chunk) in data.chunks(16).enumerate() {
+        print!("║ {:016x}: ", addr + (i * 16) as u64);
+        for byte in chunk {
+            print!("{:02x} ", byte);
+        }
+        for _ in chunk.len()..16 {
+            print!("   ");
+        }
+        print!("│ ");
+        for byte in chunk {
+            if *byte >= 32 && *byte <= 126 {
+                print!("{}", *byte as char);
+            } else {
+                print!(".");
+            }
+        }
+        println!(" ║");
+    }
+    println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+    stdout()
+        .execute(ResetColor)
+        .unwrap();
+}
+
+/// Filas del inspector de datos: cada tipo en little y big endian
+pub fn inspector_lines(regions: &[MemoryRegion], bytes: &[u8]) -> Vec<String> {
+    InspectType::ALL
+        .iter()
+        .map(|&kind| {
+            let le = decode(kind, Endian::Little, bytes).unwrap_or_else(|| "-".to_string());
+            let be = if kind.has_endian() {
+                decode(kind, Endian::Big, bytes).unwrap_or_else(|| "-".to_string())
+            } else {
+                String::new()
+            };
+            let mut line = format!("{:<6} {:<26} {}", kind.name(), truncate(&le, 26), truncate(&be, 26));
+            if kind == InspectType::Pointer {
+                if let Some(pointer) = read_pointer(bytes, Endian::Little) {
+                    line = format!("{:<6} {:<26} → {}", kind.name(), le, describe_pointer(regions, pointer));
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() > max {
+        let mut t: String = s.chars().take(max - 3).collect();
+        t.push_str("...");
+        t
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,379 @@
+use super::super::memory::inspect::{encode, InspectType, INSPECT_BYTES};
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::region::find_region;
+use super::super::memory::search::find_next;
+use super::super::utils::hex;
+use super::super::utils::input::parse_address;
+use super::display::inspector_lines;
+use super::term::{print_at, read_line, FullScreen};
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{stdout, Stdout, Write};
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+const HIGHLIGHT_TIME: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+struct HexEditor<'a> {
+    process_mem: &'a mut ProcessMemory,
+    top: u64,
+    cursor: u64,
+    column: Column,
+    pending_nibble: Option<u8>, // Primer dígito hex tecleado del byte bajo el cursor
+    bytes_per_row: u64,
+    rows: u64,
+    last_seen: HashMap<u64, u8>, // Último valor visto de cada byte visible
+    changed: HashMap<u64, Instant>, // Bytes que cambiaron solos y cuándo
+    last_search: Option<Vec<u8>>,
+    inspector: bool, // Panel de decodificación bajo el cursor
+    status: String,
+    quit: bool,
+}
+
+/// Editor hexadecimal interactivo sobre la memoria del proceso
+pub fn hex_editor(process_mem: &mut ProcessMemory, address: u64) -> Result<(), Box<dyn Error>> {
+    let mut editor = HexEditor {
+        process_mem,
+        top: address & !0xf,
+        cursor: address,
+        column: Column::Hex,
+        pending_nibble: None,
+        bytes_per_row: 16,
+        rows: 1,
+        last_seen: HashMap::new(),
+        changed: HashMap::new(),
+        last_search: None,
+        inspector: false,
+        status: String::new(),
+        quit: false,
+    };
+
+    let _screen = FullScreen::enter()?;
+    let mut out = stdout();
+    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+
+    while !editor.quit {
+        editor.draw(&mut out)?;
+        if event::poll(REFRESH_INTERVAL)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    editor.handle_key(key.code, key.modifiers)?;
+                }
+                Event::Resize(_, _) => {
+                    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+impl HexEditor<'_> {
+    fn move_cursor(&mut self, delta: i64) {
+        self.cursor = self.cursor.saturating_add_signed(delta);
+        self.pending_nibble = None;
+    }
+
+    fn jump_to(&mut self, address: u64) {
+        self.cursor = address;
+        self.top = address - address % self.bytes_per_row;
+        self.pending_nibble = None;
+    }
+
+    fn keep_cursor_visible(&mut self) {
+        let row_start = self.cursor - self.cursor % self.bytes_per_row;
+        let view_bytes = self.rows * self.bytes_per_row;
+        if row_start < self.top {
+            self.top = row_start;
+        } else if row_start - self.top >= view_bytes {
+            self.top = row_start - (view_bytes - self.bytes_per_row);
+        }
+        // La última fila visible no puede pasar del final del espacio de direcciones
+        self.top = self.top.min(u64::MAX - view_bytes + 1);
+    }
+
+    fn write_byte(&mut self, value: u8) {
+        match self.process_mem.write_memory(self.cursor, &[value]) {
+            Ok(_) => {
+                // Nuestros propios cambios no se resaltan como cambios del proceso
+                self.last_seen.insert(self.cursor, value);
+                self.changed.remove(&self.cursor);
+                self.status = format!("Wrote {:02x} at 0x{:x}", value, self.cursor);
+                self.move_cursor(1);
+            }
+            Err(e) => {
+                self.status = format!("Write failed at 0x{:x}: {}", self.cursor, e);
+                self.pending_nibble = None;
+            }
+        }
+    }
+
+    fn prompt(&mut self, label: &str) -> Result<Option<String>, Box<dyn Error>> {
+        let (_, height) = terminal::size()?;
+        Ok(read_line(label, height - 1)?)
+    }
+
+    fn search_next(&mut self) {
+        let Some(pattern) = self.last_search.clone() else {
+            self.status = "No search pattern (Ctrl-F to search)".to_string();
+            return;
+        };
+        self.status = "Searching...".to_string();
+        let Some(from) = self.cursor.checked_add(1) else {
+            self.status = "Pattern not found after cursor".to_string();
+            return;
+        };
+        match find_next(self.process_mem, from, &pattern) {
+            Ok(Some(address)) => {
+                self.jump_to(address);
+                self.status = format!("Found at 0x{:x}", address);
+            }
+            Ok(None) => self.status = "Pattern not found after cursor".to_string(),
+            Err(e) => self.status = format!("Search failed: {}", e),
+        }
+    }
+
+    /// Escribe en el cursor un valor con uno de los tipos del inspector
+    fn write_typed(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(spec) = self.prompt("Write as type (e.g. i32, u16be, f64, utf8): ")? else {
+            return Ok(());
+        };
+        let Some((kind, endian)) = InspectType::parse_spec(&spec) else {
+            self.status = format!("Unknown type: {}", spec);
+            return Ok(());
+        };
+        let Some(value) = self.prompt(&format!("{} value: ", spec))? else {
+            return Ok(());
+        };
+        self.status = match encode(kind, endian, &value) {
+            Ok(bytes) => match self.process_mem.write_memory(self.cursor, &bytes) {
+                Ok(_) => {
+                    for (i, byte) in bytes.iter().enumerate() {
+                        if let Some(addr) = self.cursor.checked_add(i as u64) {
+                            self.last_seen.insert(addr, *byte);
+                        }
+                    }
+                    format!("Wrote {} {} at 0x{:x}", spec, value, self.cursor)
+                }
+                Err(e) => format!("Write failed at 0x{:x}: {}", self.cursor, e),
+            },
+            Err(e) => e,
+        };
+        Ok(())
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<(), Box<dyn Error>> {
+        let bpr = self.bytes_per_row as i64;
+        let page = self.rows as i64 * bpr;
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+
+        match code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('q') if ctrl => self.quit = true,
+            KeyCode::Left => self.move_cursor(-1),
+            KeyCode::Right => self.move_cursor(1),
+            KeyCode::Up => self.move_cursor(-bpr),
+            KeyCode::Down => self.move_cursor(bpr),
+            KeyCode::PageUp => self.move_cursor(-page),
+            KeyCode::PageDown => self.move_cursor(page),
+            KeyCode::Home => self.move_cursor(-((self.cursor % self.bytes_per_row) as i64)),
+            KeyCode::End => self.move_cursor(bpr - 1 - (self.cursor % self.bytes_per_row) as i64),
+            KeyCode::Tab => {
+                self.column = match self.column {
+                    Column::Hex => Column::Ascii,
+                    Column::Ascii => Column::Hex,
+                };
+                self.pending_nibble = None;
+            }
+            KeyCode::Char('g') if ctrl => {
+                if let Some(input) = self.prompt("Go to address: 0x")? {
+                    match parse_address(&input) {
+                        Some(address) => self.jump_to(address),
+                        None => self.status = format!("Invalid address: {}", input),
+                    }
+                }
+            }
+            KeyCode::Char('f') if ctrl => {
+                if let Some(input) = self.prompt("Search (hex bytes, or \"text\"): ")? {
+                    let pattern = match input.strip_prefix('"') {
+                        Some(text) => Some(text.trim_end_matches('"').as_bytes().to_vec()),
+                        None => hex::decode(&input.replace(' ', "")),
+                    };
+                    match pattern {
+                        Some(pattern) if !pattern.is_empty() => {
+                            self.last_search = Some(pattern);
+                            self.search_next();
+                        }
+                        _ => self.status = format!("Invalid pattern: {}", input),
+                    }
+                }
+            }
+            KeyCode::Char('n') if ctrl => self.search_next(),
+            KeyCode::F(2) => self.inspector = !self.inspector,
+            KeyCode::F(4) => self.write_typed()?,
+            KeyCode::F(3) => self.search_next(),
+            KeyCode::Char(c) if !ctrl => match self.column {
+                Column::Hex => {
+                    if let Some(digit) = c.to_digit(16) {
+                        match self.pending_nibble.take() {
+                            Some(high) => self.write_byte(high << 4 | digit as u8),
+                            None => self.pending_nibble = Some(digit as u8),
+                        }
+                    }
+                }
+                Column::Ascii => {
+                    if c.is_ascii() && !c.is_ascii_control() {
+                        self.write_byte(c as u8);
+                    }
+                }
+            },
+            _ => {}
+        }
+        self.keep_cursor_visible();
+        Ok(())
+    }
+
+    fn draw(&mut self, out: &mut Stdout) -> Result<(), Box<dyn Error>> {
+        let (width, height) = terminal::size()?;
+        // dirección + " │ " + 3 por byte + "│ " + 1 por byte + marca de región
+        self.bytes_per_row = if width >= 16 + 3 + 16 * 4 + 2 + 20 { 16 } else { 8 };
+        let panel_rows = if self.inspector { InspectType::ALL.len() as u16 + 1 } else { 0 };
+        self.rows = height.saturating_sub(2 + panel_rows).max(1) as u64;
+        self.keep_cursor_visible();
+
+        let region = find_region(&self.process_mem.regions, self.cursor)
+            .map(|r| format!("{:x}-{:x} {} {}", r.start, r.end, r.permissions, r.pathname))
+            .unwrap_or_else(|| "unmapped".to_string());
+        let title = format!(
+            " HEX EDITOR │ PID {} │ cursor 0x{:016x} │ {} │ {}",
+            self.process_mem.pid,
+            self.cursor,
+            if self.column == Column::Hex { "HEX" } else { "ASCII" },
+            region
+        );
+        print_at(out, 0, 0, width, &title, Color::Cyan)?;
+
+        let now = Instant::now();
+        self.changed.retain(|_, t| now.duration_since(*t) < HIGHLIGHT_TIME);
+        let mut seen = HashMap::new();
+
+        for row in 0..self.rows {
+            let Some(row_addr) = self.top.checked_add(row * self.bytes_per_row) else {
+                break;
+            };
+            let data = self.process_mem.read_memory(row_addr, self.bytes_per_row as usize).ok();
+            self.draw_row(out, row as u16 + 1, width, row_addr, data.as_deref(), now)?;
+            if let Some(data) = data {
+                for (i, byte) in data.into_iter().enumerate() {
+                    if let Some(addr) = row_addr.checked_add(i as u64) {
+                        seen.insert(addr, byte);
+                    }
+                }
+            }
+        }
+        self.last_seen = seen;
+
+        if self.inspector {
+            let y = self.rows as u16 + 1;
+            let title = format!("── Inspector @ 0x{:x} ── {:<6} {:<26} Big endian", self.cursor, "Type", "Little endian");
+            print_at(out, 0, y, width, &title, Color::DarkCyan)?;
+            let bytes = match self.process_mem.read_memory(self.cursor, INSPECT_BYTES) {
+                Ok(bytes) => bytes,
+                Err(_) => self.process_mem.read_memory(self.cursor, 8).unwrap_or_default(),
+            };
+            for (i, line) in inspector_lines(&self.process_mem.regions, &bytes).iter().enumerate() {
+                print_at(out, 0, y + 1 + i as u16, width, line, Color::White)?;
+            }
+        }
+
+        let help = "Tab: hex/ascii  ^G: goto  ^F: search  ^N/F3: next  F2: inspector  F4: write typed  Esc: close";
+        let status = if self.status.is_empty() { help } else { self.status.as_str() };
+        print_at(out, 0, height - 1, width, status, Color::DarkGrey)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn draw_row(
+        &mut self,
+        out: &mut Stdout,
+        y: u16,
+        width: u16,
+        row_addr: u64,
+        data: Option<&[u8]>,
+        now: Instant,
+    ) -> Result<(), Box<dyn Error>> {
+        queue!(out, MoveTo(0, y), terminal::Clear(terminal::ClearType::CurrentLine))?;
+        queue!(out, SetForegroundColor(Color::DarkCyan), Print(format!("{:016x} │ ", row_addr)))?;
+
+        for column in [Column::Hex, Column::Ascii] {
+            for i in 0..self.bytes_per_row {
+                let Some(addr) = row_addr.checked_add(i) else {
+                    break;
+                };
+                let byte = data.map(|d| d[i as usize]);
+                if let (Some(byte), Some(old)) = (byte, self.last_seen.get(&addr)) {
+                    if byte != *old {
+                        self.changed.insert(addr, now);
+                    }
+                }
+                let text = match (column, byte) {
+                    (Column::Hex, Some(b)) if addr == self.cursor && self.pending_nibble.is_some() => {
+                        format!("{:x}_ ", self.pending_nibble.unwrap_or(b >> 4))
+                    }
+                    (Column::Hex, Some(b)) => format!("{:02x} ", b),
+                    (Column::Hex, None) => "?? ".to_string(),
+                    (Column::Ascii, Some(b)) if (32..=126).contains(&b) => (b as char).to_string(),
+                    (Column::Ascii, _) => ".".to_string(),
+                };
+                let color = if byte.is_none() {
+                    Color::DarkGrey
+                } else if self.changed.contains_key(&addr) {
+                    Color::Red
+                } else {
+                    Color::White
+                };
+                if addr == self.cursor {
+                    let attribute = if column == self.column { Attribute::Reverse } else { Attribute::Underlined };
+                    queue!(out, SetAttribute(attribute))?;
+                }
+                queue!(out, SetForegroundColor(color), Print(text), SetAttribute(Attribute::Reset))?;
+            }
+            if column == Column::Hex {
+                queue!(out, SetForegroundColor(Color::DarkCyan), Print("│ "))?;
+            }
+        }
+
+        // Marca de frontera: las regiones están alineadas a página, así que empiezan al inicio de una fila
+        let regions = &self.process_mem.regions;
+        let row_end = row_addr.checked_add(self.bytes_per_row);
+        let marker = if let Some(r) = regions.iter().find(|r| r.start == row_addr) {
+            Some(format!(" ◀ {} {}", r.permissions, r.pathname.rsplit('/').next().unwrap_or("")))
+        } else if regions.iter().any(|r| Some(r.end) == row_end) {
+            Some(" ◀ end".to_string())
+        } else {
+            None
+        };
+        if let Some(marker) = marker {
+            let used = 16 + 3 + self.bytes_per_row as u16 * 4 + 2;
+            let room = width.saturating_sub(used) as usize;
+            let marker: String = marker.chars().take(room).collect();
+            queue!(out, SetForegroundColor(Color::Yellow), Print(marker))?;
+        }
+        queue!(out, ResetColor)?;
+        Ok(())
+    }
+}
@@ -1,13 +1,40 @@
+use super::super::memory::core::write_core;
+use super::super::memory::dump::dump_regions;
+use super::super::memory::error::describe_error;
 use super::super::memory::process::ProcessMemory;
-use super::super::types::SearchFilter;
-use super::display::{clear_screen, print_header, list_regions};
-use super::super::utils::input::get_input;
-use super::super::utils::system::get_pid_by_name;
+use super::super::memory::region::find_region;
+use super::super::memory::history::describe_step;
+use super::super::memory::inspect::{encode, InspectType, INSPECT_BYTES};
+use super::super::memory::session::ScanSession;
+use super::super::memory::table::CheatTable;
+use super::super::types::{SearchFilter, ValueType};
+use super::browser::result_browser;
+use super::picker::{pick_process, wait_for_process};
+use super::debugview::debug_menu;
+use super::disasmview::disasm_view;
+use super::hexview::hex_editor;
+use super::patchview::patch_menu;
+use super::snapview::snapshot_menu;
+use super::structview::struct_menu;
+use super::watchview::watch_menu;
+use super::tui::{run_tui, TuiExit};
+use super::display::{clear_screen, inspector_lines, print_header, print_hex_dump, list_regions};
+use super::super::utils::diagnostics::{run_diagnostics, Status};
+use super::super::utils::hex;
+use super::super::utils::input::{get_input, parse_selection};
 use std::error::Error;
 use std::io::stdout;
+use std::path::Path;
 use crossterm::{ExecutableCommand, style::{Color, SetForegroundColor, ResetColor}};
 
-pub fn get_process() -> Result<u32, Box<dyn Error>> {
+/// Lo que se abre: un proceso vivo o memoria guardada (volcado o core) para escanear sin conexión
+pub enum Target {
+    Pid(u32),
+    Saved(String),
+    Waited { pid: u32, table: Option<String> }, // Proceso esperado hasta su arranque; tabla a cargar al conectar
+}
+
+pub fn get_process() -> Result<Target, Box<dyn Error>> {
     clear_screen();
     print_header();
 
@@ -17,8 +44,11 @@ pub fn get_process() -> Result<u32, Box<dyn Error>> {
     println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
     println!("║                              PROCESS SELECTION                                ║");
     println!("╠════════════════════════════════════════════════════════════════════════════════╣");
-    println!("║ [1] Search by process name                                                     ║");
+    println!("║ [1] Find process (name, command line, regex or fuzzy)                          ║");
     println!("║ [2] Enter PID directly                                                         ║");
+    println!("║ [3] Open memory dump or core file (offline)                                    ║");
+    println!("║ [4] Wait for process to start and auto-attach                                  ║");
+    println!("║ [5] Permission diagnostics for a PID                                           ║");
     println!("║ [0] Exit                                                                       ║");
     println!("╚════════════════════════════════════════════════════════════════════════════════╝");
     stdout()
@@ -29,43 +59,16 @@ pub fn get_process() -> Result<u32, Box<dyn Error>> {
 
     match choice.as_str() {
         "1" => {
-            let process_name = get_input("Enter process name: ");
-            println!("\nSearching for process '{}'...", process_name);
-
-            let pids = get_pid_by_name(&process_name)?;
-
-            if pids.is_empty() {
-                println!("❌ No process found with name: {}", process_name);
-                get_input("Press Enter to continue...");
-                return get_process();
-            }
-
-            if pids.len() == 1 {
-                println!("✅ Found process: PID {}", pids[0]);
-                get_input("Press Enter to continue...");
-                return Ok(pids[0]);
-            }
-
-            println!("\n🔍 Multiple processes found:");
-            for (i, pid) in pids.iter().enumerate() {
-                println!("  [{}] PID {}", i + 1, pid);
-            }
-
-            let selection = get_input(&format!("Select process (1-{}): ", pids.len()));
-            let index: usize = selection.parse()?;
-
-            if index == 0 || index > pids.len() {
-                println!("❌ Invalid selection");
-                get_input("Press Enter to try again...");
-                return get_process();
+            let query = get_input("Search (name, /regex/, ~fuzzy or @package, Enter for all): ");
+            match pick_process(&query)? {
+                Some(pid) => Ok(Target::Pid(pid)),
+                None => get_process(),
             }
-
-            Ok(pids[index - 1])
         }
         "2" => {
             let pid_str = get_input("Enter PID: ");
             match pid_str.parse::<u32>() {
-                Ok(pid) => Ok(pid),
+                Ok(pid) => Ok(Target::Pid(pid)),
                 Err(_) => {
                     println!("❌ Invalid PID");
                     get_input("Press Enter to try again...");
@@ -73,6 +76,43 @@ pub fn get_process() -> Result<u32, Box<dyn Error>> {
                 }
             }
         }
+        "3" => {
+            let path = get_input("Enter dump directory or core file path: ");
+            Ok(Target::Saved(path))
+        }
+        "4" => {
+            let query = get_input("Process to wait for (name, /regex/, ~fuzzy or @package): ");
+            if query.is_empty() {
+                return get_process();
+            }
+            let module = get_input("Wait for module before attaching (e.g. libil2cpp.so, Enter to skip): ");
+            let table = get_input("Cheat table to load after attaching (Enter to skip): ");
+            match wait_for_process(&query, Some(module.as_str()).filter(|m| !m.is_empty())) {
+                Ok(Some(process)) => {
+                    println!("✅ Found process: PID {} ({})", process.pid, process.command());
+                    Ok(Target::Waited {
+                        pid: process.pid,
+                        table: Some(table).filter(|t| !t.is_empty()),
+                    })
+                }
+                Ok(None) => get_process(),
+                Err(e) => {
+                    println!("❌ Cannot wait for module '{}': {}", module, describe_error(e.as_ref()));
+                    get_input("Press Enter to try again...");
+                    get_process()
+                }
+            }
+        }
+        "5" => {
+            match get_input("Enter PID: ").parse::<u32>() {
+                Ok(pid) => diagnostics_menu(pid, None)?,
+                Err(_) => {
+                    println!("❌ Invalid PID");
+                    get_input("Press Enter to try again...");
+                }
+            }
+            get_process()
+        }
         "0" => {
             println!("👋 Goodbye!");
             std::process::exit(0);
@@ -86,7 +126,32 @@ pub fn get_process() -> Result<u32, Box<dyn Error>> {
 }
 
 pub fn main_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    // Se empieza en la vista a pantalla completa; el menú clásico queda a una tecla
+    let mut full_screen = true;
     loop {
+        // Avisar una sola vez, aunque la salida se haya detectado antes en una lectura
+        process_mem.check_alive();
+        if process_mem.has_exited() && !process_mem.reattach_offered {
+            reattach_menu(process_mem)?;
+            continue;
+        }
+        if full_screen {
+            full_screen = false;
+            match run_tui(process_mem) {
+                Ok(TuiExit::Menu) => {}
+                Ok(TuiExit::Exited) => {
+                    // Ofrecer reconectar y volver a la vista completa
+                    full_screen = true;
+                    continue;
+                }
+                Ok(TuiExit::ChangeProcess) => return Ok(()),
+                Ok(TuiExit::Quit) => quit(process_mem),
+                Err(e) => {
+                    println!("❌ {}", describe_error(e.as_ref()));
+                    get_input("Press Enter to continue...");
+                }
+            }
+        }
         clear_screen();
         print_header();
 
@@ -94,7 +159,7 @@ pub fn main_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>>
             .execute(SetForegroundColor(Color::Green))
             .unwrap();
         println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
-        println!("║                              MAIN MENU - PID {}                              ║", process_mem.pid);
+        println!("║{:^80}║", format!("MAIN MENU - {}", process_mem.describe()));
         println!("╠════════════════════════════════════════════════════════════════════════════════╣");
         println!("║ [1] 📋 List memory regions                                                     ║");
         println!("║ [2] 🔍 Search for values                                                       ║");
@@ -103,6 +168,23 @@ pub fn main_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>>
         println!("║ [5] 🔄 Filter search results                                                  ║");
         println!("║ [6] 📍 Manage saved addresses                                                 ║");
         println!("║ [7] 🔄 Change process                                                          ║");
+        println!("║ [8] 💾 Save/Load scan session                                                  ║");
+        println!("║ [9] 🕑 Scan history (undo/redo)                                                ║");
+        println!("║ [10] 📑 Browse search results                                                  ║");
+        println!("║ [11] 🖥️  Full-screen mode                                                       ║");
+        println!("║ [12] 🧩 Structure dissector                                                    ║");
+        println!("║ [13] 👀 Watch memory range for changes                                         ║");
+        println!("║ [14] 💽 Dump memory to disk                                                    ║");
+        println!("║ [15] 📸 Snapshots & diff                                                       ║");
+        // Sin conexión (volcado, core o proceso terminado) no hay nada que diagnosticar
+        if process_mem.is_live() {
+            println!("║ [16] 🩺 Permission diagnostics                                                 ║");
+        }
+        println!("║ [17] ⏯️  Process control (pause/resume, batch write, threads, registers)        ║");
+        println!("║ [18] 🩹 Code patches (NOP, force branch, toggle, revert)                       ║");
+        if process_mem.has_exited() {
+            println!("║ [R] 🔁 Reattach to a new instance of the exited process                        ║");
+        }
         println!("║ [0] 🚪 Exit                                                                    ║");
         println!("╚════════════════════════════════════════════════════════════════════════════════╝");
         stdout()
@@ -111,26 +193,119 @@ pub fn main_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>>
 
         let choice = get_input("\n> Enter your choice: ");
 
-        match choice.as_str() {
-            "1" => list_regions_menu(process_mem)?,
-            "2" => search_menu(process_mem)?,
-            "3" => read_memory_menu(process_mem)?,
-            "4" => write_memory_menu(process_mem)?,
-            "5" => filter_menu(process_mem)?,
-            "6" => manage_addresses_menu(process_mem)?,
+        // Los errores de una acción se muestran (con consejo si lo hay) sin salir del programa
+        let result = match choice.as_str() {
+            "1" => list_regions_menu(process_mem),
+            "2" => search_menu(process_mem),
+            "3" => read_memory_menu(process_mem),
+            "4" => write_memory_menu(process_mem),
+            "5" => filter_menu(process_mem),
+            "6" => manage_addresses_menu(process_mem),
             "7" => return Ok(()),
-            "0" => {
-                println!("👋 Goodbye!");
-                std::process::exit(0);
+            "8" => session_menu(process_mem),
+            "9" => history_menu(process_mem),
+            "10" => result_browser(process_mem),
+            "11" => {
+                full_screen = true;
+                Ok(())
             }
+            "12" => struct_menu(process_mem),
+            "13" => watch_menu(process_mem),
+            "14" => dump_menu(process_mem),
+            "15" => snapshot_menu(process_mem),
+            "16" if process_mem.is_live() => diagnostics_menu(process_mem.pid, Some(process_mem)),
+            "17" => debug_menu(process_mem),
+            "18" => patch_menu(process_mem),
+            "r" | "R" if process_mem.has_exited() => reattach_menu(process_mem),
+            "0" => quit(process_mem),
             _ => {
                 println!("❌ Invalid choice");
                 get_input("Press Enter to continue...");
+                Ok(())
             }
+        };
+        if let Err(e) = result {
+            println!("❌ {}", describe_error(e.as_ref()));
+            get_input("Press Enter to continue...");
         }
     }
 }
 
+fn quit(process_mem: &mut ProcessMemory) -> ! {
+    // exit() no ejecuta Drop: soltar los hilos si el proceso está en pausa
+    process_mem.resume();
+    println!("👋 Goodbye!");
+    std::process::exit(0);
+}
+
+/// El proceso terminó: ofrece esperar a una nueva instancia y reconectar con la tabla remapeada
+fn reattach_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    let Some(query) = process_mem.reattach_query().map(str::to_string) else {
+        return Ok(());
+    };
+    process_mem.reattach_offered = true;
+    println!("\n⚠️  Process {} has exited.", process_mem.pid);
+    if get_input(&format!("Wait for a new instance matching '{}' and reattach? (Y/n): ", query))
+        .eq_ignore_ascii_case("n")
+    {
+        println!("Memory actions will fail until you reattach ([R]) or change process ([7]).");
+        get_input("Press Enter to continue...");
+        return Ok(());
+    }
+
+    match wait_for_process(&query, None)? {
+        Some(process) => match process_mem.reattach(process.pid) {
+            Ok(stats) => {
+                println!("✅ Reattached to PID {}", process.pid);
+                println!(
+                    "📍 Saved addresses: {} kept, {} remapped, {} dropped; scan results cleared",
+                    stats.absolute, stats.remapped, stats.dropped
+                );
+                if stats.patches + stats.patches_dropped > 0 {
+                    println!(
+                        "🩹 Code patches: {} found by signature, {} dropped (module missing or code changed)",
+                        stats.patches, stats.patches_dropped
+                    );
+                }
+            }
+            Err(e) => println!("❌ Failed to attach to PID {}: {}", process.pid, describe_error(&e)),
+        },
+        None => println!("Cancelled."),
+    }
+    get_input("Press Enter to continue...");
+    Ok(())
+}
+
+fn diagnostics_menu(pid: u32, session: Option<&mut ProcessMemory>) -> Result<(), Box<dyn Error>> {
+    clear_screen();
+    print_header();
+
+    stdout()
+        .execute(SetForegroundColor(Color::Cyan))
+        .unwrap();
+    println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+    println!("║{:^80}║", format!("PERMISSION DIAGNOSTICS - PID {}", pid));
+    println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+    stdout()
+        .execute(ResetColor)
+        .unwrap();
+
+    let checks = run_diagnostics(pid, session);
+    for check in &checks {
+        // Los consejos de `describe_error` vienen en líneas aparte: se sangran bajo la comprobación
+        let detail = check.detail.replace('\n', &format!("\n{:23}", ""));
+        println!("{} {:<22} {}", check.status.icon(), check.name, detail);
+    }
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failed == 0 {
+        println!("\n✅ No blocking problems found");
+    } else {
+        println!("\n❌ {} blocking problem(s); see the details above", failed);
+    }
+    get_input("\nPress Enter to continue...");
+    Ok(())
+}
+
 fn list_regions_menu(process_mem: &ProcessMemory) -> Result<(), Box<dyn Error>> {
     list_regions(process_mem.pid, &process_mem.regions);
     get_input("\nPress Enter to return to main menu...");
@@ -251,12 +426,12 @@ fn search_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
         "7" => {
             let value = get_input("Enter string: ");
             println!("🔍 Searching for string: '{}'...", value);
-            process_mem.search_pattern(value.as_bytes())?
+            process_mem.search_pattern(value.as_bytes(), ValueType::String)?
         }
         "8" => {
             let value_str = get_input("Enter hex bytes (e.g., DEADBEEF): ");
             let value_str = value_str.trim();
-            if value_str.len() % 2 != 0 {
+            if !value_str.len().is_multiple_of(2) {
                 println!("❌ Invalid hex string length");
                 get_input("Press Enter to continue...");
                 return Ok(());
@@ -268,7 +443,7 @@ fn search_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
             match bytes {
                 Ok(bytes) => {
                     println!("🔍 Searching for bytes: {:02X?}...", bytes);
-                    process_mem.search_pattern(&bytes)?
+                    process_mem.search_pattern(&bytes, ValueType::Bytes)?
                 }
                 Err(_) => {
                     println!("❌ Invalid hex string");
@@ -285,15 +460,14 @@ fn search_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
         }
     };
 
-    println!("\n✅ Found {} matches:", matches.len());
-    for (i, addr) in matches.iter().take(20).enumerate() {
-        println!("  [{}] 0x{:016x}", i + 1, addr);
-    }
-    if matches.len() > 20 {
-        println!("  ... and {} more matches", matches.len() - 20);
+    println!("\n✅ Found {} matches:", matches);
+    if process_mem.search_results.is_spilled() {
+        println!("💽 Large result set, stored in a temporary file");
     }
 
-    get_input("\nPress Enter to continue...");
+    if matches > 0 && get_input("\nOpen result browser? (y/N): ").eq_ignore_ascii_case("y") {
+        return result_browser(process_mem);
+    }
     Ok(())
 }
 
@@ -313,6 +487,8 @@ fn filter_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
     println!("║ [4] 📈 Increased value                                                         ║");
     println!("║ [5] 📉 Decreased value                                                         ║");
     println!("║ [6] 📏 Value in range                                                          ║");
+    println!("║ [7] ➕ Increased by amount                                                     ║");
+    println!("║ [8] ➖ Decreased by amount                                                     ║");
     println!("║ [0] ⬅️  Back to main menu                                                      ║");
     println!("╚════════════════════════════════════════════════════════════════════════════════╝");
     stdout()
@@ -323,18 +499,14 @@ fn filter_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
 
     let (filter, value) = match choice.as_str() {
         "1" => {
-            let value_str = get_input("Enter exact value (hex bytes or number): ");
-            let bytes = if value_str.starts_with("0x") {
-                let value_str = value_str.trim_start_matches("0x");
-                (0..value_str.len())
-                    .step_by(2)
-                    .map(|i| u8::from_str_radix(&value_str[i..i + 2], 16))
-                    .collect::<Result<Vec<u8>, _>>()
-                    .map_err(|_| "Invalid hex string")?
-            } else {
-                // Asumimos que es un número (i32 por defecto, podría expandirse)
-                let value: i32 = value_str.parse().map_err(|_| "Invalid number")?;
-                bytemuck::bytes_of(&value).to_vec()
+            // Mismo tipo que la búsqueda, para que el tamaño coincida con el de los resultados
+            let value_type = process_mem.value_type.unwrap_or(ValueType::I32);
+            let value_str = get_input(&format!("Enter exact {} value (or 0x-prefixed hex bytes): ", value_type.name()));
+            let bytes = match value_str.strip_prefix("0x") {
+                Some(hex) => hex::decode(hex).ok_or("Invalid hex string")?,
+                None => value_type
+                    .parse(&value_str)
+                    .ok_or_else(|| format!("Invalid {} value", value_type.name()))?,
             };
             (SearchFilter::Exact, Some(bytes))
         }
@@ -349,6 +521,13 @@ fn filter_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
             let max: f64 = max_str.parse().map_err(|_| "Invalid maximum value")?;
             (SearchFilter::Range(min, max), None)
         }
+        "7" | "8" => {
+            let delta: f64 = get_input("Enter amount: ").parse().map_err(|_| "Invalid amount")?;
+            match choice.as_str() {
+                "7" => (SearchFilter::IncreasedBy(delta), None),
+                _ => (SearchFilter::DecreasedBy(delta), None),
+            }
+        }
         "0" => return Ok(()),
         _ => {
             println!("❌ Invalid choice");
@@ -357,16 +536,19 @@ fn filter_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
         }
     };
 
-    let matches = process_mem.filter_results(filter, value)?;
-    println!("\n✅ Found {} matches after filtering:", matches.len());
-    for (i, addr) in matches.iter().take(20).enumerate() {
-        println!("  [{}] 0x{:016x}", i + 1, addr);
+    let stats = process_mem.filter_results(filter, value)?;
+    let matches = stats.matches;
+    println!("\n✅ Found {} matches after filtering:", matches);
+    if stats.unreadable > 0 {
+        println!("⚠️  {} result(s) dropped: their memory could no longer be read", stats.unreadable);
     }
-    if matches.len() > 20 {
-        println!("  ... and {} more matches", matches.len() - 20);
+    if process_mem.search_results.is_spilled() {
+        println!("💽 Large result set, stored in a temporary file");
     }
 
-    get_input("\nPress Enter to continue...");
+    if matches > 0 && get_input("\nOpen result browser? (y/N): ").eq_ignore_ascii_case("y") {
+        return result_browser(process_mem);
+    }
     Ok(())
 }
 
@@ -387,48 +569,31 @@ fn read_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error
     let addr_str = get_input("\nEnter address (hex, with or without 0x): ");
     let size_str = get_input("Enter size (bytes): ");
 
-    let addr_clean = if addr_str.starts_with("0x") {
-        &addr_str[2..]
-    } else {
-        &addr_str
-    };
+    let addr_clean = addr_str.strip_prefix("0x").unwrap_or(&addr_str);
 
     match (u64::from_str_radix(addr_clean, 16), size_str.parse::<usize>()) {
         (Ok(addr), Ok(size)) => {
             println!("\n📖 Reading {} bytes from 0x{:016x}...", size, addr);
             match process_mem.read_memory(addr, size) {
                 Ok(data) => {
-                    stdout()
-                        .execute(SetForegroundColor(Color::Yellow))
-                        .unwrap();
-                    println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
-                    println!("║                              MEMORY CONTENTS                                  ║");
-                    println!("╠════════════════════════════════════════════════════════════════════════════════╣");
-                    for (i, chunk) in data.chunks(16).enumerate() {
-                        print!("║ {:08x}: ", addr as u32 + (i * 16) as u32);
-                        for byte in chunk {
-                            print!("{:02x} ", byte);
-                        }
-                        for _ in chunk.len()..16 {
-                            print!("   ");
-                        }
-                        print!("│ ");
-                        for byte in chunk {
-                            if *byte >= 32 && *byte <= 126 {
-                                print!("{}", *byte as char);
-                            } else {
-                                print!(".");
-                            }
-                        }
-                        println!(" ║");
-                    }
-                    println!("╚════════════════════════════════════════════════════════════════════════════════╝");
-                    stdout()
-                        .execute(ResetColor)
-                        .unwrap();
-                }
-                Err(e) => println!("❌ Error reading memory: {}", e),
+                    print_hex_dump(addr, &data);
+                }
+                Err(e) => println!("❌ Error reading memory: {}", describe_error(&e)),
+            }
+            let choice = get_input("\n[E] Hex editor  [I] Data inspector  [D] Disassemble  [Enter] Continue: ");
+            if choice.eq_ignore_ascii_case("e") {
+                hex_editor(process_mem, addr)?;
+            } else if choice.eq_ignore_ascii_case("i") {
+                inspect_menu(process_mem, addr)?;
+            } else if choice.eq_ignore_ascii_case("d") {
+                if find_region(&process_mem.regions, addr).is_some_and(|r| r.permissions.contains('x')) {
+                    disasm_view(process_mem, addr)?;
+                } else {
+                    println!("❌ 0x{:x} is not in an executable region", addr);
+                    get_input("Press Enter to continue...");
+                }
             }
+            return Ok(());
         }
         _ => println!("❌ Invalid address or size"),
     }
@@ -437,6 +602,53 @@ fn read_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error
     Ok(())
 }
 
+fn inspect_menu(process_mem: &mut ProcessMemory, addr: u64) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+
+        let bytes = match process_mem.read_memory(addr, INSPECT_BYTES) {
+            Ok(bytes) => bytes,
+            Err(_) => process_mem.read_memory(addr, 8)?,
+        };
+
+        stdout()
+            .execute(SetForegroundColor(Color::Yellow))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║                       DATA INSPECTOR - 0x{:016x}                     ║", addr);
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ {:<6} {:<26} Big endian", "Type", "Little endian");
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        for line in inspector_lines(&process_mem.regions, &bytes) {
+            println!("║ {}", line);
+        }
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+
+        let spec = get_input("\nWrite back as type (e.g. i32, u16be, f64, utf8) or Enter to return: ");
+        if spec.is_empty() {
+            return Ok(());
+        }
+        let Some((kind, endian)) = InspectType::parse_spec(&spec) else {
+            println!("❌ Unknown type: {}", spec);
+            get_input("Press Enter to continue...");
+            continue;
+        };
+        let value = get_input(&format!("Enter {} value: ", spec));
+        match encode(kind, endian, &value) {
+            Ok(bytes) => match process_mem.write_memory(addr, &bytes) {
+                Ok(_) => println!("✅ Wrote {} bytes to 0x{:016x}", bytes.len(), addr),
+                Err(e) => println!("❌ Error writing memory: {}", describe_error(&e)),
+            },
+            Err(e) => println!("❌ {}", e),
+        }
+        get_input("Press Enter to continue...");
+    }
+}
+
 fn write_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
     clear_screen();
     print_header();
@@ -468,11 +680,7 @@ fn write_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Erro
     }
 
     let addr_str = get_input("Enter address (hex, with or without 0x): ");
-    let addr_clean = if addr_str.starts_with("0x") {
-        &addr_str[2..]
-    } else {
-        &addr_str
-    };
+    let addr_clean = addr_str.strip_prefix("0x").unwrap_or(&addr_str);
 
     let addr = match u64::from_str_radix(addr_clean, 16) {
         Ok(addr) => addr,
@@ -483,6 +691,18 @@ fn write_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Erro
         }
     };
 
+    let value_type = match choice.as_str() {
+        "1" => Some(ValueType::I32),
+        "2" => Some(ValueType::I64),
+        "3" => Some(ValueType::U32),
+        "4" => Some(ValueType::U64),
+        "5" => Some(ValueType::F32),
+        "6" => Some(ValueType::F64),
+        "7" => Some(ValueType::String),
+        "8" => Some(ValueType::Bytes),
+        _ => None,
+    };
+    let mut written_len = 0; // Para cadenas y bytes, que no tienen tamaño fijo
     let result = match choice.as_str() {
         "1" => {
             let value_str = get_input("Enter i32 value: ");
@@ -577,12 +797,13 @@ fn write_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Erro
         "7" => {
             let value = get_input("Enter string: ");
             println!("✏️ Writing string '{}' to 0x{:016x}...", value, addr);
+            written_len = value.len();
             process_mem.write_memory(addr, value.as_bytes())
         }
         "8" => {
             let value_str = get_input("Enter hex bytes (e.g., DEADBEEF): ");
             let value_str = value_str.trim();
-            if value_str.len() % 2 != 0 {
+            if !value_str.len().is_multiple_of(2) {
                 println!("❌ Invalid hex string length");
                 get_input("Press Enter to continue...");
                 return Ok(());
@@ -594,6 +815,7 @@ fn write_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Erro
             match bytes {
                 Ok(bytes) => {
                     println!("✏️ Writing bytes {:02X?} to 0x{:016x}...", bytes, addr);
+                    written_len = bytes.len();
                     process_mem.write_memory(addr, &bytes)
                 }
                 Err(_) => {
@@ -612,12 +834,13 @@ fn write_memory_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Erro
 
     match result {
         Ok(_) => println!("✅ Successfully wrote to memory!"),
-        Err(e) => println!("❌ Error writing memory: {}", e),
+        Err(e) => println!("❌ Error writing memory: {}", describe_error(&e)),
     }
 
     let desc = get_input("Enter description for this address (or leave empty): ");
     if !desc.is_empty() {
-        process_mem.save_address(addr, desc);
+        let value = value_type.map(|t| (t, t.size().unwrap_or(written_len)));
+        process_mem.save_address(addr, desc, value);
     }
 
     get_input("Press Enter to continue...");
@@ -636,6 +859,8 @@ fn manage_addresses_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn
     println!("╠════════════════════════════════════════════════════════════════════════════════╣");
     println!("║ [1] 📋 List saved addresses                                                    ║");
     println!("║ [2] ✏️  Edit saved address                                                     ║");
+    println!("║ [3] 💾 Save cheat table                                                        ║");
+    println!("║ [4] 📂 Load cheat table                                                        ║");
     println!("║ [0] ⬅️  Back to main menu                                                      ║");
     println!("╚════════════════════════════════════════════════════════════════════════════════╝");
     stdout()
@@ -651,7 +876,7 @@ fn manage_addresses_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn
             println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
             println!("║                             SAVED ADDRESSES                                   ║");
             println!("╠════════════════════════════════════════════════════════════════════════════════╣");
-            println!("║ {:<4} │ {:<16} │ {}", "ID", "Address", "Description");
+            println!("║ {:<4} │ {:<16} │ Description", "ID", "Address");
             println!("╠════════════════════════════════════════════════════════════════════════════════╣");
             for (i, (addr, desc)) in process_mem.get_saved_addresses().iter().enumerate() {
                 println!("║ {:<4} │ {:016x} │ {}", i + 1, addr, desc);
@@ -677,6 +902,25 @@ fn manage_addresses_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn
             let (_addr, _) = process_mem.get_saved_addresses()[index - 1];
             write_memory_menu(process_mem)?;
         }
+        "3" => {
+            let path = get_input("Enter file path to save: ");
+            match CheatTable::save(process_mem, &path) {
+                Ok(_) => println!(
+                    "✅ Saved {} addresses, {} layouts and {} patches to {}",
+                    process_mem.get_saved_addresses().len(),
+                    process_mem.struct_layouts.len(),
+                    process_mem.patches.len(),
+                    path
+                ),
+                Err(e) => println!("❌ Error saving cheat table: {}", e),
+            }
+            get_input("Press Enter to continue...");
+        }
+        "4" => {
+            let path = get_input("Enter cheat table path: ");
+            load_cheat_table(process_mem, &path);
+            get_input("Press Enter to continue...");
+        }
         "0" => return Ok(()),
         _ => {
             println!("❌ Invalid choice");
@@ -685,3 +929,244 @@ fn manage_addresses_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn
     }
     Ok(())
 }
+
+/// Carga una tabla de trucos mostrando cuántas direcciones se conservaron o remapearon
+pub fn load_cheat_table(process_mem: &mut ProcessMemory, path: &str) {
+    match CheatTable::load(process_mem, path) {
+        Ok(stats) => {
+            println!(
+                "✅ Loaded {} addresses ({} same address, {} remapped, {} dropped) and {} layouts",
+                stats.absolute + stats.remapped,
+                stats.absolute,
+                stats.remapped,
+                stats.dropped,
+                process_mem.struct_layouts.len()
+            );
+            if stats.patches + stats.patches_dropped > 0 {
+                let enabled = process_mem.patches.iter().filter(|p| p.enabled).count();
+                println!(
+                    "🩹 Loaded {} code patches ({} enabled), {} dropped (module missing or signature mismatch)",
+                    stats.patches, enabled, stats.patches_dropped
+                );
+            }
+        }
+        Err(e) => println!("❌ Error loading cheat table: {}", e),
+    }
+}
+
+fn session_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    clear_screen();
+    print_header();
+
+    stdout()
+        .execute(SetForegroundColor(Color::Cyan))
+        .unwrap();
+    println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+    println!("║                               SCAN SESSION                                    ║");
+    println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+    println!("║ [1] 💾 Save current scan session                                               ║");
+    println!("║ [2] 📂 Load scan session                                                       ║");
+    println!("║ [0] ⬅️  Back to main menu                                                      ║");
+    println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+    stdout()
+        .execute(ResetColor)
+        .unwrap();
+
+    let type_name = process_mem.value_type.map(|t| t.name()).unwrap_or("none");
+    println!(
+        "\nCurrent session: {} results, type {}, {} filters applied",
+        process_mem.search_results.len(),
+        type_name,
+        process_mem.filter_history.len()
+    );
+
+    let choice = get_input("\n> Enter choice: ");
+
+    match choice.as_str() {
+        "1" => {
+            if process_mem.search_results.is_empty() {
+                println!("❌ No search results to save");
+                get_input("Press Enter to continue...");
+                return Ok(());
+            }
+            let path = get_input("Enter file path to save: ");
+            match ScanSession::save(process_mem, &path) {
+                Ok(_) => println!("✅ Session saved to {}", path),
+                Err(e) => println!("❌ Error saving session: {}", e),
+            }
+        }
+        "2" => {
+            let path = get_input("Enter session file path: ");
+            let stats = match ScanSession::restore(process_mem, &path) {
+                Ok(stats) => stats,
+                Err(e) => {
+                    println!("❌ Error loading session: {}", e);
+                    get_input("Press Enter to continue...");
+                    return Ok(());
+                }
+            };
+            if stats.saved_pid != process_mem.pid {
+                println!(
+                    "ℹ️  Session was saved for PID {}, remapped to PID {} by module offsets",
+                    stats.saved_pid, process_mem.pid
+                );
+            }
+            println!(
+                "✅ Loaded {} results ({} same address, {} remapped, {} dropped)",
+                stats.absolute + stats.remapped,
+                stats.absolute,
+                stats.remapped,
+                stats.dropped
+            );
+        }
+        "0" => return Ok(()),
+        _ => println!("❌ Invalid choice"),
+    }
+
+    get_input("Press Enter to continue...");
+    Ok(())
+}
+
+fn dump_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    let format = get_input("\n[1] Regions + manifest  [2] ELF core file (loadable in gdb)  [0] Back: ");
+    match format.as_str() {
+        "1" => {}
+        "2" => return core_menu(process_mem),
+        _ => return Ok(()),
+    }
+    process_mem.refresh_regions()?;
+    list_regions(process_mem.pid, &process_mem.regions);
+
+    let selection = get_input("\nRegions to dump (e.g. 1,3,5-9, 'all' readable, or a path substring): ");
+    let selected: Vec<_> = if selection == "all" {
+        process_mem.regions.iter().collect()
+    } else if let Some(positions) = parse_selection(&selection, process_mem.regions.len()) {
+        positions.into_iter().map(|i| &process_mem.regions[i]).collect()
+    } else {
+        process_mem.regions.iter().filter(|r| !selection.is_empty() && r.pathname.contains(&selection)).collect()
+    };
+    let total = selected.len();
+    let regions: Vec<_> = selected.into_iter().filter(|r| r.permissions.starts_with('r')).cloned().collect();
+    if regions.is_empty() {
+        println!("❌ No readable regions selected");
+        get_input("Press Enter to continue...");
+        return Ok(());
+    }
+    if regions.len() < total {
+        println!("ℹ️  Skipping {} non-readable regions", total - regions.len());
+    }
+
+    let dir = get_input("Output directory: ");
+    if dir.is_empty() {
+        return Ok(());
+    }
+    let sparse = get_input("Write everything to a single sparse file? (y/N): ")
+        .eq_ignore_ascii_case("y");
+    let size: u64 = regions.iter().map(|r| r.end - r.start).sum();
+    println!("\n💽 Dumping {} regions ({} bytes)...", regions.len(), size);
+
+    match dump_regions(process_mem, &regions, Path::new(&dir), sparse) {
+        Ok(stats) => {
+            println!("✅ Dumped {} regions ({} bytes) to {}", stats.regions, stats.bytes, dir);
+            if stats.unreadable_bytes > 0 {
+                println!("⚠️  {} bytes could not be read (see 'unreadable' lines in the manifest)", stats.unreadable_bytes);
+            }
+        }
+        Err(e) => println!("❌ Error dumping memory: {}", e),
+    }
+    get_input("Press Enter to continue...");
+    Ok(())
+}
+
+fn core_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    let path = get_input("Core file path: ");
+    if path.is_empty() {
+        return Ok(());
+    }
+    println!("\n💽 Writing core file (threads are paused while dumping)...");
+    match write_core(process_mem, Path::new(&path)) {
+        Ok(stats) => {
+            println!(
+                "✅ Wrote {} with {} threads and {} memory segments",
+                path, stats.threads, stats.segments
+            );
+            if stats.registers < stats.threads {
+                println!(
+                    "⚠️  Registers captured for {} of {} threads (ptrace attach failed for the rest)",
+                    stats.registers, stats.threads
+                );
+            }
+            if stats.unreadable_bytes > 0 {
+                println!("⚠️  {} bytes could not be read and were zero-filled", stats.unreadable_bytes);
+            }
+            println!("   Open it with: gdb <executable> {}", path);
+        }
+        Err(e) => println!("❌ Error writing core file: {}", e),
+    }
+    get_input("Press Enter to continue...");
+    Ok(())
+}
+
+fn history_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+
+        stdout()
+            .execute(SetForegroundColor(Color::Cyan))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║                               SCAN HISTORY                                    ║");
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ {:<4} │ {:<12} │ Produced by", "Step", "Results");
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        let undo_steps = process_mem.history.undo_steps();
+        for (i, step) in undo_steps.iter().enumerate() {
+            println!("║ {:<4} │ {:<12} │ {}", i + 1, step.results.len(), step.describe());
+        }
+        println!(
+            "║ {:<4} │ {:<12} │ {}  ◀ current",
+            undo_steps.len() + 1,
+            process_mem.search_results.len(),
+            describe_step(process_mem.value_type, &process_mem.filter_history)
+        );
+        for (i, step) in process_mem.history.redo_steps().iter().rev().enumerate() {
+            println!(
+                "║ {:<4} │ {:<12} │ {}  (undone)",
+                undo_steps.len() + i + 2,
+                step.results.len(),
+                step.describe()
+            );
+        }
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ [1] ↩️  Undo last step                                                         ║");
+        println!("║ [2] ↪️  Redo step                                                              ║");
+        println!("║ [0] ⬅️  Back to main menu                                                      ║");
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+
+        let choice = get_input("\n> Enter choice: ");
+
+        match choice.as_str() {
+            "1" => {
+                if !process_mem.undo_scan() {
+                    println!("❌ Nothing to undo");
+                    get_input("Press Enter to continue...");
+                }
+            }
+            "2" => {
+                if !process_mem.redo_scan() {
+                    println!("❌ Nothing to redo");
+                    get_input("Press Enter to continue...");
+                }
+            }
+            "0" => return Ok(()),
+            _ => {
+                println!("❌ Invalid choice");
+                get_input("Press Enter to continue...");
+            }
+        }
+    }
+}
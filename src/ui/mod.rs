@@ -1,3 +1,15 @@
-
-pub mod menus;
+pub mod breakpointview;
+pub mod browser;
+pub mod debugview;
+pub mod disasmview;
 pub mod display;
+pub mod hexview;
+pub mod menus;
+pub mod patchview;
+pub mod picker;
+pub mod snapview;
+pub mod structview;
+pub mod term;
+pub mod tui;
+pub mod watchpointview;
+pub mod watchview;
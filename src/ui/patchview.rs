@@ -0,0 +1,199 @@
+use super::super::memory::disasm::decode_all;
+use super::super::memory::error::describe_error;
+use super::super::memory::patch::{Patch, PatchState};
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::region::code_location;
+use super::super::utils::hex;
+use super::super::utils::input::{get_input, parse_address, parse_position};
+use super::disasmview::disasm_view;
+use super::display::{clear_screen, print_header, truncate};
+use crossterm::{ExecutableCommand, style::{Color, SetForegroundColor, ResetColor}};
+use std::error::Error;
+use std::io::stdout;
+
+/// Parches de código: NOP, forzar saltos o bytes a mano, con activar/desactivar y revertir
+pub fn patch_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+        process_mem.refresh_regions()?;
+
+        stdout()
+            .execute(SetForegroundColor(Color::Yellow))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║{:^80}║", format!("CODE PATCHES - {}", process_mem.describe()));
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ [1] 🚫 NOP out instructions                                                    ║");
+        println!("║ [2] ↪️  Force a conditional branch                                              ║");
+        println!("║ [3] ✏️  Patch with custom bytes                                                 ║");
+        println!("║ [4] 🔁 Toggle patch on/off                                                     ║");
+        println!("║ [5] ↩️  Revert and remove patch                                                 ║");
+        println!("║ [6] 🔬 Show patch in disassembly                                               ║");
+        println!("║ [0] ⬅️  Back to main menu                                                      ║");
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+        print_patches(process_mem);
+
+        match get_input("\n> Enter your choice: ").as_str() {
+            "1" => new_patch(process_mem, PatchKind::Nop),
+            "2" => new_patch(process_mem, PatchKind::ForceBranch),
+            "3" => new_patch(process_mem, PatchKind::Bytes),
+            "4" => {
+                if let Some(index) = pick_patch(process_mem) {
+                    println!("{}", toggle_patch(process_mem, index));
+                }
+            }
+            "5" => {
+                if let Some(index) = pick_patch(process_mem) {
+                    println!("{}", remove_patch(process_mem, index));
+                }
+            }
+            "6" => {
+                if let Some(index) = pick_patch(process_mem) {
+                    disasm_view(process_mem, process_mem.patches[index].address)?;
+                    continue;
+                }
+            }
+            "0" => return Ok(()),
+            _ => println!("❌ Invalid choice"),
+        }
+        get_input("\nPress Enter to continue...");
+    }
+}
+
+enum PatchKind {
+    Nop,
+    ForceBranch,
+    Bytes,
+}
+
+fn print_patches(process_mem: &mut ProcessMemory) {
+    if process_mem.patches.is_empty() {
+        println!("\nNo patches yet.");
+        return;
+    }
+    println!("\n{:<4} {:<7} {:<28} {:<32} Description", "#", "State", "Location", "Bytes");
+    for i in 0..process_mem.patches.len() {
+        let patch = process_mem.patches[i].clone();
+        // "changed": el código ya no es ni el original ni el parche (otra versión o reescrito)
+        let state = match patch.state(process_mem) {
+            PatchState::Patched => "on",
+            PatchState::Original => "off",
+            PatchState::Mismatch => "changed",
+        };
+        let bytes = format!("{} → {}", hex::encode(&patch.original), hex::encode(&patch.patched));
+        println!(
+            "{:<4} {:<7} {:<28} {:<32} {}",
+            i + 1,
+            state,
+            truncate(&code_location(&process_mem.regions, patch.address), 28),
+            truncate(&bytes, 32),
+            patch.description
+        );
+    }
+}
+
+fn pick_patch(process_mem: &ProcessMemory) -> Option<usize> {
+    let index = parse_position(&get_input("Patch #: "), process_mem.patches.len());
+    if index.is_none() {
+        println!("❌ Invalid selection");
+    }
+    index
+}
+
+fn new_patch(process_mem: &mut ProcessMemory, kind: PatchKind) {
+    let saved = process_mem.get_saved_addresses().to_vec();
+    for (i, (address, description)) in saved.iter().enumerate() {
+        println!("#{:<3} {:016x}  {}", i + 1, address, description);
+    }
+    let input = get_input("\nCode address (hex) or saved address #N: ");
+    let address = match input.strip_prefix('#') {
+        Some(position) => parse_position(position, saved.len()).map(|i| saved[i].0),
+        None => parse_address(&input),
+    };
+    let Some(address) = address else {
+        println!("❌ Invalid address");
+        return;
+    };
+
+    let patch = match kind {
+        PatchKind::Nop => {
+            let count = get_input("Instructions to NOP out [1]: ").parse().unwrap_or(1);
+            Patch::nop(process_mem, address, count)
+        }
+        PatchKind::ForceBranch => Patch::force_branch(process_mem, address),
+        PatchKind::Bytes => {
+            let input = get_input("New bytes (hex, e.g. 90 90 or eb 10): ");
+            match hex::decode(&input.replace(' ', "")) {
+                Some(bytes) => {
+                    let description = get_input("Description: ");
+                    Patch::new(process_mem, address, &bytes, description)
+                }
+                None => Err(format!("Invalid hex bytes: {}", input)),
+            }
+        }
+    };
+    let patch = match patch {
+        Ok(patch) => patch,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
+
+    println!("\nAt {}:", code_location(&process_mem.regions, address));
+    let before = decode_all(&patch.original, address);
+    let after = decode_all(&patch.patched, address);
+    for (label, instructions) in [("before", &before), ("after", &after)] {
+        println!("  {}:", label);
+        for instruction in instructions {
+            println!("    {:016x}  {:<24} {}", instruction.address, hex::encode(&instruction.bytes), instruction.text);
+        }
+    }
+    let apply = !get_input("\nApply now? (Y/n): ").eq_ignore_ascii_case("n");
+    println!("{}", add_patch(process_mem, patch, apply));
+}
+
+/// Guarda el parche en la lista y, si se pide, lo aplica; devuelve el mensaje de estado
+pub fn add_patch(process_mem: &mut ProcessMemory, mut patch: Patch, apply: bool) -> String {
+    let message = if apply {
+        match patch.apply(process_mem) {
+            Ok(_) => format!("✅ Patched {} bytes at 0x{:x}", patch.len(), patch.address),
+            Err(e) => format!("❌ Saved but not applied: {}", describe_error(&e)),
+        }
+    } else {
+        format!("✅ Saved patch at 0x{:x} (off)", patch.address)
+    };
+    process_mem.patches.push(patch);
+    message
+}
+
+pub fn toggle_patch(process_mem: &mut ProcessMemory, index: usize) -> String {
+    let mut patch = process_mem.patches[index].clone();
+    if patch.state(process_mem) == PatchState::Mismatch {
+        return "❌ The code there changed since the patch was made; revert and remove it".to_string();
+    }
+    let message = match patch.toggle(process_mem) {
+        Ok(_) if patch.enabled => format!("✅ Patch at 0x{:x} on", patch.address),
+        Ok(_) => format!("✅ Patch at 0x{:x} off, original bytes restored", patch.address),
+        Err(e) => format!("❌ {}", describe_error(&e)),
+    };
+    process_mem.patches[index] = patch;
+    message
+}
+
+/// Restaura los bytes originales (si el parche está puesto, aunque el código de alrededor
+/// haya cambiado) y lo quita de la lista
+pub fn remove_patch(process_mem: &mut ProcessMemory, index: usize) -> String {
+    let mut patch = process_mem.patches[index].clone();
+    if patch.current(process_mem).as_ref() == Some(&patch.patched) {
+        if let Err(e) = patch.revert(process_mem) {
+            return format!("❌ {}", describe_error(&e));
+        }
+    }
+    process_mem.patches.remove(index);
+    format!("✅ Removed patch at 0x{:x}", patch.address)
+}
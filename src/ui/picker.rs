@@ -0,0 +1,169 @@
+use super::super::memory::error::MemoryError;
+use super::super::utils::input::get_input;
+use super::super::utils::procfs::{
+    find_module, find_processes, format_elapsed, AndroidApp, ProcessInfo, ProcessQuery, ProcessWaiter,
+};
+use super::display::{clear_screen, print_header, truncate};
+use super::term::{print_at, FullScreen};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    terminal, ExecutableCommand,
+    style::{Color, SetForegroundColor, ResetColor},
+};
+use std::error::Error;
+use std::io::{stdout, Write};
+use std::time::{Duration, Instant};
+
+const PICKER_ROWS: usize = 25;
+const CONNECTOR_INTERVAL: Duration = Duration::from_millis(50);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Lista interactiva de procesos: se filtra por subcadena, /regex/, ~fuzzy o @paquete y se elige por número.
+/// Devuelve `None` si el usuario vuelve atrás.
+pub fn pick_process(initial: &str) -> Result<Option<u32>, Box<dyn Error>> {
+    let mut query_text = initial.to_string();
+    loop {
+        let query = match ProcessQuery::parse(&query_text) {
+            Ok(query) => query,
+            Err(e) => {
+                println!("❌ Invalid search: {}", e);
+                query_text = get_input("New search: ");
+                continue;
+            }
+        };
+        let processes = find_processes(&query);
+
+        clear_screen();
+        print_header();
+        stdout()
+            .execute(SetForegroundColor(Color::Green))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║{:^80}║", format!("PROCESSES MATCHING '{}' ({})", truncate(&query_text, 30), processes.len()));
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+        print_processes(&processes);
+
+        println!("\nSearch: text (substring), /regex/, ~fuzzy or @package[:process] (Android)");
+        let input = get_input("> # to attach, new search, Enter to refresh, 0 to go back: ");
+        if input == "0" {
+            return Ok(None);
+        }
+        if input.is_empty() {
+            continue;
+        }
+        match input.parse::<usize>() {
+            Ok(n) if n >= 1 && n <= processes.len().min(PICKER_ROWS) => {
+                let process = &processes[n - 1];
+                if let Some(app) = &process.android {
+                    print_app(process, app);
+                }
+                return Ok(Some(process.pid));
+            }
+            Ok(_) => {
+                println!("❌ Invalid selection");
+                get_input("Press Enter to continue...");
+            }
+            Err(_) => query_text = input,
+        }
+    }
+}
+
+fn print_processes(processes: &[ProcessInfo]) {
+    if processes.is_empty() {
+        println!("\n📭 No matching processes");
+        return;
+    }
+    println!(
+        "\n{:<4} {:<8} {:<10} {:>9} {:>12} {:<16} Command",
+        "#", "PID", "User", "RSS (MB)", "Elapsed", "Name"
+    );
+    for (i, process) in processes.iter().take(PICKER_ROWS).enumerate() {
+        let pid = if process.zygote_child { format!("{}*", process.pid) } else { process.pid.to_string() };
+        println!(
+            "{:<4} {:<8} {:<10} {:>9.1} {:>12} {:<16} {}",
+            i + 1,
+            pid,
+            truncate(&process.user, 10),
+            process.rss_kb as f64 / 1024.0,
+            format_elapsed(process.elapsed_secs()),
+            truncate(&process.android.as_ref().map_or(process.comm.clone(), |app| app.process_name()), 16),
+            truncate(&process.command(), 60)
+        );
+    }
+    if processes.iter().take(PICKER_ROWS).any(|p| p.zygote_child) {
+        println!("* = Android app started by zygote");
+    }
+    if processes.len() > PICKER_ROWS {
+        println!("... {} more, refine the search", processes.len() - PICKER_ROWS);
+    }
+}
+
+fn print_app(process: &ProcessInfo, app: &AndroidApp) {
+    println!(
+        "\n📱 {} (UID {}, user {}, app id {}{}{})",
+        app.process_name(),
+        process.uid,
+        app.user_id,
+        app.app_id,
+        if app.isolated { ", isolated" } else { "" },
+        if process.zygote_child { ", zygote child" } else { "" }
+    );
+}
+
+/// Espera a que arranque un proceso que coincida y, si se pide, a que cargue `module`.
+/// Si el proceso muere mientras se espera el módulo, se vuelve a esperar al proceso.
+/// Devuelve `None` si se cancela con Esc, q o Ctrl+C.
+pub fn wait_for_process(query_text: &str, module: Option<&str>) -> Result<Option<ProcessInfo>, Box<dyn Error>> {
+    let query = ProcessQuery::parse(query_text)?;
+    let mut waiter = ProcessWaiter::new(query);
+    let (mode, interval) = if waiter.uses_connector() {
+        ("netlink proc connector", CONNECTOR_INTERVAL)
+    } else {
+        ("polling /proc", POLL_INTERVAL)
+    };
+
+    let _screen = FullScreen::enter()?;
+    let mut out = stdout();
+    let started = Instant::now();
+    let mut found: Option<ProcessInfo> = None;
+    loop {
+        let status = match (&found, module) {
+            (None, _) => match waiter.check() {
+                Some(process) => {
+                    found = Some(process);
+                    continue;
+                }
+                None => format!("⏳ Waiting for '{}' to start...", query_text),
+            },
+            (Some(process), None) => return Ok(Some(process.clone())),
+            (Some(process), Some(module)) => match find_module(process.pid, module) {
+                Ok(Some(_)) => return Ok(Some(process.clone())),
+                Ok(None) => format!("⏳ PID {} ({}) started, waiting for module '{}'...", process.pid, process.comm, module),
+                Err(MemoryError::ProcessGone { .. }) => {
+                    found = None;
+                    continue;
+                }
+                // Sin permiso para leer sus mapas no tiene sentido seguir esperando
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        let (width, _) = terminal::size()?;
+        print_at(&mut out, 0, 1, width, &status, Color::Yellow)?;
+        print_at(&mut out, 0, 3, width, &format!("Elapsed: {:.0}s, using {}", started.elapsed().as_secs_f64(), mode), Color::White)?;
+        print_at(&mut out, 0, 5, width, "Esc/q: cancel", Color::DarkGrey)?;
+        out.flush()?;
+
+        if event::poll(interval)? {
+            if let Event::Key(key) = event::read()? {
+                let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press && (matches!(key.code, KeyCode::Esc | KeyCode::Char('q')) || ctrl_c) {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
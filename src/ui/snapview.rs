@@ -0,0 +1,324 @@
+use super::super::memory::history::ScanStep;
+use super::super::memory::inspect::{encode, InspectType};
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::snapshot::{diff_sources, filter_changes, DiffKind, RegionDiff, Snapshot};
+use super::super::memory::source::{open_saved, MemorySource, SavedMemory};
+use super::super::types::{SearchFilter, ValueType};
+use super::super::utils::input::get_input;
+use super::browser::result_browser;
+use super::display::{clear_screen, print_header};
+use crossterm::{ExecutableCommand, style::{Color, SetForegroundColor, ResetColor}};
+use std::error::Error;
+use std::fs::File;
+use std::io::{stdout, BufWriter, Write};
+
+const RANGES_SHOWN: usize = 8; // Rangos por región en pantalla; el informe completo va a fichero
+
+/// Uno de los lados de una comparación
+enum Side {
+    Stored(usize, Snapshot), // Sacada temporalmente de la lista; se devuelve al terminar
+    Live,
+    Saved(SavedMemory),
+}
+
+impl Side {
+    fn label(&self, process_mem: &ProcessMemory) -> String {
+        match self {
+            Side::Stored(index, snapshot) => format!("#{} {}", index + 1, snapshot.label),
+            Side::Live => process_mem.describe(),
+            Side::Saved(saved) => saved.origin.clone(),
+        }
+    }
+
+    /// La fuente de memoria propia; `None` para el proceso vivo, que vive en `process_mem`
+    fn source(&mut self) -> Option<&mut dyn MemorySource> {
+        match self {
+            Side::Stored(_, snapshot) => Some(snapshot),
+            Side::Live => None,
+            Side::Saved(saved) => Some(saved),
+        }
+    }
+}
+
+pub fn snapshot_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+
+        stdout()
+            .execute(SetForegroundColor(Color::Cyan))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║                              SNAPSHOTS & DIFF                                 ║");
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ [1] 📸 Take snapshot                                                           ║");
+        println!("║ [2] 📋 List snapshots                                                          ║");
+        println!("║ [3] 🔍 Diff report (changed ranges per region)                                 ║");
+        println!("║ [4] 🔢 Filter changed values into search results                               ║");
+        println!("║ [5] 🗑️  Delete snapshot                                                         ║");
+        println!("║ [0] ⬅️  Back to main menu                                                      ║");
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+
+        match get_input("\n> Enter your choice: ").as_str() {
+            "1" => take_snapshot(process_mem),
+            "2" => list_snapshots(process_mem),
+            "3" => {
+                compare(process_mem, false)?;
+            }
+            "4" => {
+                if compare(process_mem, true)? {
+                    return result_browser(process_mem);
+                }
+            }
+            "5" => delete_snapshot(process_mem),
+            "0" => return Ok(()),
+            _ => println!("❌ Invalid choice"),
+        }
+        get_input("\nPress Enter to continue...");
+    }
+}
+
+fn take_snapshot(process_mem: &mut ProcessMemory) {
+    let label = get_input("Label for the snapshot: ");
+    let writable_only = !get_input("Include read-only regions? (y/N): ").eq_ignore_ascii_case("y");
+    let label = if label.is_empty() { format!("snapshot {}", process_mem.snapshots.len() + 1) } else { label };
+
+    println!("📸 Capturing...");
+    match Snapshot::capture(process_mem, &label, writable_only) {
+        Ok(snapshot) => {
+            println!(
+                "✅ Snapshot #{} '{}': {} regions, {} KB",
+                process_mem.snapshots.len() + 1,
+                snapshot.label,
+                snapshot.regions().len(),
+                snapshot.size() / 1024
+            );
+            process_mem.snapshots.push(snapshot);
+        }
+        Err(e) => println!("❌ Error taking snapshot: {}", e),
+    }
+}
+
+fn list_snapshots(process_mem: &ProcessMemory) {
+    if process_mem.snapshots.is_empty() {
+        println!("📭 No snapshots taken");
+        return;
+    }
+    println!("\n{:<4} {:<30} {:<10} {:<12} Age", "#", "Label", "Regions", "Size (KB)");
+    for (i, snapshot) in process_mem.snapshots.iter().enumerate() {
+        println!(
+            "{:<4} {:<30} {:<10} {:<12} {:.0}s ago",
+            i + 1,
+            snapshot.label,
+            snapshot.regions().len(),
+            snapshot.size() / 1024,
+            snapshot.taken.elapsed().as_secs_f64()
+        );
+    }
+}
+
+fn delete_snapshot(process_mem: &mut ProcessMemory) {
+    list_snapshots(process_mem);
+    if let Some(index) = get_input("\nSnapshot # to delete: ")
+        .parse::<usize>()
+        .ok()
+        .filter(|n| *n >= 1 && *n <= process_mem.snapshots.len())
+    {
+        let snapshot = process_mem.snapshots.remove(index - 1);
+        println!("🗑️  Deleted '{}'", snapshot.label);
+    } else {
+        println!("❌ Invalid snapshot");
+    }
+}
+
+/// "#n" (instantánea en RAM), "live" o la ruta de un volcado/core
+fn parse_side(
+    input: &str,
+    process_mem: &ProcessMemory,
+    slots: &mut [Option<Snapshot>],
+) -> Result<Side, Box<dyn Error>> {
+    if input.eq_ignore_ascii_case("live") {
+        if !process_mem.is_live() {
+            return Err("No live process attached".into());
+        }
+        return Ok(Side::Live);
+    }
+    if let Some(n) = input.strip_prefix('#') {
+        let index = n.parse::<usize>().map_err(|_| "Invalid snapshot number")?;
+        let snapshot = index
+            .checked_sub(1)
+            .and_then(|i| slots.get_mut(i))
+            .and_then(Option::take)
+            .ok_or_else(|| format!("No snapshot #{} (or already picked)", index))?;
+        return Ok(Side::Stored(index - 1, snapshot));
+    }
+    Ok(Side::Saved(open_saved(input)?))
+}
+
+/// Pide los dos lados y muestra el informe de diferencias o filtra valores.
+/// Devuelve true si se cargaron resultados de búsqueda y conviene abrir el navegador.
+fn compare(process_mem: &mut ProcessMemory, filter_values: bool) -> Result<bool, Box<dyn Error>> {
+    list_snapshots(process_mem);
+    println!("\nSources: #n (snapshot), 'live' (attached process) or a dump/core path");
+
+    let mut slots: Vec<Option<Snapshot>> = std::mem::take(&mut process_mem.snapshots)
+        .into_iter()
+        .map(Some)
+        .collect();
+    let before = get_input("Before (A): ");
+    let after = get_input("After (B) [live]: ");
+    let after = if after.is_empty() { "live".to_string() } else { after };
+
+    let outcome = match (
+        parse_side(&before, process_mem, &mut slots),
+        parse_side(&after, process_mem, &mut slots),
+    ) {
+        (Ok(mut a), Ok(mut b)) => {
+            let outcome = if filter_values {
+                filter_sides(process_mem, &mut a, &mut b)
+            } else {
+                report_sides(process_mem, &mut a, &mut b)
+            };
+            restore(&mut slots, [Some(a), Some(b)]);
+            outcome
+        }
+        (a, b) => {
+            let error = a.as_ref().err().or(b.as_ref().err()).map(|e| e.to_string());
+            restore(&mut slots, [a.ok(), b.ok()]);
+            Err(error.unwrap_or_default().into())
+        }
+    };
+    process_mem.snapshots = slots.into_iter().flatten().collect();
+
+    match outcome {
+        Ok(loaded) => Ok(loaded),
+        Err(e) => {
+            println!("❌ {}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Devuelve a su sitio las instantáneas sacadas por `parse_side`
+fn restore(slots: &mut [Option<Snapshot>], sides: [Option<Side>; 2]) {
+    for side in sides.into_iter().flatten() {
+        if let Side::Stored(index, snapshot) = side {
+            slots[index] = Some(snapshot);
+        }
+    }
+}
+
+/// Ejecuta `f(a, b)` resolviendo "live" al proceso conectado
+fn with_sources<R>(
+    process_mem: &mut ProcessMemory,
+    a: &mut Side,
+    b: &mut Side,
+    f: impl FnOnce(&mut dyn MemorySource, &mut dyn MemorySource) -> Result<R, Box<dyn Error>>,
+) -> Result<R, Box<dyn Error>> {
+    match (a.source(), b.source()) {
+        (Some(a), Some(b)) => f(a, b),
+        (None, Some(b)) => f(process_mem, b),
+        (Some(a), None) => f(a, process_mem),
+        (None, None) => Err("Both sides are the live process".into()),
+    }
+}
+
+fn report_sides(process_mem: &mut ProcessMemory, a: &mut Side, b: &mut Side) -> Result<bool, Box<dyn Error>> {
+    let (label_a, label_b) = (a.label(process_mem), b.label(process_mem));
+    println!("🔍 Comparing {} → {}...", label_a, label_b);
+    let diffs = with_sources(process_mem, a, b, |a, b| diff_sources(a, b))?;
+    if diffs.is_empty() {
+        println!("✅ No differences");
+        return Ok(false);
+    }
+
+    let changed: u64 = diffs.iter().map(|d| d.changed_bytes()).sum();
+    println!("\n{} regions differ, {} bytes changed\n", diffs.len(), changed);
+    for diff in &diffs {
+        println!("{}", region_line(diff));
+        for (start, end) in diff.ranges.iter().take(RANGES_SHOWN) {
+            println!("    {:016x}-{:016x} ({} bytes)", start, end, end - start);
+        }
+        if diff.ranges.len() > RANGES_SHOWN {
+            println!("    ... {} more ranges", diff.ranges.len() - RANGES_SHOWN);
+        }
+    }
+
+    let path = get_input("\nSave full report to file (path, or Enter to skip): ");
+    if !path.is_empty() {
+        match save_report(&diffs, &label_a, &label_b, &path) {
+            Ok(_) => println!("✅ Report saved to {}", path),
+            Err(e) => println!("❌ Error saving report: {}", e),
+        }
+    }
+    Ok(false)
+}
+
+fn region_line(diff: &RegionDiff) -> String {
+    let region = &diff.region;
+    let status = match diff.kind {
+        DiffKind::Changed => format!("{} ranges, {} bytes changed", diff.ranges.len(), diff.changed_bytes()),
+        DiffKind::Resized(end) => format!(
+            "resized to end 0x{:x}, {} ranges, {} bytes changed",
+            end,
+            diff.ranges.len(),
+            diff.changed_bytes()
+        ),
+        DiffKind::Added => "added".to_string(),
+        DiffKind::Removed => "removed".to_string(),
+    };
+    format!(
+        "{:016x}-{:016x} {} {}: {}",
+        region.start, region.end, region.permissions, region.pathname, status
+    )
+}
+
+fn save_report(diffs: &[RegionDiff], label_a: &str, label_b: &str, path: &str) -> Result<(), Box<dyn Error>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "# diff {} -> {}", label_a, label_b)?;
+    for diff in diffs {
+        writeln!(out, "{}", region_line(diff))?;
+        for (start, end) in &diff.ranges {
+            writeln!(out, "    {:016x}-{:016x} ({} bytes)", start, end, end - start)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn filter_sides(process_mem: &mut ProcessMemory, a: &mut Side, b: &mut Side) -> Result<bool, Box<dyn Error>> {
+    let value_type = ValueType::from_name(&get_input("Value type (i32, i64, u32, u64, f32, f64) [i32]: "))
+        .or(Some(ValueType::I32))
+        .filter(|t| t.size().is_some())
+        .ok_or("Snapshot filters need a numeric value type")?;
+    println!("Filters: changed, unchanged, increased, decreased, increased_by N, decreased_by N, range MIN MAX, exact");
+    let filter = SearchFilter::from_name(&get_input("Filter: ")).ok_or("Invalid filter")?;
+    let exact = match filter {
+        SearchFilter::Exact => {
+            let (kind, endian) = InspectType::parse_spec(value_type.name()).ok_or("Invalid value type")?;
+            Some(encode(kind, endian, &get_input("Exact value (in B): "))?)
+        }
+        _ => None,
+    };
+
+    println!("🔍 Filtering {} → {}...", a.label(process_mem), b.label(process_mem));
+    let results = with_sources(process_mem, a, b, |a, b| {
+        filter_changes(a, b, value_type, &filter, exact.as_deref())
+    })?;
+    let count = results.len();
+    println!("✅ {} values match '{}'", count, filter.name());
+    if count == 0 {
+        return Ok(false);
+    }
+
+    process_mem.load_scan_step(ScanStep {
+        results,
+        value_type: Some(value_type),
+        filter_history: vec![(filter, exact)],
+    });
+    println!("📥 Loaded as search results (previous results kept in history)");
+    Ok(get_input("Open result browser? (y/N): ").eq_ignore_ascii_case("y"))
+}
@@ -0,0 +1,273 @@
+use super::super::memory::error::describe_error;
+use super::super::memory::inspect::{encode, Endian, InspectType};
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::structs::{dissect, guess_layout, FieldType, StructField, StructLayout};
+use super::super::utils::input::{get_input, parse_address};
+use super::display::{clear_screen, print_header, truncate};
+use super::term::{print_at, print_highlighted, read_line, scroll_into_view, FullScreen};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::{Color, ResetColor, SetForegroundColor},
+    terminal, ExecutableCommand,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{stdout, Write};
+use std::time::Duration;
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_GUESS_SIZE: usize = 4096;
+
+pub fn struct_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    loop {
+        clear_screen();
+        print_header();
+
+        stdout()
+            .execute(SetForegroundColor(Color::Cyan))
+            .unwrap();
+        println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+        println!("║                             STRUCTURE DISSECTOR                               ║");
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        for (i, layout) in process_mem.struct_layouts.iter().enumerate() {
+            println!("║ {:<4} │ {:<24} │ {} fields", i + 1, truncate(&layout.name, 24), layout.fields.len());
+        }
+        if process_mem.struct_layouts.is_empty() {
+            println!("║ (no layouts defined)");
+        }
+        println!("╠════════════════════════════════════════════════════════════════════════════════╣");
+        println!("║ [1] 📝 Define layout                                                           ║");
+        println!("║ [2] 🔮 Guess layout from memory                                                ║");
+        println!("║ [3] 🔬 Apply layout at address (live)                                          ║");
+        println!("║ [4] 📋 Show layout fields                                                      ║");
+        println!("║ [5] 🗑️  Delete layout                                                           ║");
+        println!("║ [0] ⬅️  Back to main menu                                                      ║");
+        println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+        stdout()
+            .execute(ResetColor)
+            .unwrap();
+        println!("Layouts are saved with the cheat table (Manage saved addresses).");
+
+        let choice = get_input("\n> Enter choice: ");
+
+        match choice.as_str() {
+            "1" => define_layout(process_mem),
+            "2" => guess_layout_menu(process_mem),
+            "3" => {
+                let Some(index) = select_layout(process_mem) else {
+                    continue;
+                };
+                let addr_str = get_input("Enter address (hex): ");
+                match parse_address(&addr_str) {
+                    Some(address) => struct_viewer(process_mem, index, address)?,
+                    None => {
+                        println!("❌ Invalid address");
+                        get_input("Press Enter to continue...");
+                    }
+                }
+            }
+            "4" => {
+                if let Some(index) = select_layout(process_mem) {
+                    let layout = &process_mem.struct_layouts[index];
+                    println!("\nstruct {}", layout.name);
+                    for field in &layout.fields {
+                        println!("  {}", field.to_line());
+                    }
+                    get_input("\nPress Enter to continue...");
+                }
+            }
+            "5" => {
+                if let Some(index) = select_layout(process_mem) {
+                    let layout = process_mem.struct_layouts.remove(index);
+                    println!("✅ Deleted layout {}", layout.name);
+                    get_input("Press Enter to continue...");
+                }
+            }
+            "0" => return Ok(()),
+            _ => {
+                println!("❌ Invalid choice");
+                get_input("Press Enter to continue...");
+            }
+        }
+    }
+}
+
+fn select_layout(process_mem: &ProcessMemory) -> Option<usize> {
+    if process_mem.struct_layouts.is_empty() {
+        println!("❌ No layouts defined");
+        get_input("Press Enter to continue...");
+        return None;
+    }
+    let input = get_input("Enter layout ID or name: ");
+    let index = match input.parse::<usize>() {
+        Ok(id) if id >= 1 && id <= process_mem.struct_layouts.len() => Some(id - 1),
+        _ => process_mem.struct_layouts.iter().position(|l| l.name == input),
+    };
+    if index.is_none() {
+        println!("❌ Unknown layout: {}", input);
+        get_input("Press Enter to continue...");
+    }
+    index
+}
+
+/// Añade el layout o reemplaza el que tenga el mismo nombre
+fn store_layout(process_mem: &mut ProcessMemory, layout: StructLayout) {
+    match process_mem.struct_layouts.iter_mut().find(|l| l.name == layout.name) {
+        Some(existing) => *existing = layout,
+        None => process_mem.struct_layouts.push(layout),
+    }
+}
+
+fn define_layout(process_mem: &mut ProcessMemory) {
+    let name = get_input("Layout name: ");
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        println!("❌ Layout names cannot be empty or contain spaces");
+        get_input("Press Enter to continue...");
+        return;
+    }
+
+    println!("\nEnter fields as '<offset> <name> <type>', one per line; empty line to finish.");
+    println!("Types: i8 u8 i16 u16 i32 u32 i64 u64 f32 f64 ptr utf8 utf16 (add 'be' for big endian),");
+    println!("       or ptr:<Layout> for a pointer to another layout. Example: 0x10 health i32");
+    let mut fields = Vec::new();
+    loop {
+        let line = get_input("field> ");
+        if line.is_empty() {
+            break;
+        }
+        match StructField::parse(&line) {
+            Some(field) => fields.push(field),
+            None => println!("❌ Invalid field: {}", line),
+        }
+    }
+
+    println!("✅ Layout {} with {} fields", name, fields.len());
+    store_layout(process_mem, StructLayout { name, fields });
+    get_input("Press Enter to continue...");
+}
+
+fn guess_layout_menu(process_mem: &mut ProcessMemory) {
+    let addr_str = get_input("Enter address (hex): ");
+    let size_str = get_input("Enter structure size (bytes): ");
+    let (Some(address), Ok(size)) = (parse_address(&addr_str), size_str.parse::<usize>()) else {
+        println!("❌ Invalid address or size");
+        get_input("Press Enter to continue...");
+        return;
+    };
+    let bytes = match process_mem.read_memory(address, size.min(MAX_GUESS_SIZE)) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            println!("❌ Error reading memory: {}", describe_error(&e));
+            get_input("Press Enter to continue...");
+            return;
+        }
+    };
+
+    let mut layout = guess_layout(process_mem, "guess", &bytes);
+    let layouts = vec![layout.clone()];
+    println!("\n{:<8} {:<12} {:<6} Value", "Offset", "Name", "Type");
+    for field in dissect(process_mem, &layouts, &layout, address) {
+        println!("0x{:<6x} {:<12} {:<6} {}", field.offset, field.name, field.type_name, field.value);
+    }
+
+    let name = get_input("\nSave as layout (name, or Enter to discard): ");
+    if name.is_empty() {
+        return;
+    }
+    layout.name = name.replace(char::is_whitespace, "_");
+    println!("✅ Saved layout {}", layout.name);
+    store_layout(process_mem, layout);
+    get_input("Press Enter to continue...");
+}
+
+/// Vista a pantalla completa con los campos de la estructura refrescados en vivo
+fn struct_viewer(process_mem: &mut ProcessMemory, index: usize, address: u64) -> Result<(), Box<dyn Error>> {
+    let _screen = FullScreen::enter()?;
+    let mut out = stdout();
+    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+
+    let mut selected = 0usize;
+    let mut top = 0usize;
+    let mut previous: HashMap<u64, String> = HashMap::new();
+    let mut status = String::new();
+
+    loop {
+        let layouts = process_mem.struct_layouts.clone();
+        let layout = &layouts[index];
+        let fields = dissect(process_mem, &layouts, layout, address);
+        selected = selected.min(fields.len().saturating_sub(1));
+
+        let (width, height) = terminal::size()?;
+        let rows = height.saturating_sub(3) as usize;
+        scroll_into_view(&mut top, selected, rows);
+
+        let title = format!(" STRUCT {} @ 0x{:016x} │ PID {}", layout.name, address, process_mem.pid);
+        print_at(&mut out, 0, 0, width, &title, Color::Cyan)?;
+        let header = format!("{:<18} {:<8} {:<20} {:<12} Value", "Address", "Offset", "Name", "Type");
+        print_at(&mut out, 0, 1, width, &header, Color::DarkCyan)?;
+        for row in 0..rows {
+            let y = row as u16 + 2;
+            let Some(field) = fields.get(top + row) else {
+                print_at(&mut out, 0, y, width, "", Color::White)?;
+                continue;
+            };
+            let name = format!("{}{}", "  ".repeat(field.depth), field.name);
+            let text = format!(
+                "{:016x}   +0x{:<5x} {:<20} {:<12} {}",
+                field.address,
+                field.offset,
+                truncate(&name, 20),
+                field.type_name,
+                field.value
+            );
+            let changed = previous.get(&field.address).is_some_and(|v| v != &field.value);
+            if top + row == selected {
+                print_highlighted(&mut out, 0, y, width, &text)?;
+            } else {
+                print_at(&mut out, 0, y, width, &text, if changed { Color::Red } else { Color::White })?;
+            }
+        }
+        previous = fields.iter().map(|f| (f.address, f.value.clone())).collect();
+
+        let help = "↑↓: select  e: edit value  Esc/q: close";
+        print_at(&mut out, 0, height - 1, width, if status.is_empty() { help } else { &status }, Color::DarkGrey)?;
+        out.flush()?;
+
+        if !event::poll(REFRESH_INTERVAL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+            KeyCode::Up => selected = selected.saturating_sub(1),
+            KeyCode::Down => selected += 1,
+            KeyCode::PageUp => selected = selected.saturating_sub(rows),
+            KeyCode::PageDown => selected += rows,
+            KeyCode::Char('e') => {
+                let Some(field) = fields.get(selected) else {
+                    continue;
+                };
+                let (kind, endian) = match FieldType::parse(&field.type_name) {
+                    Some(FieldType::Value(kind, endian)) => (kind, endian),
+                    _ => (InspectType::Pointer, Endian::Little),
+                };
+                let Some(input) = read_line(&format!("{} = ", field.name), height - 1)? else {
+                    continue;
+                };
+                status = match encode(kind, endian, &input) {
+                    Ok(bytes) => match process_mem.write_memory(field.address, &bytes) {
+                        Ok(_) => format!("Wrote {} = {}", field.name, input),
+                        Err(e) => format!("Write failed at 0x{:x}: {}", field.address, e),
+                    },
+                    Err(e) => e,
+                };
+            }
+            _ => {}
+        }
+    }
+}
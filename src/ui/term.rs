@@ -0,0 +1,109 @@
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io::{self, stdout, Stdout, Write};
+
+/// Pantalla completa en modo raw; se restaura al salir del ámbito (también con panic).
+/// Si ya hay una vista a pantalla completa activa no hace nada, así se pueden anidar.
+pub struct FullScreen {
+    owned: bool,
+}
+
+impl FullScreen {
+    pub fn enter() -> io::Result<FullScreen> {
+        if terminal::is_raw_mode_enabled()? {
+            return Ok(FullScreen { owned: false });
+        }
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen, Hide)?;
+        Ok(FullScreen { owned: true })
+    }
+}
+
+impl Drop for FullScreen {
+    fn drop(&mut self) {
+        if self.owned {
+            let _ = execute!(stdout(), LeaveAlternateScreen, Show, ResetColor);
+            let _ = terminal::disable_raw_mode();
+        }
+    }
+}
+
+/// Escribe `text` en (x, y) recortado o rellenado a exactamente `width` columnas
+pub fn print_at(out: &mut Stdout, x: u16, y: u16, width: u16, text: &str, color: Color) -> io::Result<()> {
+    let mut line: String = text.chars().take(width as usize).collect();
+    let len = line.chars().count();
+    line.extend(std::iter::repeat_n(' ', width as usize - len));
+    queue!(out, MoveTo(x, y), SetForegroundColor(color), Print(line), ResetColor)
+}
+
+/// Igual que `print_at` pero en vídeo inverso, para la fila seleccionada
+pub fn print_highlighted(out: &mut Stdout, x: u16, y: u16, width: u16, text: &str) -> io::Result<()> {
+    queue!(out, SetAttribute(Attribute::Reverse))?;
+    print_at(out, x, y, width, text, Color::White)?;
+    queue!(out, SetAttribute(Attribute::Reset))
+}
+
+/// Marco de un panel con título; el panel enfocado se dibuja resaltado
+pub fn draw_box(out: &mut Stdout, x: u16, y: u16, w: u16, h: u16, title: &str, focused: bool) -> io::Result<()> {
+    if w < 4 || h < 2 {
+        return Ok(());
+    }
+    let color = if focused { Color::Yellow } else { Color::DarkCyan };
+    let inner = (w - 2) as usize;
+    let title: String = format!(" {} ", title).chars().take(inner).collect();
+    let top = format!("┌{}{}┐", title, "─".repeat(inner - title.chars().count()));
+    let bottom = format!("└{}┘", "─".repeat(inner));
+    queue!(out, SetForegroundColor(color), MoveTo(x, y), Print(top))?;
+    for row in 1..h - 1 {
+        queue!(out, MoveTo(x, y + row), Print("│"), MoveTo(x + w - 1, y + row), Print("│"))?;
+    }
+    queue!(out, MoveTo(x, y + h - 1), Print(bottom), ResetColor)
+}
+
+/// Lee una línea de texto en la fila `y` con edición mínima; `None` si se cancela con Esc
+pub fn read_line(prompt: &str, y: u16) -> io::Result<Option<String>> {
+    let mut out = stdout();
+    let (width, _) = terminal::size()?;
+    let mut input = String::new();
+    execute!(out, Show)?;
+    let result = loop {
+        print_at(&mut out, 0, y, width, &format!("{}{}", prompt, input), Color::White)?;
+        let cursor = (prompt.chars().count() + input.chars().count()).min(width as usize - 1);
+        queue!(out, MoveTo(cursor as u16, y))?;
+        out.flush()?;
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Enter => break Some(input.trim().to_string()),
+                KeyCode::Esc => break None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+        }
+    };
+    execute!(out, Hide)?;
+    Ok(result)
+}
+
+/// Mantiene `selected` visible dentro de una ventana de `height` filas que empieza en `top`
+pub fn scroll_into_view(top: &mut usize, selected: usize, height: usize) {
+    if height == 0 {
+        return;
+    }
+    if selected < *top {
+        *top = selected;
+    } else if selected >= *top + height {
+        *top = selected + 1 - height;
+    }
+}
@@ -0,0 +1,432 @@
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::region::find_region;
+use super::super::types::ValueType;
+use super::super::utils::input::parse_address;
+use super::hexview::hex_editor;
+use super::term::{draw_box, print_at, print_highlighted, read_line, scroll_into_view, FullScreen};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::Color,
+    terminal,
+};
+use std::error::Error;
+use std::io::{stdout, Stdout, Write};
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+const STATUS_TIMEOUT: Duration = Duration::from_secs(4);
+const MIN_WIDTH: u16 = 80;
+const MIN_HEIGHT: u16 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Pane {
+    Regions,
+    Results,
+    Saved,
+    Hex,
+}
+
+const PANES: [Pane; 4] = [Pane::Regions, Pane::Results, Pane::Saved, Pane::Hex];
+
+/// Cómo se salió de la vista a pantalla completa
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TuiExit {
+    Menu,
+    Exited, // El proceso terminó y aún no se ofreció reconectar
+    ChangeProcess,
+    Quit,
+}
+
+/// Lista con selección y desplazamiento
+#[derive(Debug, Default)]
+struct ListState {
+    selected: usize,
+    top: usize,
+}
+
+impl ListState {
+    fn move_by(&mut self, delta: isize, len: usize) {
+        if len == 0 {
+            self.selected = 0;
+            return;
+        }
+        let target = self.selected as isize + delta;
+        self.selected = target.clamp(0, len as isize - 1) as usize;
+    }
+}
+
+struct App<'a> {
+    process_mem: &'a mut ProcessMemory,
+    comm: String,
+    focus: Pane,
+    regions: ListState,
+    results: ListState,
+    saved: ListState,
+    hex_addr: u64,
+    hex_rows: usize,
+    hex_bytes_per_row: usize,
+    status: Option<(String, Instant)>,
+    exit: Option<TuiExit>,
+}
+
+/// Interfaz a pantalla completa: regiones, resultados, direcciones guardadas y vista hex
+/// que se refrescan en vivo
+pub fn run_tui(process_mem: &mut ProcessMemory) -> Result<TuiExit, Box<dyn Error>> {
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", process_mem.pid))
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+    let hex_addr = process_mem
+        .regions
+        .iter()
+        .find(|r| r.permissions.starts_with('r'))
+        .map(|r| r.start)
+        .unwrap_or(0);
+
+    let mut app = App {
+        process_mem,
+        comm,
+        focus: Pane::Regions,
+        regions: ListState::default(),
+        results: ListState::default(),
+        saved: ListState::default(),
+        hex_addr,
+        hex_rows: 1,
+        hex_bytes_per_row: 16,
+        status: None,
+        exit: None,
+    };
+
+    let _screen = FullScreen::enter()?;
+    let mut out = stdout();
+    let mut last_draw = Instant::now() - REFRESH_INTERVAL;
+
+    loop {
+        if let Some(exit) = app.exit {
+            return Ok(exit);
+        }
+        if app.process_mem.has_exited() && !app.process_mem.reattach_offered {
+            return Ok(TuiExit::Exited);
+        }
+        if last_draw.elapsed() >= REFRESH_INTERVAL {
+            app.draw(&mut out)?;
+            last_draw = Instant::now();
+        }
+        if event::poll(REFRESH_INTERVAL.saturating_sub(last_draw.elapsed()))? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    app.handle_key(key.code)?;
+                    app.draw(&mut out)?;
+                    last_draw = Instant::now();
+                }
+                Event::Resize(_, _) => {
+                    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+                    app.draw(&mut out)?;
+                    last_draw = Instant::now();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl App<'_> {
+    fn set_status(&mut self, message: String) {
+        self.status = Some((message, Instant::now()));
+    }
+
+    fn list_len(&self, pane: Pane) -> usize {
+        match pane {
+            Pane::Regions => self.process_mem.regions.len(),
+            Pane::Results => self.process_mem.search_results.len(),
+            Pane::Saved => self.process_mem.get_saved_addresses().len(),
+            Pane::Hex => 0,
+        }
+    }
+
+    fn list_state(&mut self, pane: Pane) -> Option<&mut ListState> {
+        match pane {
+            Pane::Regions => Some(&mut self.regions),
+            Pane::Results => Some(&mut self.results),
+            Pane::Saved => Some(&mut self.saved),
+            Pane::Hex => None,
+        }
+    }
+
+    fn selected_address(&self) -> Option<u64> {
+        match self.focus {
+            Pane::Regions => self.process_mem.regions.get(self.regions.selected).map(|r| r.start),
+            Pane::Results => self
+                .process_mem
+                .search_results
+                .get(self.results.selected)
+                .ok()
+                .map(|(addr, _)| addr),
+            Pane::Saved => self
+                .process_mem
+                .get_saved_addresses()
+                .get(self.saved.selected)
+                .map(|(addr, _)| *addr),
+            Pane::Hex => None,
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> Result<(), Box<dyn Error>> {
+        let (_, height) = terminal::size()?;
+        let page = (height / 3).max(1) as isize;
+
+        let delta = match code {
+            KeyCode::Up => Some(-1),
+            KeyCode::Down => Some(1),
+            KeyCode::PageUp => Some(-page),
+            KeyCode::PageDown => Some(page),
+            KeyCode::Home => Some(isize::MIN / 2),
+            KeyCode::End => Some(isize::MAX / 2),
+            _ => None,
+        };
+        if let Some(delta) = delta {
+            if self.focus == Pane::Hex {
+                let step = self.hex_bytes_per_row as i64;
+                let lines = delta.clamp(-(self.hex_rows as isize), self.hex_rows as isize) as i64;
+                self.hex_addr = self.hex_addr.saturating_add_signed(lines * step);
+            } else {
+                let len = self.list_len(self.focus);
+                if let Some(state) = self.list_state(self.focus) {
+                    state.move_by(delta, len);
+                }
+            }
+            return Ok(());
+        }
+
+        match code {
+            KeyCode::Tab => {
+                let i = PANES.iter().position(|p| *p == self.focus).unwrap_or(0);
+                self.focus = PANES[(i + 1) % PANES.len()];
+            }
+            KeyCode::BackTab => {
+                let i = PANES.iter().position(|p| *p == self.focus).unwrap_or(0);
+                self.focus = PANES[(i + PANES.len() - 1) % PANES.len()];
+            }
+            KeyCode::Enter if self.focus == Pane::Hex => {
+                hex_editor(self.process_mem, self.hex_addr)?;
+            }
+            KeyCode::Enter => {
+                if let Some(addr) = self.selected_address() {
+                    self.hex_addr = addr & !0xf;
+                    self.focus = Pane::Hex;
+                }
+            }
+            KeyCode::Char('g') => {
+                let (_, height) = terminal::size()?;
+                if let Some(input) = read_line("Go to address: 0x", height - 1)? {
+                    match parse_address(&input) {
+                        Some(addr) => {
+                            self.hex_addr = addr & !0xf;
+                            self.focus = Pane::Hex;
+                        }
+                        None => self.set_status(format!("Invalid address: {}", input)),
+                    }
+                }
+            }
+            KeyCode::Char('s') if self.focus == Pane::Results => {
+                if let Some(addr) = self.selected_address() {
+                    let (_, height) = terminal::size()?;
+                    if let Some(desc) = read_line("Description: ", height - 1)? {
+                        let desc = if desc.is_empty() { "Search result".to_string() } else { desc };
+                        let value = self.process_mem.value_type.map(|t| (t, self.process_mem.search_results.value_size()));
+                        self.process_mem.save_address(addr, desc, value);
+                        self.set_status(format!("Saved 0x{:x}", addr));
+                    }
+                }
+            }
+            KeyCode::Char('r') => match self.process_mem.refresh_regions() {
+                Ok(_) => self.set_status(format!("{} regions", self.process_mem.regions.len())),
+                Err(e) => self.set_status(format!("Error reading maps: {}", e)),
+            },
+            KeyCode::Char('m') | KeyCode::Esc => self.exit = Some(TuiExit::Menu),
+            KeyCode::Char('c') => self.exit = Some(TuiExit::ChangeProcess),
+            KeyCode::Char('q') => self.exit = Some(TuiExit::Quit),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, out: &mut Stdout) -> Result<(), Box<dyn Error>> {
+        let (width, height) = terminal::size()?;
+        if width < MIN_WIDTH || height < MIN_HEIGHT {
+            crossterm::queue!(out, terminal::Clear(terminal::ClearType::All))?;
+            print_at(
+                out,
+                0,
+                0,
+                width,
+                &format!("Terminal too small ({}x{}), need {}x{}", width, height, MIN_WIDTH, MIN_HEIGHT),
+                Color::Red,
+            )?;
+            out.flush()?;
+            return Ok(());
+        }
+
+        // Disposición: info (3 filas) | regiones, resultados, guardadas | hex | barra de estado
+        let info_h = 3;
+        let body_h = height - info_h - 1;
+        let lists_h = body_h / 2;
+        let hex_h = body_h - lists_h;
+        let col_w = width / 3;
+
+        self.draw_info(out, 0, 0, width, info_h)?;
+        self.draw_regions(out, 0, info_h, col_w, lists_h)?;
+        self.draw_results(out, col_w, info_h, col_w, lists_h)?;
+        self.draw_saved(out, col_w * 2, info_h, width - col_w * 2, lists_h)?;
+        self.draw_hex(out, 0, info_h + lists_h, width, hex_h)?;
+        self.draw_status(out, height - 1, width)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn draw_info(&self, out: &mut Stdout, x: u16, y: u16, w: u16, h: u16) -> Result<(), Box<dyn Error>> {
+        draw_box(out, x, y, w, h, "Process", false)?;
+        let pm = &self.process_mem;
+        let value_type = pm.value_type.map(|t| t.name()).unwrap_or("-");
+        let line = format!(
+            "PID {} │ {} │ {} regions │ {} results ({}) │ {} filters │ {} saved",
+            pm.pid,
+            self.comm,
+            pm.regions.len(),
+            pm.search_results.len(),
+            value_type,
+            pm.filter_history.len(),
+            pm.get_saved_addresses().len()
+        );
+        print_at(out, x + 1, y + 1, w - 2, &line, Color::White)?;
+        Ok(())
+    }
+
+    fn draw_regions(&mut self, out: &mut Stdout, x: u16, y: u16, w: u16, h: u16) -> Result<(), Box<dyn Error>> {
+        draw_box(out, x, y, w, h, "Regions", self.focus == Pane::Regions)?;
+        let rows = (h - 2) as usize;
+        scroll_into_view(&mut self.regions.top, self.regions.selected, rows);
+        for row in 0..rows {
+            let index = self.regions.top + row;
+            let text = match self.process_mem.regions.get(index) {
+                Some(r) => {
+                    let name = r.pathname.rsplit('/').next().unwrap_or("");
+                    format!("{:012x} {} {}", r.start, r.permissions, name)
+                }
+                None => String::new(),
+            };
+            let selected = index == self.regions.selected;
+            draw_row(out, x + 1, y + 1 + row as u16, w - 2, &text, selected, self.focus == Pane::Regions)?;
+        }
+        Ok(())
+    }
+
+    fn draw_results(&mut self, out: &mut Stdout, x: u16, y: u16, w: u16, h: u16) -> Result<(), Box<dyn Error>> {
+        let title = format!("Results ({})", self.process_mem.search_results.len());
+        draw_box(out, x, y, w, h, &title, self.focus == Pane::Results)?;
+        let rows = (h - 2) as usize;
+        let value_type = self.process_mem.value_type.unwrap_or(ValueType::Bytes);
+        scroll_into_view(&mut self.results.top, self.results.selected, rows);
+        for row in 0..rows {
+            let index = self.results.top + row;
+            let text = match self.process_mem.search_results.get(index) {
+                Ok((addr, previous)) => {
+                    let current = self.process_mem.read_memory(addr, previous.len());
+                    let (value, changed) = match current {
+                        Ok(bytes) => (value_type.format(&bytes), bytes != previous),
+                        Err(_) => ("??".to_string(), false),
+                    };
+                    format!("{:012x} {}{}", addr, if changed { "*" } else { " " }, value)
+                }
+                Err(_) => String::new(),
+            };
+            let selected = index == self.results.selected;
+            draw_row(out, x + 1, y + 1 + row as u16, w - 2, &text, selected, self.focus == Pane::Results)?;
+        }
+        Ok(())
+    }
+
+    fn draw_saved(&mut self, out: &mut Stdout, x: u16, y: u16, w: u16, h: u16) -> Result<(), Box<dyn Error>> {
+        draw_box(out, x, y, w, h, "Saved addresses", self.focus == Pane::Saved)?;
+        let rows = (h - 2) as usize;
+        scroll_into_view(&mut self.saved.top, self.saved.selected, rows);
+        let saved: Vec<(u64, String)> = self.process_mem.get_saved_addresses().to_vec();
+        for row in 0..rows {
+            let index = self.saved.top + row;
+            let text = match saved.get(index) {
+                Some((addr, desc)) => {
+                    // Sin tipo guardado se muestran los bytes en hexadecimal
+                    let value = match self.process_mem.saved_type(index) {
+                        Some((value_type, len)) => match self.process_mem.read_memory(*addr, len) {
+                            Ok(bytes) => value_type.format(&bytes),
+                            Err(_) => "??".to_string(),
+                        },
+                        None => match self.process_mem.read_memory(*addr, 8) {
+                            Ok(bytes) => ValueType::Bytes.format(&bytes),
+                            Err(_) => "??".to_string(),
+                        },
+                    };
+                    format!("{:<12} {:>11} {:x}", desc, value, addr)
+                }
+                None => String::new(),
+            };
+            let selected = index == self.saved.selected;
+            draw_row(out, x + 1, y + 1 + row as u16, w - 2, &text, selected, self.focus == Pane::Saved)?;
+        }
+        Ok(())
+    }
+
+    fn draw_hex(&mut self, out: &mut Stdout, x: u16, y: u16, w: u16, h: u16) -> Result<(), Box<dyn Error>> {
+        let region = find_region(&self.process_mem.regions, self.hex_addr)
+            .map(|r| format!("{} {}", r.permissions, r.pathname))
+            .unwrap_or_else(|| "unmapped".to_string());
+        draw_box(out, x, y, w, h, &format!("Hex 0x{:x} [{}]", self.hex_addr, region), self.focus == Pane::Hex)?;
+        self.hex_rows = (h - 2) as usize;
+        // dirección (16) + ": " + 3 columnas y 1 carácter por byte
+        self.hex_bytes_per_row = if w as usize >= 2 + 18 + 16 * 4 + 2 { 16 } else { 8 };
+        let bpr = self.hex_bytes_per_row;
+
+        for row in 0..self.hex_rows {
+            let addr = self.hex_addr.wrapping_add((row * bpr) as u64);
+            let text = match self.process_mem.read_memory(addr, bpr) {
+                Ok(data) => {
+                    let hex: String = data.iter().map(|b| format!("{:02x} ", b)).collect();
+                    let ascii: String = data
+                        .iter()
+                        .map(|&b| if (32..=126).contains(&b) { b as char } else { '.' })
+                        .collect();
+                    format!("{:016x}: {} {}", addr, hex, ascii)
+                }
+                Err(_) => format!("{:016x}: {}", addr, "?? ".repeat(bpr)),
+            };
+            print_at(out, x + 1, y + 1 + row as u16, w - 2, &text, Color::White)?;
+        }
+        Ok(())
+    }
+
+    fn draw_status(&mut self, out: &mut Stdout, y: u16, width: u16) -> Result<(), Box<dyn Error>> {
+        if let Some((_, since)) = &self.status {
+            if since.elapsed() > STATUS_TIMEOUT {
+                self.status = None;
+            }
+        }
+        let (text, color) = match &self.status {
+            Some((message, _)) => (message.clone(), Color::Yellow),
+            None => (
+                "Tab:pane ↑↓:move Enter:hex/edit g:goto s:save r:reload m:menu c:process q:quit"
+                    .to_string(),
+                Color::DarkGrey,
+            ),
+        };
+        print_at(out, 0, y, width, &text, color)?;
+        Ok(())
+    }
+}
+
+fn draw_row(out: &mut Stdout, x: u16, y: u16, w: u16, text: &str, selected: bool, focused: bool) -> Result<(), Box<dyn Error>> {
+    match (selected, focused) {
+        (true, true) => print_highlighted(out, x, y, w, text)?,
+        (true, false) => print_at(out, x, y, w, text, Color::Yellow)?,
+        _ => print_at(out, x, y, w, text, Color::White)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,225 @@
+use super::super::memory::debugreg::{hit_slot, HitLog, WatchKind, Watchpoint};
+use super::super::memory::error::describe_error;
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::ptrace::TraceEvent;
+use super::super::memory::region::code_location;
+use super::super::memory::registers::Registers;
+use super::super::utils::input::{get_input, parse_address, parse_position};
+use super::display::{clear_screen, print_header};
+use super::term::{print_at, FullScreen};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind},
+    style::{Color, ResetColor, SetForegroundColor},
+    terminal, ExecutableCommand,
+};
+use std::error::Error;
+use std::io::{stdout, Write};
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// "¿Qué escribe en esta dirección?": watchpoint hardware en todos los hilos y registro de
+/// las instrucciones que lo disparan
+pub fn watchpoint_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    clear_screen();
+    print_header();
+
+    stdout()
+        .execute(SetForegroundColor(Color::Magenta))
+        .unwrap();
+    println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+    println!("║                      FIND WHAT WRITES/ACCESSES AN ADDRESS                     ║");
+    println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+    stdout()
+        .execute(ResetColor)
+        .unwrap();
+
+    let saved = process_mem.get_saved_addresses().to_vec();
+    for (i, (address, description)) in saved.iter().enumerate() {
+        println!("#{:<3} {:016x}  {}", i + 1, address, description);
+    }
+    let input = get_input("\nAddress (hex) or saved address #N: ");
+    let address = match input.strip_prefix('#') {
+        Some(position) => parse_position(position, saved.len()).map(|i| saved[i].0),
+        None => parse_address(&input),
+    };
+    let Some(address) = address else {
+        println!("❌ Invalid address");
+        get_input("Press Enter to continue...");
+        return Ok(());
+    };
+    let kind = match get_input("[1] Writes  [2] Reads and writes [1]: ").as_str() {
+        "2" => WatchKind::Access,
+        _ => WatchKind::Write,
+    };
+    let len_str = get_input("Length in bytes (1, 2, 4, 8) [4]: ");
+    let len = if len_str.is_empty() { Ok(4) } else { len_str.parse::<usize>() };
+    let watchpoint = match len.map_err(|_| "Invalid length".to_string()).and_then(|len| Watchpoint::new(address, len, kind)) {
+        Ok(watchpoint) => watchpoint,
+        Err(e) => {
+            println!("❌ {}", e);
+            get_input("Press Enter to continue...");
+            return Ok(());
+        }
+    };
+
+    let paused_here = !process_mem.is_paused();
+    process_mem.pause()?;
+    let mut log = HitLog::default();
+    let result = watch_hits(process_mem, watchpoint, &mut log);
+    if paused_here {
+        process_mem.resume();
+    }
+    if let Err(e) = result {
+        println!("❌ Cannot set watchpoint: {}", describe_error(e.as_ref()));
+        get_input("Press Enter to continue...");
+        return Ok(());
+    }
+
+    print_hits(process_mem, &watchpoint, &log);
+    if !log.hits.is_empty() {
+        let input = get_input("\nSave a hit location as a saved address (#, Enter to skip): ");
+        if let Some(index) = parse_position(&input, log.hits.len()) {
+            let hit = &log.hits[index];
+            let location = code_location(&process_mem.regions, hit.ip);
+            process_mem.save_address(hit.ip, format!("{} 0x{:x} ({})", kind.name(), address, location), None);
+            println!("✅ Saved 0x{:x} ({})", hit.ip, location);
+        }
+    }
+    get_input("\nPress Enter to continue...");
+    Ok(())
+}
+
+/// Pone el watchpoint, deja correr el proceso y cuenta los disparos hasta que se pulse Esc.
+/// Al salir los hilos quedan detenidos y sin watchpoints.
+fn watch_hits(process_mem: &mut ProcessMemory, watchpoint: Watchpoint, log: &mut HitLog) -> Result<(), Box<dyn Error>> {
+    process_mem.refresh_regions()?;
+    let regions = process_mem.regions.clone();
+    let pid = process_mem.pid;
+    let tracer = process_mem.tracer_mut().ok_or("Process is not paused")?;
+    tracer.set_watchpoints(vec![watchpoint])?;
+    tracer.run();
+
+    let started = Instant::now();
+    let mut status = String::new();
+    let screen = FullScreen::enter()?;
+    let mut out = stdout();
+    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+
+    loop {
+        while let Some(event) = tracer.poll() {
+            match event {
+                TraceEvent::Trap(tid) => {
+                    if hit_slot(tid, tracer.watchpoints()).is_some() {
+                        if let Ok(registers) = Registers::read(tid) {
+                            log.record(tid, registers.pc());
+                        }
+                        tracer.cont_after_watch(tid);
+                    } else {
+                        tracer.forward_trap(tid);
+                        tracer.cont(tid);
+                    }
+                }
+                TraceEvent::ThreadExited(tid) => status = format!("Thread {} exited", tid),
+            }
+        }
+        if tracer.threads().is_empty() {
+            status = "Process exited".to_string();
+        }
+
+        let (width, height) = terminal::size()?;
+        let title = format!(
+            " WATCHPOINT {} 0x{:x} +{} │ PID {} │ {} threads │ {:.0}s │ {} hits",
+            watchpoint.kind.name(),
+            watchpoint.address,
+            watchpoint.len,
+            pid,
+            tracer.threads().len(),
+            started.elapsed().as_secs_f64(),
+            log.total
+        );
+        print_at(&mut out, 0, 0, width, &title, Color::Cyan)?;
+        print_at(&mut out, 0, 1, width, &format!("{:>8}  {:<8} {:<18} Location", "Count", "TID", "IP"), Color::DarkCyan)?;
+        let rows = height.saturating_sub(3) as usize;
+        for row in 0..rows {
+            let text = log
+                .hits
+                .get(row)
+                .map(|hit| {
+                    format!(
+                        "{:>8}  {:<8} 0x{:<16x} {}",
+                        hit.count,
+                        hit.tid,
+                        hit.ip,
+                        code_location(&regions, hit.ip)
+                    )
+                })
+                .unwrap_or_default();
+            print_at(&mut out, 0, row as u16 + 2, width, &text, Color::White)?;
+        }
+        let help = "c: clear  Esc/q: stop";
+        print_at(&mut out, 0, height - 1, width, if status.is_empty() { help } else { &status }, Color::DarkGrey)?;
+        out.flush()?;
+
+        if tracer.threads().is_empty() || (event::poll(POLL_INTERVAL)? && stop_requested(log, &mut status)?) {
+            break;
+        }
+    }
+    drop(screen);
+
+    // Los accesos que llegaron mientras se detenían los hilos también cuentan
+    for tid in tracer.interrupt_all() {
+        if hit_slot(tid, tracer.watchpoints()).is_some() {
+            if let Ok(registers) = Registers::read(tid) {
+                log.record(tid, registers.pc());
+            }
+        } else {
+            tracer.forward_trap(tid);
+        }
+    }
+    tracer.set_watchpoints(Vec::new())?;
+    Ok(())
+}
+
+fn stop_requested(log: &mut HitLog, status: &mut String) -> Result<bool, Box<dyn Error>> {
+    match event::read()? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(true),
+            KeyCode::Char('c') => {
+                log.clear();
+                *status = "Hits cleared".to_string();
+            }
+            _ => {}
+        },
+        Event::Resize(_, _) => crossterm::execute!(stdout(), terminal::Clear(terminal::ClearType::All))?,
+        _ => {}
+    }
+    Ok(false)
+}
+
+fn print_hits(process_mem: &ProcessMemory, watchpoint: &Watchpoint, log: &HitLog) {
+    println!(
+        "\n{} hits on 0x{:x} ({}) from {} instructions",
+        log.total,
+        watchpoint.address,
+        watchpoint.kind.name(),
+        log.hits.len()
+    );
+    if log.hits.is_empty() {
+        return;
+    }
+    println!("\n{:<4} {:>8}  {:<8} {:<18} Location", "#", "Count", "TID", "IP");
+    for (i, hit) in log.hits.iter().enumerate() {
+        println!(
+            "{:<4} {:>8}  {:<8} 0x{:<16x} {}",
+            i + 1,
+            hit.count,
+            hit.tid,
+            hit.ip,
+            code_location(&process_mem.regions, hit.ip)
+        );
+    }
+    if cfg!(target_arch = "x86_64") {
+        println!("\nOn x86_64 the IP is the instruction right after the one that accessed the address.");
+    }
+}
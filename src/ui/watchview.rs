@@ -0,0 +1,223 @@
+use super::super::memory::error::describe_error;
+use super::super::memory::process::ProcessMemory;
+use super::super::memory::watch::{MemoryWatch, MAX_WATCH_LEN};
+use super::super::utils::hex;
+use super::super::utils::input::{get_input, parse_address};
+use super::display::{clear_screen, print_header};
+use super::term::{print_at, FullScreen};
+use crossterm::{
+    cursor::MoveTo,
+    event::{self, Event, KeyCode, KeyEventKind},
+    queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal, ExecutableCommand,
+};
+use std::error::Error;
+use std::fs::File;
+use std::io::{stdout, BufWriter, Stdout, Write};
+use std::time::{Duration, Instant};
+
+const BYTES_PER_ROW: usize = 16;
+const HIGHLIGHT_TIME: Duration = Duration::from_secs(2);
+const MIN_INTERVAL: Duration = Duration::from_millis(10);
+
+pub fn watch_menu(process_mem: &mut ProcessMemory) -> Result<(), Box<dyn Error>> {
+    clear_screen();
+    print_header();
+
+    stdout()
+        .execute(SetForegroundColor(Color::Magenta))
+        .unwrap();
+    println!("\n╔════════════════════════════════════════════════════════════════════════════════╗");
+    println!("║                                 WATCH MEMORY                                  ║");
+    println!("╚════════════════════════════════════════════════════════════════════════════════╝");
+    stdout()
+        .execute(ResetColor)
+        .unwrap();
+
+    let addr_str = get_input("\nEnter start address (hex): ");
+    let len_str = get_input(&format!("Enter length (bytes, max {}): ", MAX_WATCH_LEN));
+    let interval_str = get_input("Sample interval in ms [200]: ");
+    let interval = if interval_str.is_empty() { Ok(200) } else { interval_str.parse::<u64>() };
+
+    let (Some(start), Ok(len), Ok(interval)) = (parse_address(&addr_str), len_str.parse::<usize>(), interval) else {
+        println!("❌ Invalid address, length or interval");
+        get_input("Press Enter to continue...");
+        return Ok(());
+    };
+
+    let mut watch = match process_mem.watch(start, len) {
+        Ok(watch) => watch,
+        Err(e) => {
+            println!("❌ Cannot watch range: {}", describe_error(&e));
+            get_input("Press Enter to continue...");
+            return Ok(());
+        }
+    };
+    watch_view(process_mem, &mut watch, Duration::from_millis(interval).max(MIN_INTERVAL))?;
+
+    println!(
+        "\n{} samples over {:.1}s, {} changes recorded",
+        watch.samples,
+        watch.elapsed().as_secs_f64(),
+        watch.changes.len()
+    );
+    let hot = watch.hot_offsets();
+    if hot.is_empty() {
+        println!("No bytes changed.");
+    } else {
+        println!("\n{:<18} {:<8} Changes", "Address", "Offset");
+        for (offset, count) in hot.iter().take(20) {
+            println!("{:016x}   +0x{:<5x} {}", start + *offset as u64, offset, count);
+        }
+    }
+
+    if !watch.changes.is_empty() {
+        let path = get_input("\nExport change history to CSV (path, or Enter to skip): ");
+        if !path.is_empty() {
+            match export_changes(&watch, &path) {
+                Ok(_) => println!("✅ Exported {} changes to {}", watch.changes.len(), path),
+                Err(e) => println!("❌ Error exporting changes: {}", e),
+            }
+        }
+    }
+    get_input("\nPress Enter to continue...");
+    Ok(())
+}
+
+fn export_changes(watch: &MemoryWatch, path: &str) -> Result<(), Box<dyn Error>> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "seconds,address,offset,old,new")?;
+    for change in &watch.changes {
+        writeln!(
+            out,
+            "{:.3},{:x},{:x},{},{}",
+            change.elapsed.as_secs_f64(),
+            watch.start + change.offset as u64,
+            change.offset,
+            hex::encode(&change.old),
+            hex::encode(&change.new)
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Muestrea el rango hasta que se pulse Esc: volcado hex con los bytes que cambian
+/// resaltados y la lista de los últimos cambios
+fn watch_view(process_mem: &mut ProcessMemory, watch: &mut MemoryWatch, mut interval: Duration) -> Result<(), Box<dyn Error>> {
+    let _screen = FullScreen::enter()?;
+    let mut out = stdout();
+    crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?;
+
+    let mut top_row = 0usize;
+    let mut status = String::new();
+    let mut next_sample = Instant::now() + interval;
+
+    loop {
+        if Instant::now() >= next_sample {
+            if let Err(e) = watch.sample(process_mem) {
+                status = format!("Read failed: {}", e);
+            }
+            next_sample = Instant::now() + interval;
+        }
+        let (width, height) = terminal::size()?;
+        let total_rows = watch.len().div_ceil(BYTES_PER_ROW);
+        let hex_rows = total_rows.min((height.saturating_sub(3) / 2).max(1) as usize);
+        top_row = top_row.min(total_rows.saturating_sub(hex_rows));
+
+        let title = format!(
+            " WATCH 0x{:x} +{} │ PID {} │ every {} ms │ {} samples │ {} changes",
+            watch.start,
+            watch.len(),
+            process_mem.pid,
+            interval.as_millis(),
+            watch.samples,
+            watch.changes.len()
+        );
+        print_at(&mut out, 0, 0, width, &title, Color::Cyan)?;
+        for row in 0..hex_rows {
+            draw_hex_row(&mut out, watch, top_row + row, row as u16 + 1, width)?;
+        }
+
+        let list_y = hex_rows as u16 + 1;
+        print_at(&mut out, 0, list_y, width, "── Recent changes (newest first) ──", Color::DarkCyan)?;
+        let list_rows = height.saturating_sub(list_y + 2) as usize;
+        let mut recent = watch.changes.iter().rev();
+        for row in 0..list_rows {
+            let text = recent
+                .next()
+                .map(|c| {
+                    format!(
+                        "{:>9.3}s  {:016x} +0x{:<5x} {} → {}",
+                        c.elapsed.as_secs_f64(),
+                        watch.start + c.offset as u64,
+                        c.offset,
+                        hex::encode(&c.old),
+                        hex::encode(&c.new)
+                    )
+                })
+                .unwrap_or_default();
+            print_at(&mut out, 0, list_y + 1 + row as u16, width, &text, Color::White)?;
+        }
+
+        let help = "↑↓/PgUp/PgDn: scroll  +/-: interval  c: clear history  Esc/q: stop";
+        print_at(&mut out, 0, height - 1, width, if status.is_empty() { help } else { &status }, Color::DarkGrey)?;
+        out.flush()?;
+
+        let timeout = next_sample.saturating_duration_since(Instant::now());
+        if !event::poll(timeout)? {
+            continue;
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                KeyCode::Up => top_row = top_row.saturating_sub(1),
+                KeyCode::Down => top_row += 1,
+                KeyCode::PageUp => top_row = top_row.saturating_sub(hex_rows),
+                KeyCode::PageDown => top_row += hex_rows,
+                KeyCode::Char('+') => interval = (interval * 2).min(Duration::from_secs(10)),
+                KeyCode::Char('-') => interval = (interval / 2).max(MIN_INTERVAL),
+                KeyCode::Char('c') => {
+                    watch.clear();
+                    status = "History cleared".to_string();
+                }
+                _ => {}
+            },
+            Event::Resize(_, _) => crossterm::execute!(out, terminal::Clear(terminal::ClearType::All))?,
+            _ => {}
+        }
+    }
+}
+
+/// Bytes cambiados hace poco en rojo; los que cambiaron alguna vez en amarillo
+fn draw_hex_row(out: &mut Stdout, watch: &MemoryWatch, row: usize, y: u16, width: u16) -> Result<(), Box<dyn Error>> {
+    let first = row * BYTES_PER_ROW;
+    let last = (first + BYTES_PER_ROW).min(watch.len());
+    print_at(out, 0, y, width, "", Color::White)?;
+    if first >= last {
+        return Ok(());
+    }
+    queue!(out, MoveTo(0, y), SetForegroundColor(Color::DarkGrey), Print(format!("{:016x} │ ", watch.start + first as u64)))?;
+
+    let elapsed = watch.elapsed();
+    let data = watch.current();
+    for (offset, byte) in data.iter().enumerate().take(last).skip(first) {
+        let recent = watch
+            .changes
+            .iter()
+            .rev()
+            .take_while(|c| elapsed.saturating_sub(c.elapsed) < HIGHLIGHT_TIME)
+            .any(|c| (c.offset..c.offset + c.new.len()).contains(&offset));
+        let color = if recent {
+            Color::Red
+        } else if watch.change_counts[offset] > 0 {
+            Color::Yellow
+        } else {
+            Color::White
+        };
+        queue!(out, SetForegroundColor(color), Print(format!("{:02x} ", byte)))?;
+    }
+    queue!(out, ResetColor)?;
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use crate::memory::error::describe_error;
+use crate::memory::process::ProcessMemory;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
+const CAP_NET_ADMIN: u32 = 12;
+const CAP_SYS_PTRACE: u32 = 19;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Info,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Status::Ok => "✅",
+            Status::Info => "ℹ️ ",
+            Status::Warn => "⚠️ ",
+            Status::Fail => "❌",
+        }
+    }
+}
+
+/// Resultado de una comprobación del diagnóstico de permisos
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+fn check(name: &'static str, status: Status, detail: impl Into<String>) -> Check {
+    Check {
+        name,
+        status,
+        detail: detail.into(),
+    }
+}
+
+/// Todas las comprobaciones para acceder a la memoria de `pid`, terminando con una
+/// lectura de prueba y una escritura inocua (se reescriben los mismos bytes). Con `session`
+/// (ya conectada a `pid`) las pruebas usan esa conexión y su pausa, si la hay.
+pub fn run_diagnostics(pid: u32, session: Option<&mut ProcessMemory>) -> Vec<Check> {
+    let euid = unsafe { libc::geteuid() };
+    let mut checks = vec![
+        if euid == 0 {
+            check("Running as", Status::Ok, "root")
+        } else {
+            check("Running as", Status::Warn, format!("UID {} (not root)", euid))
+        },
+        ptrace_scope_check(euid),
+        capability_check(euid),
+    ];
+
+    let target_uids = status_uids(pid);
+    let Some((real_uid, effective_uid)) = target_uids else {
+        checks.push(check("Target", Status::Fail, format!("PID {} not found in /proc", pid)));
+        return checks;
+    };
+    checks.push(if euid == 0 || (euid == real_uid && euid == effective_uid) {
+        check("Same UID", Status::Ok, format!("target runs as UID {}", real_uid))
+    } else {
+        check(
+            "Same UID",
+            Status::Fail,
+            format!("target runs as UID {}/{}, we are {}; only root can attach", real_uid, effective_uid, euid),
+        )
+    });
+    checks.push(dumpable_check(pid, effective_uid));
+    checks.extend(selinux_checks(pid));
+    checks.push(mem_override_check());
+    checks.extend(access_checks(pid, session));
+    checks
+}
+
+fn ptrace_scope_check(euid: u32) -> Check {
+    let scope = fs::read_to_string("/proc/sys/kernel/yama/ptrace_scope")
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok());
+    match scope {
+        None => check("ptrace_scope", Status::Ok, "Yama not enabled (no restriction)"),
+        Some(0) => check("ptrace_scope", Status::Ok, "0 (classic permissions)"),
+        Some(1) if euid == 0 => check("ptrace_scope", Status::Ok, "1 (restricted to descendants; root is exempt)"),
+        Some(1) => check(
+            "ptrace_scope",
+            Status::Fail,
+            "1: only descendants can be traced; run as root or sysctl kernel.yama.ptrace_scope=0",
+        ),
+        Some(2) if euid == 0 => check("ptrace_scope", Status::Ok, "2 (admin only; we are root)"),
+        Some(2) => check("ptrace_scope", Status::Fail, "2: only CAP_SYS_PTRACE can attach; run as root"),
+        Some(n) => check("ptrace_scope", Status::Fail, format!("{}: ptrace disabled until reboot", n)),
+    }
+}
+
+fn capability_check(euid: u32) -> Check {
+    let effective = fs::read_to_string("/proc/self/status")
+        .ok()
+        .and_then(|status| {
+            let hex = status.lines().find_map(|l| l.strip_prefix("CapEff:"))?.trim().to_string();
+            u64::from_str_radix(&hex, 16).ok()
+        })
+        .unwrap_or(0);
+    let has = |cap: u32| effective & (1 << cap) != 0;
+    let mut held = Vec::new();
+    if has(CAP_SYS_PTRACE) {
+        held.push("CAP_SYS_PTRACE");
+    }
+    if has(CAP_NET_ADMIN) {
+        held.push("CAP_NET_ADMIN (proc connector)");
+    }
+    let detail = if held.is_empty() { "none relevant".to_string() } else { held.join(", ") };
+    match (has(CAP_SYS_PTRACE), euid) {
+        (true, _) => check("Capabilities", Status::Ok, detail),
+        (false, 0) => check("Capabilities", Status::Warn, format!("{}: root without CAP_SYS_PTRACE (container?)", detail)),
+        (false, _) => check("Capabilities", Status::Info, format!("{}: only same-UID targets", detail)),
+    }
+}
+
+/// (UID real, UID efectivo) de /proc/<pid>/status
+fn status_uids(pid: u32) -> Option<(u32, u32)> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    let uids: Vec<u32> = status
+        .lines()
+        .find_map(|l| l.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .filter_map(|u| u.parse().ok())
+        .collect();
+    Some((*uids.first()?, *uids.get(1)?))
+}
+
+/// Un proceso no volcable (prctl PR_SET_DUMPABLE 0, setuid...) tiene sus ficheros de /proc
+/// a nombre de root: solo root puede leer su memoria
+fn dumpable_check(pid: u32, effective_uid: u32) -> Check {
+    match fs::metadata(format!("/proc/{}/mem", pid)) {
+        Ok(meta) if meta.uid() == effective_uid => check("Dumpable", Status::Ok, "yes"),
+        Ok(_) => check(
+            "Dumpable",
+            Status::Warn,
+            "no: /proc files are owned by root, only root can access its memory",
+        ),
+        Err(e) => check("Dumpable", Status::Fail, format!("cannot stat /proc/{}/mem: {}", pid, e)),
+    }
+}
+
+fn selinux_checks(pid: u32) -> Vec<Check> {
+    let Ok(enforce) = fs::read_to_string("/sys/fs/selinux/enforce") else {
+        return vec![check("SELinux", Status::Ok, "not present")];
+    };
+    let context = |path: String| {
+        fs::read_to_string(path)
+            .map(|c| c.trim_end_matches('\0').trim().to_string())
+            .unwrap_or_else(|_| "unknown".to_string())
+    };
+    let enforcing = enforce.trim() == "1";
+    vec![
+        check(
+            "SELinux",
+            if enforcing { Status::Warn } else { Status::Ok },
+            if enforcing { "enforcing: policy may deny access even to root" } else { "permissive" },
+        ),
+        check("Our context", Status::Info, context("/proc/self/attr/current".to_string())),
+        check("Target context", Status::Info, context(format!("/proc/{}/attr/current", pid))),
+    ]
+}
+
+/// Desde Linux 6.12 `proc_mem.force_override=never` impide escribir por /proc/<pid>/mem
+fn mem_override_check() -> Check {
+    let cmdline = fs::read_to_string("/proc/cmdline").unwrap_or_default();
+    match cmdline.split_whitespace().find_map(|arg| arg.strip_prefix("proc_mem.force_override=")) {
+        Some("never") => check("/proc/<pid>/mem writes", Status::Fail, "disabled by proc_mem.force_override=never"),
+        Some("ptrace") => check("/proc/<pid>/mem writes", Status::Info, "only while ptrace-attached (proc_mem.force_override=ptrace)"),
+        Some(other) => check("/proc/<pid>/mem writes", Status::Ok, format!("proc_mem.force_override={}", other)),
+        None => check("/proc/<pid>/mem writes", Status::Ok, "kernel default (allowed)"),
+    }
+}
+
+/// Conexión real, lectura de prueba y escritura de los mismos bytes en una región escribible
+/// (con el proceso en pausa, para no deshacer un cambio que haga entre la lectura y la escritura)
+fn access_checks(pid: u32, session: Option<&mut ProcessMemory>) -> Vec<Check> {
+    // Un segundo PTRACE_SEIZE fallaría si la sesión ya tiene el proceso en pausa
+    let mut own;
+    let (process, attach) = match session {
+        Some(process) => (process, "using this session's /proc/<pid>/mem"),
+        None => match ProcessMemory::new(pid) {
+            Ok(process) => {
+                own = process;
+                (&mut own, "opened /proc/<pid>/mem for read/write")
+            }
+            Err(e) => return vec![check("Attach", Status::Fail, describe_error(&e))],
+        },
+    };
+    let mut checks = vec![check("Attach", Status::Ok, attach)];
+
+    let readable = process.regions.iter().find(|r| r.permissions.contains('r')).cloned();
+    let writable = process
+        .regions
+        .iter()
+        .find(|r| r.permissions.starts_with("rw"))
+        .cloned();
+
+    match readable {
+        Some(region) => checks.push(match process.read_memory(region.start, 16) {
+            Ok(_) => check("Test read", Status::Ok, format!("16 bytes at 0x{:x} ({})", region.start, region.pathname)),
+            Err(e) => check("Test read", Status::Fail, describe_error(&e)),
+        }),
+        None => checks.push(check("Test read", Status::Warn, "no readable regions")),
+    }
+
+    match writable {
+        Some(region) => {
+            let was_paused = process.is_paused();
+            match process.pause() {
+                Ok(_) => {
+                    let result = process
+                        .read_memory(region.start, 8)
+                        .and_then(|bytes| process.write_batch(&[(region.start, bytes)]));
+                    if !was_paused {
+                        process.resume();
+                    }
+                    checks.push(match result {
+                        Ok(_) => check("Test write", Status::Ok, format!("rewrote 8 bytes at 0x{:x} unchanged", region.start)),
+                        Err(e) => check("Test write", Status::Fail, describe_error(&e)),
+                    });
+                }
+                Err(e) => checks.push(check(
+                    "Test write",
+                    Status::Warn,
+                    format!("skipped, the process could not be paused: {}", describe_error(&e)),
+                )),
+            }
+        }
+        None => checks.push(check("Test write", Status::Warn, "no writable regions")),
+    }
+    checks
+}
@@ -7,3 +7,36 @@ pub fn get_input(prompt: &str) -> String {
     io::stdin().read_line(&mut input).unwrap();
     input.trim().to_string()
 }
+
+/// Dirección en hexadecimal, con o sin prefijo 0x
+pub fn parse_address(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    u64::from_str_radix(s, 16).ok()
+}
+
+pub fn parse_position(s: &str, total: usize) -> Option<usize> {
+    match s.trim().parse::<usize>() {
+        Ok(n) if n >= 1 && n <= total => Some(n - 1),
+        _ => None,
+    }
+}
+
+/// Interpreta "1,3,5-9" como posiciones (base 0)
+pub fn parse_selection(s: &str, total: usize) -> Option<Vec<usize>> {
+    let mut positions = Vec::new();
+    for part in s.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let from = parse_position(from, total)?;
+                let to = parse_position(to, total)?;
+                if from > to {
+                    return None;
+                }
+                positions.extend(from..=to);
+            }
+            None => positions.push(parse_position(part, total)?),
+        }
+    }
+    Some(positions)
+}
@@ -1,3 +1,6 @@
-
+pub mod diagnostics;
+pub mod hex;
 pub mod input;
+pub mod netlink;
+pub mod procfs;
 pub mod system;
@@ -0,0 +1,92 @@
+use std::error::Error;
+use std::io;
+
+const PROC_CN_MCAST_LISTEN: u32 = 1;
+const PROC_EVENT_EXEC: u32 = 0x2;
+const PROC_EVENT_COMM: u32 = 0x200;
+const NLMSG_DONE: u16 = 3;
+const NLMSG_HEADER: usize = 16;
+const CN_MSG_HEADER: usize = 20; // cb_id (idx, val), seq, ack, len, flags
+const EVENT_DATA: usize = NLMSG_HEADER + CN_MSG_HEADER + 16; // what, cpu, timestamp_ns
+
+/// Conector de procesos del kernel (netlink): avisa de exec() y cambios de nombre
+/// sin recorrer /proc. Necesita CAP_NET_ADMIN, así que sin root no está disponible.
+pub struct ProcConnector {
+    fd: i32,
+}
+
+impl ProcConnector {
+    pub fn open() -> Result<ProcConnector, Box<dyn Error>> {
+        let fd = unsafe {
+            libc::socket(
+                libc::AF_NETLINK,
+                libc::SOCK_DGRAM | libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK,
+                libc::NETLINK_CONNECTOR,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        let connector = ProcConnector { fd };
+
+        let mut address: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        address.nl_family = libc::AF_NETLINK as u16;
+        address.nl_pid = std::process::id();
+        address.nl_groups = libc::CN_IDX_PROC;
+        let bound = unsafe {
+            libc::bind(
+                fd,
+                &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+                std::mem::size_of::<libc::sockaddr_nl>() as u32,
+            )
+        };
+        if bound < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let mut message = Vec::with_capacity(NLMSG_HEADER + CN_MSG_HEADER + 4);
+        message.extend_from_slice(&((NLMSG_HEADER + CN_MSG_HEADER + 4) as u32).to_ne_bytes());
+        message.extend_from_slice(&NLMSG_DONE.to_ne_bytes());
+        message.extend_from_slice(&0u16.to_ne_bytes()); // flags
+        message.extend_from_slice(&0u32.to_ne_bytes()); // seq
+        message.extend_from_slice(&std::process::id().to_ne_bytes());
+        message.extend_from_slice(&libc::CN_IDX_PROC.to_ne_bytes());
+        message.extend_from_slice(&libc::CN_VAL_PROC.to_ne_bytes());
+        message.extend_from_slice(&[0u8; 8]); // seq, ack
+        message.extend_from_slice(&4u16.to_ne_bytes());
+        message.extend_from_slice(&0u16.to_ne_bytes());
+        message.extend_from_slice(&PROC_CN_MCAST_LISTEN.to_ne_bytes());
+        let sent = unsafe { libc::send(fd, message.as_ptr() as *const libc::c_void, message.len(), 0) };
+        if sent < 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(connector)
+    }
+
+    /// PIDs que hicieron exec() o cambiaron de nombre desde la última llamada (no bloquea)
+    pub fn drain(&mut self) -> Vec<u32> {
+        let mut pids = Vec::new();
+        let mut buffer = [0u8; 4096];
+        loop {
+            let len = unsafe { libc::recv(self.fd, buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+            if len < EVENT_DATA as isize + 8 {
+                break;
+            }
+            let field = |at: usize| u32::from_ne_bytes(buffer[at..at + 4].try_into().unwrap());
+            let what = field(NLMSG_HEADER + CN_MSG_HEADER);
+            if what == PROC_EVENT_EXEC || what == PROC_EVENT_COMM {
+                let tgid = field(EVENT_DATA + 4);
+                if !pids.contains(&tgid) {
+                    pids.push(tgid);
+                }
+            }
+        }
+        pids
+    }
+}
+
+impl Drop for ProcConnector {
+    fn drop(&mut self) {
+        unsafe { libc::close(self.fd) };
+    }
+}
@@ -0,0 +1,556 @@
+use super::netlink::ProcConnector;
+use crate::memory::error::MemoryError;
+use crate::memory::region::MemoryRegion;
+use regex::Regex;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::time::{Duration, Instant};
+
+const FULL_SCAN_INTERVAL: Duration = Duration::from_secs(2); // Con el conector, /proc se recorre de vez en cuando
+
+const PER_USER_RANGE: u32 = 100_000; // UID Android = user_id * 100000 + app_id
+const FIRST_APPLICATION_UID: u32 = 10_000;
+const LAST_APPLICATION_UID: u32 = 19_999;
+const FIRST_ISOLATED_UID: u32 = 90_000;
+const LAST_ISOLATED_UID: u32 = 99_999;
+const ZYGOTES: [&str; 6] = ["zygote", "zygote64", "usap32", "usap64", "webview_zygote", "app_zygote"];
+
+/// Identidad de una app Android sacada del UID y de argv[0] ("com.example.game:remote")
+#[derive(Debug, Clone)]
+pub struct AndroidApp {
+    pub package: String,
+    pub process: Option<String>, // Sufijo tras ':' de los procesos secundarios
+    pub user_id: u32,
+    pub app_id: u32,
+    pub isolated: bool,
+}
+
+impl AndroidApp {
+    /// `None` si el UID no es de una app o argv[0] no parece un nombre de paquete
+    pub fn from_process(uid: u32, argv0: &str) -> Option<AndroidApp> {
+        let (user_id, app_id) = (uid / PER_USER_RANGE, uid % PER_USER_RANGE);
+        let isolated = (FIRST_ISOLATED_UID..=LAST_ISOLATED_UID).contains(&app_id);
+        if !isolated && !(FIRST_APPLICATION_UID..=LAST_APPLICATION_UID).contains(&app_id) {
+            return None;
+        }
+        let (package, process) = split_package(argv0)?;
+        Some(AndroidApp {
+            package,
+            process,
+            user_id,
+            app_id,
+            isolated,
+        })
+    }
+
+    /// "com.example.game" o "com.example.game:remote"
+    pub fn process_name(&self) -> String {
+        match &self.process {
+            Some(process) => format!("{}:{}", self.package, process),
+            None => self.package.clone(),
+        }
+    }
+
+    /// ¿Coincide con "com.example.game" (todos sus procesos) o "com.example.game:remote" (solo ese)?
+    pub fn matches_package(&self, query: &str) -> bool {
+        match query.split_once(':') {
+            Some((package, process)) => self.package == package && self.process.as_deref() == Some(process),
+            None => self.package == query,
+        }
+    }
+}
+
+/// "com.example.game:remote" -> ("com.example.game", Some("remote")); exige al menos un punto
+pub fn split_package(argv0: &str) -> Option<(String, Option<String>)> {
+    let (package, process) = match argv0.split_once(':') {
+        Some((package, process)) => (package, Some(process.to_string())),
+        None => (argv0, None),
+    };
+    let valid = package.contains('.')
+        && package
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'));
+    valid.then(|| (package.to_string(), process))
+}
+
+/// Nombre del usuario: /etc/passwd o, en Android, el estilo "u0_a123"
+pub fn user_name(uid: u32, users: &HashMap<u32, String>) -> String {
+    if let Some(name) = users.get(&uid) {
+        return name.clone();
+    }
+    let (user_id, app_id) = (uid / PER_USER_RANGE, uid % PER_USER_RANGE);
+    match app_id {
+        0 if user_id == 0 => "root".to_string(),
+        1000 => "system".to_string(),
+        2000 => "shell".to_string(),
+        FIRST_APPLICATION_UID..=LAST_APPLICATION_UID => format!("u{}_a{}", user_id, app_id - FIRST_APPLICATION_UID),
+        FIRST_ISOLATED_UID..=LAST_ISOLATED_UID => format!("u{}_i{}", user_id, app_id - FIRST_ISOLATED_UID),
+        _ => uid.to_string(),
+    }
+}
+
+/// ¿Es argv[0] de un zygote (o de un proceso USAP) de Android?
+pub fn is_zygote(argv0: &str) -> bool {
+    ZYGOTES.contains(&argv0)
+}
+
+/// Datos de un proceso leídos de /proc/<pid>
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    pub comm: String,
+    pub cmdline: Vec<String>, // argv; vacío en hilos del kernel
+    pub uid: u32,
+    pub user: String,
+    pub android: Option<AndroidApp>,
+    pub zygote_child: bool, // Se rellena en `list_processes` mirando al padre
+    pub rss_kb: u64,
+    pub start_ticks: u64, // Inicio en ticks de reloj desde el arranque
+}
+
+impl ProcessInfo {
+    pub fn read(pid: u32, users: &HashMap<u32, String>) -> Result<ProcessInfo, Box<dyn Error>> {
+        let comm = fs::read_to_string(format!("/proc/{}/comm", pid))?;
+        let cmdline = fs::read(format!("/proc/{}/cmdline", pid))?;
+        let status = fs::read_to_string(format!("/proc/{}/status", pid))?;
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).unwrap_or_default();
+        Ok(ProcessInfo::parse(pid, &comm, &cmdline, &status, &stat, users))
+    }
+
+    /// A partir del contenido de comm, cmdline (argumentos separados por \0), status y stat
+    pub fn parse(pid: u32, comm: &str, cmdline: &[u8], status: &str, stat: &str, users: &HashMap<u32, String>) -> ProcessInfo {
+        let cmdline: Vec<String> = cmdline
+            .split(|&b| b == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).into_owned())
+            .collect();
+        let status_field = |name: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(name))
+                .and_then(|rest| rest.split_whitespace().next())
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        let uid = status_field("Uid:").unwrap_or(0) as u32;
+        let stat = parse_stat(stat);
+
+        ProcessInfo {
+            pid,
+            ppid: stat_field(&stat, 1) as u32,
+            android: cmdline.first().and_then(|argv0| AndroidApp::from_process(uid, argv0)),
+            comm: comm.trim_end().to_string(),
+            cmdline,
+            uid,
+            user: user_name(uid, users),
+            zygote_child: false,
+            rss_kb: status_field("VmRSS:").unwrap_or(0),
+            start_ticks: stat_field(&stat, 19),
+        }
+    }
+
+    pub fn command(&self) -> String {
+        if self.cmdline.is_empty() {
+            format!("[{}]", self.comm)
+        } else {
+            self.cmdline.join(" ")
+        }
+    }
+
+    pub fn is_kernel_thread(&self) -> bool {
+        self.cmdline.is_empty()
+    }
+
+    /// Segundos desde que arrancó el proceso
+    pub fn elapsed_secs(&self) -> u64 {
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+        uptime_secs().saturating_sub(self.start_ticks / ticks)
+    }
+}
+
+/// Cómo se compara la búsqueda con el nombre y la línea de comandos
+pub enum ProcessQuery {
+    Substring(String),
+    Regex(Regex),
+    Fuzzy(String),
+    Package(String),
+}
+
+impl ProcessQuery {
+    /// "/regex/", "~fuzzy", "@paquete[:proceso]" o texto normal (subcadena sin distinguir mayúsculas)
+    pub fn parse(input: &str) -> Result<ProcessQuery, Box<dyn Error>> {
+        let input = input.trim();
+        if let Some(package) = input.strip_prefix('@') {
+            return Ok(ProcessQuery::Package(package.to_string()));
+        }
+        if let Some(pattern) = input.strip_prefix('/').and_then(|p| p.strip_suffix('/')) {
+            return Ok(ProcessQuery::Regex(Regex::new(pattern)?));
+        }
+        if let Some(text) = input.strip_prefix('~') {
+            return Ok(ProcessQuery::Fuzzy(text.to_lowercase()));
+        }
+        Ok(ProcessQuery::Substring(input.to_lowercase()))
+    }
+
+    /// Puntuación de la coincidencia (mayor es mejor); `None` si no coincide
+    pub fn score(&self, process: &ProcessInfo) -> Option<u32> {
+        let command = process.command();
+        match self {
+            ProcessQuery::Substring(text) => {
+                let comm = process.comm.to_lowercase();
+                if comm == *text {
+                    Some(3)
+                } else if comm.contains(text.as_str()) {
+                    Some(2)
+                } else if command.to_lowercase().contains(text.as_str()) {
+                    Some(1)
+                } else {
+                    None
+                }
+            }
+            ProcessQuery::Regex(regex) => {
+                (regex.is_match(&process.comm) || regex.is_match(&command)).then_some(1)
+            }
+            ProcessQuery::Fuzzy(text) => {
+                let by_comm = fuzzy_score(text, &process.comm.to_lowercase()).map(|s| s * 2);
+                by_comm.max(fuzzy_score(text, &command.to_lowercase()))
+            }
+            ProcessQuery::Package(package) => {
+                let app = process.android.as_ref()?;
+                // El proceso principal antes que los secundarios
+                app.matches_package(package).then_some(if app.process.is_none() { 2 } else { 1 })
+            }
+        }
+    }
+}
+
+/// Subsecuencia: todas las letras de `needle` en orden dentro de `haystack`.
+/// Puntúa más las letras consecutivas y las que empiezan palabra.
+fn fuzzy_score(needle: &str, haystack: &str) -> Option<u32> {
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    let mut chars = haystack.char_indices();
+    for wanted in needle.chars() {
+        let (index, _) = chars.by_ref().find(|(_, c)| *c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| index == p + 1) {
+            score += 4;
+        }
+        let starts_word = index == 0 || !haystack[..index].ends_with(|c: char| c.is_alphanumeric());
+        if starts_word {
+            score += 2;
+        }
+        previous = Some(index + wanted.len_utf8() - 1);
+    }
+    Some(score)
+}
+
+/// Todos los procesos visibles en /proc, ordenados por PID
+pub fn list_processes() -> Vec<ProcessInfo> {
+    let users = read_users();
+    let mut processes: Vec<ProcessInfo> = fs::read_dir("/proc")
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok()?.file_name().to_str()?.parse::<u32>().ok())
+                .filter_map(|pid| ProcessInfo::read(pid, &users).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    processes.sort_by_key(|p| p.pid);
+    mark_zygote_children(&mut processes);
+    processes
+}
+
+/// Marca `zygote_child` en los procesos cuyo padre es un zygote
+pub fn mark_zygote_children(processes: &mut [ProcessInfo]) {
+    let zygotes: Vec<u32> = processes
+        .iter()
+        .filter(|p| p.cmdline.first().is_some_and(|argv0| is_zygote(argv0)))
+        .map(|p| p.pid)
+        .collect();
+    for process in processes {
+        process.zygote_child = zygotes.contains(&process.ppid);
+    }
+}
+
+/// Procesos que coinciden con la búsqueda, los mejores primero (excluye hilos del kernel y a nosotros)
+pub fn find_processes(query: &ProcessQuery) -> Vec<ProcessInfo> {
+    let own_pid = std::process::id();
+    let mut scored: Vec<(u32, ProcessInfo)> = list_processes()
+        .into_iter()
+        .filter(|p| !p.is_kernel_thread() && p.pid != own_pid)
+        .filter_map(|p| Some((query.score(&p)?, p)))
+        .collect();
+    scored.sort_by_key(|(score, p)| (std::cmp::Reverse(*score), p.pid));
+    scored.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Espera a que arranque un proceso que coincida con la búsqueda. Usa el conector de
+/// procesos si está disponible y, si no, recorre /proc en cada comprobación.
+pub struct ProcessWaiter {
+    query: ProcessQuery,
+    connector: Option<ProcConnector>,
+    last_scan: Option<Instant>,
+}
+
+impl ProcessWaiter {
+    pub fn new(query: ProcessQuery) -> ProcessWaiter {
+        ProcessWaiter {
+            query,
+            connector: ProcConnector::open().ok(),
+            last_scan: None,
+        }
+    }
+
+    pub fn uses_connector(&self) -> bool {
+        self.connector.is_some()
+    }
+
+    /// Una comprobación sin bloquear; el llamador decide cada cuánto repetirla
+    pub fn check(&mut self) -> Option<ProcessInfo> {
+        let full_scan = self.connector.is_none() || self.last_scan.is_none_or(|t| t.elapsed() >= FULL_SCAN_INTERVAL);
+        if full_scan {
+            // Las apps Android no hacen exec (zygote solo se renombra), de ahí el recorrido periódico
+            self.last_scan = Some(Instant::now());
+            if let Some(connector) = &mut self.connector {
+                connector.drain();
+            }
+            return find_processes(&self.query).into_iter().next();
+        }
+
+        let pids = self.connector.as_mut().map(|c| c.drain()).unwrap_or_default();
+        let users = read_users();
+        pids.into_iter()
+            .filter(|&pid| pid != std::process::id())
+            .filter_map(|pid| ProcessInfo::read(pid, &users).ok())
+            .filter(|p| !p.is_kernel_thread())
+            .filter_map(|p| Some((self.query.score(&p)?, p)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, p)| p)
+    }
+}
+
+/// Región mapeada de un módulo cuyo nombre de fichero contiene `module`; error si el proceso terminó
+pub fn find_module(pid: u32, module: &str) -> Result<Option<MemoryRegion>, MemoryError> {
+    let regions = MemoryRegion::parse_maps(pid)?;
+    Ok(regions
+        .into_iter()
+        .find(|r| r.is_module() && r.pathname.rsplit('/').next().is_some_and(|name| name.contains(module))))
+}
+
+/// Nombres de usuario de /etc/passwd (no existe en Android)
+fn read_users() -> HashMap<u32, String> {
+    fs::read_to_string("/etc/passwd")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(':').collect();
+            Some((fields.get(2)?.parse().ok()?, fields.first()?.to_string()))
+        })
+        .collect()
+}
+
+fn uptime_secs() -> u64 {
+    fs::read_to_string("/proc/uptime")
+        .ok()
+        .and_then(|s| s.split('.').next()?.parse().ok())
+        .unwrap_or(0)
+}
+
+/// "1d 02:03:04" / "02:03:04" / "03:04", como el ETIME de ps
+pub fn format_elapsed(secs: u64) -> String {
+    let (days, hours, minutes, seconds) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Hilos del proceso (TIDs de /proc/<pid>/task), el principal primero
+pub fn list_threads(pid: u32) -> Vec<i32> {
+    let mut tids: Vec<i32> = fs::read_dir(format!("/proc/{}/task", pid))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok()?.file_name().to_str()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    tids.sort_by_key(|&tid| (tid as u32 != pid, tid));
+    tids
+}
+
+/// Un hilo de /proc/<pid>/task con su nombre (comm) y estado (R, S, D, t...)
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub tid: i32,
+    pub name: String,
+    pub state: char,
+}
+
+pub fn list_thread_info(pid: u32) -> Vec<ThreadInfo> {
+    list_threads(pid)
+        .into_iter()
+        .filter_map(|tid| {
+            let stat = read_stat(&format!("/proc/{}/task/{}/stat", pid, tid));
+            let name = fs::read_to_string(format!("/proc/{}/task/{}/comm", pid, tid)).ok()?;
+            Some(ThreadInfo {
+                tid,
+                name: name.trim_end().to_string(),
+                state: stat.first()?.chars().next()?,
+            })
+        })
+        .collect()
+}
+
+/// Nombre legible del estado de /proc/<pid>/stat
+pub fn state_name(state: char) -> &'static str {
+    match state {
+        'R' => "running",
+        'S' => "sleeping",
+        'D' => "disk sleep",
+        'T' => "stopped",
+        't' => "traced",
+        'Z' => "zombie",
+        'X' => "dead",
+        'I' => "idle",
+        _ => "unknown",
+    }
+}
+
+/// Campos de /proc/<pid>/stat que siguen al nombre del proceso
+pub fn read_stat(path: &str) -> Vec<String> {
+    parse_stat(&fs::read_to_string(path).unwrap_or_default())
+}
+
+/// El nombre va entre paréntesis y puede tener espacios: se parte tras el último ')'
+fn parse_stat(stat: &str) -> Vec<String> {
+    match stat.rfind(')') {
+        Some(end) => stat[end + 1..].split_whitespace().map(str::to_string).collect(),
+        None => Vec::new(),
+    }
+}
+
+pub fn stat_field(fields: &[String], index: usize) -> u64 {
+    fields.get(index).and_then(|f| f.parse::<i64>().ok()).unwrap_or(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Proceso de prueba con el cmdline (argumentos separados por \0) y el UID dados
+    fn process(pid: u32, ppid: u32, comm: &str, cmdline: &str, uid: u32) -> ProcessInfo {
+        let status = format!("Name:\t{}\nUid:\t{}\t{}\t{}\t{}\nVmRSS:\t  1234 kB\n", comm, uid, uid, uid, uid);
+        let stat = format!("{} ({}) S {} {} 0 0 -1 4194560 0 0 0 0 0 0 0 0 20 0 1 0 5000 0 0", pid, comm, ppid, pid);
+        ProcessInfo::parse(pid, &format!("{}\n", comm), cmdline.as_bytes(), &status, &stat, &HashMap::new())
+    }
+
+    fn fixtures() -> Vec<ProcessInfo> {
+        let mut processes = vec![
+            process(1, 0, "init", "/sbin/init\0", 0),
+            process(600, 1, "main", "zygote64\0-Xzygote\0", 0),
+            process(1200, 600, "example.game", "com.example.game\0", 10_123),
+            process(1201, 600, "le.game:remote", "com.example.game:remote\0", 10_123),
+            process(1300, 600, "ndroid.chrome", "com.android.chrome\0", 1_010_045),
+            process(1400, 1, "bash", "/bin/bash\0--login\0", 1000),
+            process(1500, 1, "game_server", "./game_server\0--port\08080\0", 1000),
+        ];
+        mark_zygote_children(&mut processes);
+        processes
+    }
+
+    fn matching(query: &str) -> Vec<u32> {
+        let query = ProcessQuery::parse(query).unwrap();
+        let mut scored: Vec<(u32, u32)> = fixtures().iter().filter_map(|p| Some((query.score(p)?, p.pid))).collect();
+        scored.sort_by_key(|&(score, pid)| (std::cmp::Reverse(score), pid));
+        scored.into_iter().map(|(_, pid)| pid).collect()
+    }
+
+    #[test]
+    fn split_package_with_process_suffix() {
+        assert_eq!(split_package("com.example.game"), Some(("com.example.game".to_string(), None)));
+        assert_eq!(
+            split_package("com.example.game:remote"),
+            Some(("com.example.game".to_string(), Some("remote".to_string())))
+        );
+        assert_eq!(split_package("com.google.android.gms:persistent").map(|(_, p)| p), Some(Some("persistent".to_string())));
+        assert_eq!(split_package("zygote64"), None);
+        assert_eq!(split_package("/system/bin/app_process64"), None);
+        assert_eq!(split_package("com..example"), None);
+    }
+
+    #[test]
+    fn uid_to_user_and_app_id() {
+        let app = AndroidApp::from_process(10_123, "com.example.game").unwrap();
+        assert_eq!((app.user_id, app.app_id, app.isolated), (0, 10_123, false));
+        let app = AndroidApp::from_process(1_010_045, "com.android.chrome:sandboxed_process0").unwrap();
+        assert_eq!((app.user_id, app.app_id), (10, 10_045));
+        assert_eq!(app.process_name(), "com.android.chrome:sandboxed_process0");
+        let app = AndroidApp::from_process(99_000, "com.android.chrome:isolated").unwrap();
+        assert!(app.isolated);
+        assert!(AndroidApp::from_process(1000, "com.android.settings").is_none());
+        assert!(AndroidApp::from_process(10_123, "not_a_package").is_none());
+
+        let users = HashMap::from([(1000, "alfa".to_string())]);
+        assert_eq!(user_name(1000, &users), "alfa");
+        assert_eq!(user_name(1000, &HashMap::new()), "system");
+        assert_eq!(user_name(0, &HashMap::new()), "root");
+        assert_eq!(user_name(10_123, &HashMap::new()), "u0_a123");
+        assert_eq!(user_name(1_010_045, &HashMap::new()), "u10_a45");
+        assert_eq!(user_name(99_002, &HashMap::new()), "u0_i9002");
+    }
+
+    #[test]
+    fn parse_process_fixture() {
+        let process = process(1500, 1, "game server", "./game_server\0--port\08080\0", 1000);
+        assert_eq!(process.comm, "game server");
+        assert_eq!(process.cmdline, ["./game_server", "--port", "8080"]);
+        assert_eq!(process.command(), "./game_server --port 8080");
+        assert_eq!((process.ppid, process.uid, process.rss_kb, process.start_ticks), (1, 1000, 1234, 5000));
+        assert!(process.android.is_none());
+    }
+
+    #[test]
+    fn zygote_children() {
+        let processes = fixtures();
+        let children: Vec<u32> = processes.iter().filter(|p| p.zygote_child).map(|p| p.pid).collect();
+        assert_eq!(children, [1200, 1201, 1300]);
+        assert!(is_zygote("zygote64") && is_zygote("usap64") && !is_zygote("zygoteX"));
+    }
+
+    #[test]
+    fn substring_query() {
+        // Nombre exacto, luego parte del nombre, luego solo en la línea de comandos
+        assert_eq!(matching("bash"), [1400]);
+        assert_eq!(matching("GAME"), [1200, 1201, 1500]);
+        assert_eq!(matching("8080"), [1500]);
+    }
+
+    #[test]
+    fn regex_query() {
+        assert_eq!(matching("/^com\\.example\\./"), [1200, 1201]);
+        assert_eq!(matching("/--port\\s+\\d+/"), [1500]);
+        assert!(ProcessQuery::parse("/([/").is_err());
+    }
+
+    #[test]
+    fn fuzzy_query() {
+        assert_eq!(matching("~gmsrv").first(), Some(&1500));
+        assert!(matching("~zzz").is_empty());
+        // Letras consecutivas puntúan más que sueltas
+        assert!(fuzzy_score("game", "game_server") > fuzzy_score("game", "g_a_m_e"));
+    }
+
+    #[test]
+    fn package_query() {
+        // El proceso principal antes que los secundarios
+        assert_eq!(matching("@com.example.game"), [1200, 1201]);
+        assert_eq!(matching("@com.example.game:remote"), [1201]);
+        assert_eq!(matching("@com.android.chrome"), [1300]);
+        assert!(matching("@com.example").is_empty());
+    }
+}